@@ -0,0 +1,45 @@
+#!/bin/bash
+# Record the STT health-check clip from src-tauri/resources/stt-probe.txt.
+#
+# Usage:
+#   ./scripts/make-stt-probe.sh
+#
+# Uses the macOS `say` voice, or espeak-ng elsewhere, and converts the result
+# to 16 kHz mono 16-bit PCM with ffmpeg. Listen to the clip before committing it.
+
+set -euo pipefail
+
+RESOURCES="$(pwd)/src-tauri/resources"
+PHRASE_FILE="$RESOURCES/stt-probe.txt"
+OUT="$RESOURCES/stt-probe.wav"
+
+if [ ! -f "$PHRASE_FILE" ]; then
+    echo "Missing $PHRASE_FILE (run from the repository root)" >&2
+    exit 1
+fi
+if ! command -v ffmpeg >/dev/null; then
+    echo "ffmpeg is required" >&2
+    exit 1
+fi
+
+PHRASE="$(head -n 1 "$PHRASE_FILE")"
+TMP="$(mktemp -d)"
+trap 'rm -rf "$TMP"' EXIT
+
+if command -v say >/dev/null; then
+    say -o "$TMP/probe.aiff" "$PHRASE"
+    RAW="$TMP/probe.aiff"
+elif command -v espeak-ng >/dev/null; then
+    espeak-ng -v en-us -s 150 -w "$TMP/probe.wav" "$PHRASE"
+    RAW="$TMP/probe.wav"
+else
+    echo "Need macOS 'say' or espeak-ng to synthesize the clip" >&2
+    exit 1
+fi
+
+# Trim silence at both ends, keeping at most ~150 ms.
+ffmpeg -loglevel error -y -i "$RAW" \
+    -af "silenceremove=start_periods=1:start_silence=0.15:start_threshold=-50dB,areverse,silenceremove=start_periods=1:start_silence=0.15:start_threshold=-50dB,areverse" \
+    -ar 16000 -ac 1 -c:a pcm_s16le "$OUT"
+
+echo "Wrote $OUT"
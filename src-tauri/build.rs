@@ -11,6 +11,19 @@ fn main() {
     let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
     println!("cargo:rustc-link-search=native={manifest_dir}/lib");
 
+    // The STT health check embeds its probe clip; without it the check
+    // could not tell whether a model accepts audio.
+    println!("cargo:rerun-if-changed=resources");
+    let resources = std::path::Path::new(&manifest_dir).join("resources");
+    for file in ["stt-probe.wav", "stt-probe.txt"] {
+        if !resources.join(file).is_file() {
+            panic!(
+                "Missing src-tauri/resources/{file}: run ./scripts/make-stt-probe.sh \
+                 from the repository root and commit the result"
+            );
+        }
+    }
+
     // Add rpath so the bundled dylib can be found at runtime
     #[cfg(target_os = "macos")]
     println!("cargo:rustc-link-arg=-Wl,-rpath,@executable_path/../Frameworks");
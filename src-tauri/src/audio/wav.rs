@@ -1,3 +1,5 @@
+use crate::error::AppError;
+
 /// Encode raw PCM i16 samples into a WAV file (mono, 16-bit, little-endian).
 pub fn encode_wav(samples: &[i16], sample_rate: u32) -> Vec<u8> {
     let num_channels: u16 = 1;
@@ -34,6 +36,56 @@ pub fn encode_wav(samples: &[i16], sample_rate: u32) -> Vec<u8> {
     buf
 }
 
+/// Read 16-bit PCM samples from a mono WAV file, or from raw i16 LE bytes.
+///
+/// Only the layout written by [`encode_wav`] is accepted for WAV input;
+/// bytes without a RIFF header are taken as raw samples.
+pub fn decode_pcm16(data: &[u8]) -> Result<Vec<i16>, AppError> {
+    if !data.starts_with(b"RIFF") {
+        return Ok(to_samples(data));
+    }
+    if data.len() < 12 || &data[8..12] != b"WAVE" {
+        return Err(AppError::Audio("Invalid WAV header".into()));
+    }
+
+    let mut pos = 12;
+    let mut format_ok = false;
+    while pos + 8 <= data.len() {
+        let id = &data[pos..pos + 4];
+        let size = u32::from_le_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]])
+            as usize;
+        let body = &data[pos + 8..(pos + 8 + size).min(data.len())];
+        match id {
+            b"fmt " if body.len() >= 16 => {
+                let format = u16::from_le_bytes([body[0], body[1]]);
+                let channels = u16::from_le_bytes([body[2], body[3]]);
+                let bits = u16::from_le_bytes([body[14], body[15]]);
+                format_ok = format == 1 && channels == 1 && bits == 16;
+            }
+            b"data" => {
+                if !format_ok {
+                    return Err(AppError::Audio(
+                        "Only mono 16-bit PCM WAV is supported".into(),
+                    ));
+                }
+                return Ok(to_samples(body));
+            }
+            _ => {}
+        }
+        // Chunks are word-aligned.
+        pos += 8 + size + (size & 1);
+    }
+
+    Err(AppError::Audio("WAV file has no data chunk".into()))
+}
+
+fn to_samples(bytes: &[u8]) -> Vec<i16> {
+    bytes
+        .chunks_exact(2)
+        .map(|chunk| i16::from_le_bytes([chunk[0], chunk[1]]))
+        .collect()
+}
+
 /// Calculate RMS level from a chunk of i16 samples, normalized to 0.0–1.0.
 pub fn calculate_rms(samples: &[i16]) -> f32 {
     if samples.is_empty() {
@@ -43,3 +95,17 @@ pub fn calculate_rms(samples: &[i16]) -> f32 {
     let rms = (sum_sq / samples.len() as f64).sqrt();
     (rms / i16::MAX as f64) as f32
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_pcm16_round_trip() {
+        let samples = vec![0i16, 1, -1, i16::MAX, i16::MIN];
+        assert_eq!(decode_pcm16(&encode_wav(&samples, 16000)).unwrap(), samples);
+
+        let raw: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        assert_eq!(decode_pcm16(&raw).unwrap(), samples);
+    }
+}
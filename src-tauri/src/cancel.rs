@@ -0,0 +1,248 @@
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+
+use crate::error::AppError;
+
+/// Pipeline stage, for deadlines and error messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Transcription,
+    Enhancement,
+    Insertion,
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Transcription => "transcription",
+            Self::Enhancement => "enhancement",
+            Self::Insertion => "insertion",
+        })
+    }
+}
+
+impl Stage {
+    fn timeout_error(self, limit: Duration) -> AppError {
+        let message = format!("{self} timed out after {}s", limit.as_secs());
+        match self {
+            Self::Transcription => AppError::Stt(message),
+            Self::Enhancement => AppError::Enhancement(message),
+            Self::Insertion => AppError::TextInsertion(message),
+        }
+    }
+}
+
+/// Upper bound on each stage of a dictation, on top of per-request timeouts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StageDeadlines {
+    /// All STT work for one recording: retries, fallbacks, racing, escalation.
+    pub transcription_secs: u64,
+    pub enhancement_secs: u64,
+    pub insertion_secs: u64,
+}
+
+impl Default for StageDeadlines {
+    fn default() -> Self {
+        Self {
+            transcription_secs: 240,
+            enhancement_secs: 30,
+            insertion_secs: 10,
+        }
+    }
+}
+
+impl StageDeadlines {
+    pub fn normalized(self) -> Self {
+        Self {
+            transcription_secs: self.transcription_secs.clamp(10, 600),
+            enhancement_secs: self.enhancement_secs.clamp(5, 180),
+            insertion_secs: self.insertion_secs.clamp(2, 60),
+        }
+    }
+
+    pub fn limit(&self, stage: Stage) -> Duration {
+        Duration::from_secs(match stage {
+            Stage::Transcription => self.transcription_secs,
+            Stage::Enhancement => self.enhancement_secs,
+            Stage::Insertion => self.insertion_secs,
+        })
+    }
+}
+
+/// Shared cancellation flag for one dictation; clones observe the same state.
+#[derive(Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::Release);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Resolves once the token is cancelled.
+    pub async fn cancelled(&self) {
+        loop {
+            // Register before checking so a cancel in between is not missed.
+            let notified = self.inner.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Run `work` until it finishes, the token is cancelled, or the stage's
+    /// deadline passes. Dropping `work` aborts its HTTP requests and stops any
+    /// local Vosk, Whisper or sherpa-onnx pass it started.
+    pub async fn run<T, F>(&self, stage: Stage, deadlines: &StageDeadlines, work: F) -> Result<T, AppError>
+    where
+        F: Future<Output = Result<T, AppError>>,
+    {
+        let limit = deadlines.limit(stage);
+        tokio::select! {
+            biased;
+            _ = self.cancelled() => Err(AppError::Cancelled(stage.to_string())),
+            result = tokio::time::timeout(limit, work) => {
+                result.unwrap_or_else(|_| Err(stage.timeout_error(limit)))
+            }
+        }
+    }
+}
+
+/// The dictation being processed, so `cancel_processing` can reach every stage.
+#[derive(Default)]
+pub struct Processing {
+    current: Mutex<Option<CancelToken>>,
+}
+
+impl Processing {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fresh token for a new dictation, replacing the previous one.
+    pub fn begin(&self) -> CancelToken {
+        let token = CancelToken::default();
+        *self.current.lock().unwrap() = Some(token.clone());
+        token
+    }
+
+    /// Token of the current dictation, even once cancelled, so a stage that
+    /// starts after `cancel_processing` stops at once. Outside a dictation,
+    /// a fresh token that nothing can cancel.
+    pub fn current(&self) -> CancelToken {
+        self.current.lock().unwrap().clone().unwrap_or_default()
+    }
+
+    /// Cancel the current dictation; `false` when none is running or it
+    /// was already cancelled. The token stays until the next `begin`.
+    pub fn cancel(&self) -> bool {
+        match self.current.lock().unwrap().as_ref() {
+            Some(token) if !token.is_cancelled() => {
+                token.cancel();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The dictation is over: its text was inserted or handed to the preview,
+    /// or it ended in an error.
+    pub fn finish(&self) {
+        self.current.lock().unwrap().take();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deadlines(secs: u64) -> StageDeadlines {
+        StageDeadlines {
+            transcription_secs: secs,
+            enhancement_secs: secs,
+            insertion_secs: secs,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_stops_on_cancel_and_deadline() {
+        let token = CancelToken::default();
+        let cancel = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            cancel.cancel();
+        });
+        let slow = async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(())
+        };
+        let result = token.run(Stage::Enhancement, &deadlines(60), slow).await;
+        assert!(matches!(result, Err(AppError::Cancelled(stage)) if stage == "enhancement"));
+
+        let token = CancelToken::default();
+        let never = std::future::pending::<Result<(), AppError>>();
+        let result = token.run(Stage::Insertion, &deadlines(0), never).await;
+        assert!(matches!(result, Err(AppError::TextInsertion(_))));
+
+        let done = token.run(Stage::Transcription, &deadlines(1), async { Ok(7) }).await;
+        assert_eq!(done.unwrap(), 7);
+    }
+
+    #[test]
+    fn test_processing_cancel_only_while_running() {
+        let processing = Processing::new();
+        assert!(!processing.cancel());
+        let token = processing.begin();
+        assert!(processing.cancel());
+        assert!(token.is_cancelled());
+        assert!(!processing.cancel());
+
+        processing.begin();
+        processing.finish();
+        assert!(!processing.cancel());
+        assert!(!processing.current().is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_between_stages_stops_the_next_stage() {
+        let processing = Processing::new();
+        let transcription = processing.begin();
+        let done = transcription.run(Stage::Transcription, &deadlines(60), async { Ok(()) }).await;
+        assert!(done.is_ok());
+
+        // Cancelled after transcription returned, before enhancement started.
+        assert!(processing.cancel());
+        let enhanced = processing
+            .current()
+            .run(Stage::Enhancement, &deadlines(60), async { Ok("text") })
+            .await;
+        assert!(matches!(enhanced, Err(AppError::Cancelled(stage)) if stage == "enhancement"));
+    }
+
+    #[test]
+    fn test_deadlines_are_clamped() {
+        let d = deadlines(0).normalized();
+        assert_eq!((d.transcription_secs, d.enhancement_secs, d.insertion_secs), (10, 5, 2));
+        assert_eq!(StageDeadlines::default().limit(Stage::Enhancement), Duration::from_secs(30));
+    }
+}
@@ -1,11 +1,14 @@
 use tauri::State;
 
+use crate::cancel::{Processing, Stage};
 use crate::enhancement::openai_compat::OpenAiCompatEngine;
 use crate::enhancement::ollama::OllamaEngine;
 use crate::enhancement::tw_dict::{apply_tw_lexicon_dict, collect_relevant_hints};
 use crate::enhancement::{EnhancementConfig, EnhancementEngine, EnhancementMode};
 use crate::error::AppError;
+use crate::network::HttpClients;
 use crate::security::keystore::KeyStore;
+use crate::state::AppState;
 
 fn has_mixed_script(input: &str) -> bool {
     let has_cjk = input.chars().any(|ch| {
@@ -18,6 +21,8 @@ fn has_mixed_script(input: &str) -> bool {
 }
 
 /// Enhance text using the specified LLM provider.
+///
+/// Bounded by the enhancement deadline and stopped by `cancel_processing`.
 #[tauri::command]
 pub async fn enhance_text(
     text: String,
@@ -26,6 +31,9 @@ pub async fn enhance_text(
     language: Option<String>,
     endpoint: Option<String>,
     keystore: State<'_, KeyStore>,
+    state: State<'_, AppState>,
+    clients: State<'_, HttpClients>,
+    processing: State<'_, Processing>,
 ) -> Result<String, AppError> {
     let is_local = provider == "ollama" || provider == "lmstudio";
     crate::app_log!(
@@ -46,44 +54,53 @@ pub async fn enhance_text(
         tw_lexicon_hints: collect_relevant_hints(&text, &language),
     };
 
-    let enhanced = match provider.as_str() {
-        "ollama" => {
-            let engine = OllamaEngine::new(None);
-            engine.enhance(&text, &config).await
-        }
-        "lmstudio" => {
-            let engine = OllamaEngine::lm_studio();
-            engine.enhance(&text, &config).await
-        }
-        _ => {
-            if provider == "custom_openai_compatible"
-                && endpoint
-                    .as_ref()
-                    .map(|v| v.trim().is_empty())
-                    .unwrap_or(true)
-            {
-                return Err(AppError::Enhancement(
-                    "Custom OpenAI-compatible provider requires endpoint.".to_string(),
-                ));
+    let network = state.network.lock().unwrap().clone();
+    let client = clients.client(&network, &provider, endpoint.as_deref())?;
+    let deadlines = *state.stage_deadlines.lock().unwrap();
+    let enhanced = processing
+        .current()
+        .run(Stage::Enhancement, &deadlines, async {
+            match provider.as_str() {
+                "ollama" => {
+                    let engine = OllamaEngine::new(None).with_client(client);
+                    engine.enhance(&text, &config).await
+                }
+                "lmstudio" => {
+                    let engine = OllamaEngine::lm_studio().with_client(client);
+                    engine.enhance(&text, &config).await
+                }
+                _ => {
+                    if provider == "custom_openai_compatible"
+                        && endpoint
+                            .as_ref()
+                            .map(|v| v.trim().is_empty())
+                            .unwrap_or(true)
+                    {
+                        return Err(AppError::Enhancement(
+                            "Custom OpenAI-compatible provider requires endpoint.".to_string(),
+                        ));
+                    }
+                    let maybe_api_key = keystore.get_api_key(&provider)?;
+                    crate::app_log!(
+                        "[enhancement] cloud provider key_exists={}",
+                        maybe_api_key.is_some()
+                    );
+                    let api_key = maybe_api_key
+                        .ok_or_else(|| AppError::Enhancement(format!("No API key configured for {provider}")))?;
+                    let engine = if let Some(custom_endpoint) = endpoint
+                        .map(|v| v.trim().trim_end_matches('/').to_string())
+                        .filter(|v| !v.is_empty())
+                    {
+                        OpenAiCompatEngine::new(api_key, custom_endpoint)
+                    } else {
+                        OpenAiCompatEngine::for_provider(api_key, &provider)
+                    }
+                    .with_client(client);
+                    engine.enhance(&text, &config).await
+                }
             }
-            let maybe_api_key = keystore.get_api_key(&provider)?;
-            crate::app_log!(
-                "[enhancement] cloud provider key_exists={}",
-                maybe_api_key.is_some()
-            );
-            let api_key = maybe_api_key
-                .ok_or_else(|| AppError::Enhancement(format!("No API key configured for {provider}")))?;
-            let engine = if let Some(custom_endpoint) = endpoint
-                .map(|v| v.trim().trim_end_matches('/').to_string())
-                .filter(|v| !v.is_empty())
-            {
-                OpenAiCompatEngine::new(api_key, custom_endpoint)
-            } else {
-                OpenAiCompatEngine::for_provider(api_key, &provider)
-            };
-            engine.enhance(&text, &config).await
-        }
-    }?;
+        })
+        .await?;
 
     Ok(apply_tw_lexicon_dict(&enhanced, &language))
 }
@@ -0,0 +1,66 @@
+use std::fs;
+use std::path::PathBuf;
+
+use tauri::State;
+
+use crate::error::AppError;
+use crate::state::AppState;
+use crate::stt::glossary::{Glossary, ALL_LANGUAGES};
+
+/// Read a glossary file (exported JSON or a plain-text list).
+///
+/// Plain-text terms without a `[lang]` header go to `language`, or to every
+/// language when omitted. With `merge`, the result includes the current terms;
+/// the frontend stores the returned glossary and syncs it back.
+#[tauri::command]
+pub fn import_glossary(
+    path: String,
+    language: Option<String>,
+    merge: bool,
+    state: State<'_, AppState>,
+) -> Result<Glossary, AppError> {
+    let content = fs::read_to_string(&path)?;
+    let language = language.unwrap_or_else(|| ALL_LANGUAGES.to_string());
+    let imported = Glossary::parse(&content, &language)?;
+    crate::app_log!(
+        "[glossary] Imported {} list(s) from {}",
+        imported.lists.len(),
+        path
+    );
+    if merge {
+        Ok(state.glossary.lock().unwrap().clone().merge(imported))
+    } else {
+        Ok(imported)
+    }
+}
+
+/// Write the current glossary as JSON; returns the file path.
+///
+/// Defaults to `~/Documents/Voxlore/glossary.json`.
+#[tauri::command]
+pub fn export_glossary(
+    path: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<String, AppError> {
+    let path = match path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => PathBuf::from(path),
+        None => default_export_path()?,
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let glossary = state.glossary.lock().unwrap().clone();
+    fs::write(&path, serde_json::to_string_pretty(&glossary)?)?;
+    crate::app_log!("[glossary] Exported to {}", path.display());
+    Ok(path.display().to_string())
+}
+
+fn default_export_path() -> Result<PathBuf, AppError> {
+    let home = std::env::var("HOME").map(PathBuf::from).map_err(|_| {
+        AppError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "Cannot determine home directory",
+        ))
+    })?;
+    Ok(home.join("Documents").join("Voxlore").join("glossary.json"))
+}
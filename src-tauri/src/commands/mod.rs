@@ -1,12 +1,15 @@
 pub mod audio;
 pub mod enhancement;
 pub mod floating;
+pub mod glossary;
 pub mod model_manager;
+pub mod offline_queue;
 pub mod permissions;
 pub mod preview;
 pub mod recording;
 pub mod settings;
 pub mod stt;
+pub mod subtitles;
 pub mod text_insert;
 
 use serde::Serialize;
@@ -1,8 +1,18 @@
-use tauri::{AppHandle, Manager, State};
+use std::io::Write;
+use std::path::Path;
+
+use futures_util::StreamExt;
+use tauri::{AppHandle, Emitter, Manager, State};
 
 use crate::error::AppError;
 use crate::models::{downloader, registry};
-use crate::stt::vosk_engine::{VoskManager, VoskModelStatus};
+use crate::stt::registry::EngineKind;
+use crate::stt::routing;
+use crate::stt::sherpa_engine::{
+    self, SherpaManager, SherpaModel, SherpaModelKind, SherpaModelStatus,
+};
+use crate::stt::vosk_engine::{VoskManager, VoskMode, VoskModelStatus};
+use crate::stt::whisper_engine::{self, WhisperManager, WhisperModel, WhisperModelStatus};
 
 /// Get the models directory path (inside app data dir).
 fn models_dir(app: &AppHandle) -> Result<std::path::PathBuf, AppError> {
@@ -69,3 +79,327 @@ pub fn list_downloaded_vosk_models(app: AppHandle) -> Result<Vec<String>, AppErr
     let dir = models_dir(&app)?;
     Ok(downloader::list_downloaded_models(&dir))
 }
+
+/// Register or replace the phrase list for a grammar use-case (e.g. "commands").
+#[tauri::command]
+pub fn set_vosk_grammar(
+    name: String,
+    phrases: Vec<String>,
+    vosk: State<'_, VoskManager>,
+) -> Result<VoskModelStatus, AppError> {
+    vosk.set_grammar(&name, &phrases)?;
+    Ok(vosk.status())
+}
+
+/// Remove a grammar use-case.
+#[tauri::command]
+pub fn remove_vosk_grammar(name: String, vosk: State<'_, VoskManager>) -> VoskModelStatus {
+    vosk.remove_grammar(&name);
+    vosk.status()
+}
+
+/// Switch Vosk between free dictation and a registered grammar.
+#[tauri::command]
+pub fn set_vosk_mode(
+    mode: VoskMode,
+    vosk: State<'_, VoskManager>,
+) -> Result<VoskModelStatus, AppError> {
+    vosk.set_mode(mode)?;
+    Ok(vosk.status())
+}
+
+/// Set how many n-best alternatives Vosk returns (0 disables).
+#[tauri::command]
+pub fn set_vosk_max_alternatives(count: u16, vosk: State<'_, VoskManager>) -> VoskModelStatus {
+    vosk.set_max_alternatives(count);
+    vosk.status()
+}
+
+/// Get the Whisper models directory path (inside app data dir).
+fn whisper_models_dir(app: &AppHandle) -> Result<std::path::PathBuf, AppError> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Audio(format!("Failed to resolve app data dir: {e}")))?;
+    Ok(data_dir.join("models").join("whisper"))
+}
+
+fn find_whisper_model(model_id: &str) -> Result<WhisperModel, AppError> {
+    whisper_engine::available_models()
+        .into_iter()
+        .find(|m| m.id == model_id)
+        .ok_or_else(|| AppError::Audio(format!("Unknown model: {model_id}")))
+}
+
+/// List the Whisper models that can be downloaded.
+#[tauri::command]
+pub fn list_whisper_models() -> Vec<WhisperModel> {
+    whisper_engine::available_models()
+}
+
+/// Download a Whisper ggml model by its ID.
+#[tauri::command]
+pub async fn download_whisper_model(
+    app: AppHandle,
+    model_id: String,
+) -> Result<String, AppError> {
+    let model_info = find_whisper_model(&model_id)?;
+    let model_path = whisper_models_dir(&app)?.join(&model_info.file_name);
+    download_file(&app, &model_id, &model_info.url, &model_path).await?;
+    Ok(model_path.display().to_string())
+}
+
+/// Load a previously downloaded Whisper model into memory.
+#[tauri::command]
+pub fn load_whisper_model(
+    app: AppHandle,
+    model_id: String,
+    whisper: State<'_, WhisperManager>,
+) -> Result<WhisperModelStatus, AppError> {
+    let model_info = find_whisper_model(&model_id)?;
+    let model_path = whisper_models_dir(&app)?.join(&model_info.file_name);
+
+    if !model_path.exists() {
+        return Err(AppError::Stt(format!(
+            "Model not downloaded: {model_id}. Download it first."
+        )));
+    }
+
+    whisper.load_model(&model_id, &model_path)?;
+    Ok(whisper.status())
+}
+
+/// Unload the current Whisper model from memory.
+#[tauri::command]
+pub fn unload_whisper_model(whisper: State<'_, WhisperManager>) -> WhisperModelStatus {
+    whisper.unload_model();
+    whisper.status()
+}
+
+/// Get the current Whisper model status.
+#[tauri::command]
+pub fn get_whisper_status(whisper: State<'_, WhisperManager>) -> WhisperModelStatus {
+    whisper.status()
+}
+
+/// List Whisper models that are already downloaded on disk.
+#[tauri::command]
+pub fn list_downloaded_whisper_models(app: AppHandle) -> Result<Vec<String>, AppError> {
+    let dir = whisper_models_dir(&app)?;
+    Ok(whisper_engine::available_models()
+        .into_iter()
+        .filter(|m| dir.join(&m.file_name).is_file())
+        .map(|m| m.id)
+        .collect())
+}
+
+/// Get the sherpa-onnx models directory path (inside app data dir).
+fn sherpa_models_dir(app: &AppHandle) -> Result<std::path::PathBuf, AppError> {
+    let data_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Audio(format!("Failed to resolve app data dir: {e}")))?;
+    Ok(data_dir.join("models").join("sherpa"))
+}
+
+fn find_sherpa_model(model_id: &str) -> Result<SherpaModel, AppError> {
+    sherpa_engine::available_models()
+        .into_iter()
+        .find(|m| m.id == model_id)
+        .ok_or_else(|| AppError::Audio(format!("Unknown model: {model_id}")))
+}
+
+/// List the sherpa-onnx recognizer and punctuation models that can be downloaded.
+#[tauri::command]
+pub fn list_sherpa_models() -> Vec<SherpaModel> {
+    sherpa_engine::available_models()
+}
+
+/// Download and extract a sherpa-onnx model archive by its ID.
+#[tauri::command]
+pub async fn download_sherpa_model(
+    app: AppHandle,
+    model_id: String,
+) -> Result<String, AppError> {
+    let model_info = find_sherpa_model(&model_id)?;
+    let dir = sherpa_models_dir(&app)?;
+    let archive_path = dir.join(format!("{model_id}.tar.bz2"));
+    download_file(&app, &model_id, &model_info.url, &archive_path).await?;
+
+    let _ = app.emit(
+        "model:download-progress",
+        serde_json::json!({
+            "modelId": model_id,
+            "downloaded": 0,
+            "total": 0,
+            "percent": 100,
+            "stage": "extracting",
+        }),
+    );
+    let extract_dir = dir.clone();
+    let extract_archive = archive_path.clone();
+    tokio::task::spawn_blocking(move || {
+        sherpa_engine::extract_archive(&extract_archive, &extract_dir)
+    })
+    .await
+    .map_err(|e| AppError::Audio(format!("Model extraction failed: {e}")))??;
+    let _ = std::fs::remove_file(&archive_path);
+
+    Ok(dir.join(&model_id).display().to_string())
+}
+
+/// Load a downloaded sherpa-onnx model; punctuation models load alongside the recognizer.
+#[tauri::command]
+pub fn load_sherpa_model(
+    app: AppHandle,
+    model_id: String,
+    sherpa: State<'_, SherpaManager>,
+) -> Result<SherpaModelStatus, AppError> {
+    let model_info = find_sherpa_model(&model_id)?;
+    let model_dir = sherpa_models_dir(&app)?.join(&model_id);
+
+    if !model_dir.exists() {
+        return Err(AppError::Stt(format!(
+            "Model not downloaded: {model_id}. Download it first."
+        )));
+    }
+
+    if model_info.kind == SherpaModelKind::Punctuation {
+        sherpa.load_punctuation(&model_info, &model_dir)?;
+    } else {
+        sherpa.load_model(&model_info, &model_dir)?;
+    }
+    Ok(sherpa.status())
+}
+
+/// Unload the sherpa-onnx models from memory.
+#[tauri::command]
+pub fn unload_sherpa_model(sherpa: State<'_, SherpaManager>) -> SherpaModelStatus {
+    sherpa.unload_model();
+    sherpa.status()
+}
+
+/// Get the current sherpa-onnx model status.
+#[tauri::command]
+pub fn get_sherpa_status(sherpa: State<'_, SherpaManager>) -> SherpaModelStatus {
+    sherpa.status()
+}
+
+/// List sherpa-onnx models that are already downloaded on disk.
+#[tauri::command]
+pub fn list_downloaded_sherpa_models(app: AppHandle) -> Result<Vec<String>, AppError> {
+    let dir = sherpa_models_dir(&app)?;
+    Ok(sherpa_engine::available_models()
+        .into_iter()
+        .filter(|m| dir.join(&m.id).is_dir())
+        .map(|m| m.id)
+        .collect())
+}
+
+/// Enable or disable punctuation restoration for Paraformer output.
+#[tauri::command]
+pub fn set_sherpa_punctuation(
+    enabled: bool,
+    sherpa: State<'_, SherpaManager>,
+) -> SherpaModelStatus {
+    sherpa.set_punctuation_enabled(enabled);
+    sherpa.status()
+}
+
+/// Stream a single-file model to `dest`, emitting `model:download-progress`.
+///
+/// Writes to a `.part` file first so an interrupted download is never
+/// mistaken for a complete model.
+async fn download_file(
+    app: &AppHandle,
+    model_id: &str,
+    url: &str,
+    dest: &Path,
+) -> Result<(), AppError> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let response = reqwest::get(url).await?;
+    if !response.status().is_success() {
+        return Err(AppError::Audio(format!(
+            "Model download failed ({}): {url}",
+            response.status()
+        )));
+    }
+
+    let total = response.content_length().unwrap_or(0);
+    let part_path = dest.with_extension("part");
+    let mut file = std::fs::File::create(&part_path)?;
+    let mut downloaded: u64 = 0;
+    let mut last_percent: Option<u64> = None;
+    let mut stream = response.bytes_stream();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        file.write_all(&chunk)?;
+        downloaded += chunk.len() as u64;
+
+        let percent = if total > 0 { downloaded * 100 / total } else { 0 };
+        if last_percent != Some(percent) {
+            last_percent = Some(percent);
+            let _ = app.emit(
+                "model:download-progress",
+                serde_json::json!({
+                    "modelId": model_id,
+                    "downloaded": downloaded,
+                    "total": total,
+                    "percent": percent,
+                    "stage": "downloading",
+                }),
+            );
+        }
+    }
+
+    file.flush()?;
+    drop(file);
+    std::fs::rename(&part_path, dest)?;
+
+    let _ = app.emit(
+        "model:download-progress",
+        serde_json::json!({
+            "modelId": model_id,
+            "downloaded": downloaded,
+            "total": total,
+            "percent": 100,
+            "stage": "complete",
+        }),
+    );
+    crate::app_log!("[models] Downloaded {model_id} to {}", dest.display());
+
+    Ok(())
+}
+
+/// Make `model_id` the loaded model of a local engine, loading it from disk
+/// when another model (or none) is loaded. Returns whether a model was loaded.
+pub async fn select_local_model(
+    app: &AppHandle,
+    engine: EngineKind,
+    model_id: &str,
+) -> Result<bool, AppError> {
+    let loaded = match engine {
+        EngineKind::Vosk => app.state::<VoskManager>().status().model_id,
+        EngineKind::Whisper => app.state::<WhisperManager>().status().model_id,
+        EngineKind::Sherpa => app.state::<SherpaManager>().status().model_id,
+        _ => return Ok(false),
+    };
+    let Some(model_id) = routing::model_to_load(Some(model_id), loaded.as_deref()) else {
+        return Ok(false);
+    };
+
+    crate::app_log!("[stt] Loading routed model engine={engine:?} model={model_id}");
+    let (app, model_id) = (app.clone(), model_id.to_string());
+    tokio::task::spawn_blocking(move || match engine {
+        EngineKind::Whisper => load_whisper_model(app.clone(), model_id, app.state()).map(drop),
+        EngineKind::Sherpa => load_sherpa_model(app.clone(), model_id, app.state()).map(drop),
+        _ => load_vosk_model(app.clone(), model_id, app.state()).map(drop),
+    })
+    .await
+    .map_err(|e| AppError::Stt(format!("Model load failed: {e}")))??;
+    Ok(true)
+}
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::audio::wav;
+use crate::error::AppError;
+use crate::state::AppState;
+use crate::stt::queue::{PendingQueue, PendingTranscription, MAX_ATTEMPTS};
+use crate::stt::registry::SttRegistry;
+use crate::stt::transcript::Transcript;
+use crate::stt::SttResult;
+
+use super::stt::{provider_reachable, transcribe_samples, TranscribeOptions};
+
+const SAMPLE_RATE: u32 = 16000;
+/// How often the queue checks whether the network is back.
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Recordings waiting for the network.
+#[tauri::command]
+pub fn list_pending_transcriptions(queue: State<'_, PendingQueue>) -> Vec<PendingTranscription> {
+    queue.items()
+}
+
+/// Try the queue now instead of waiting for the next check.
+/// Returns how many recordings are still pending.
+#[tauri::command]
+pub async fn retry_pending_transcriptions(app: AppHandle) -> Result<usize, AppError> {
+    drain(&app).await;
+    Ok(app.state::<PendingQueue>().len())
+}
+
+/// Drop a queued recording; its WAV and interim text stay on disk.
+#[tauri::command]
+pub fn discard_pending_transcription(
+    app: AppHandle,
+    id: String,
+    queue: State<'_, PendingQueue>,
+) -> Result<(), AppError> {
+    crate::app_log!("[queue] discard id={id}");
+    queue.remove(&id)?;
+    emit_changed(&app, &queue);
+    Ok(())
+}
+
+/// Check the queue for the life of the app, starting with anything left
+/// from the last session.
+pub fn spawn_worker(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            drain(&app).await;
+            tokio::time::sleep(RETRY_INTERVAL).await;
+        }
+    });
+}
+
+pub fn emit_changed(app: &AppHandle, queue: &PendingQueue) {
+    let _ = app.emit(
+        "offline-queue:changed",
+        serde_json::json!({"pending": queue.len()}),
+    );
+}
+
+/// Transcribe queued recordings whose provider is reachable again.
+async fn drain(app: &AppHandle) {
+    let queue = app.state::<PendingQueue>();
+    if queue.len() == 0 || !queue.begin_drain() {
+        return;
+    }
+    let mut online: HashMap<String, bool> = HashMap::new();
+    let mut changed = false;
+
+    for item in queue.items() {
+        let key = format!("{}|{}", item.provider, item.base_url.as_deref().unwrap_or_default());
+        let reachable = match online.get(&key) {
+            Some(reachable) => *reachable,
+            None => {
+                let reachable =
+                    provider_reachable(app, &item.provider, item.base_url.as_deref()).await;
+                online.insert(key.clone(), reachable);
+                reachable
+            }
+        };
+        if !reachable {
+            continue;
+        }
+
+        match transcribe_pending(app, &item).await {
+            Ok(result) => {
+                crate::app_log!(
+                    "[queue] transcribed id={} provider={} text_len={}",
+                    item.id, item.provider, result.text.len()
+                );
+                if let Err(e) = queue.remove(&item.id) {
+                    crate::app_log!("[queue] Failed to update queue: {e}");
+                }
+                changed = true;
+                let _ = app.emit(
+                    "offline-queue:transcribed",
+                    serde_json::json!({
+                        "id": item.id,
+                        "audioPath": item.audio_path,
+                        "textPath": item.text_path,
+                        "text": result.text,
+                        "interimText": item.interim_text,
+                        "provider": item.provider,
+                    }),
+                );
+            }
+            Err(e) => {
+                crate::app_log!("[queue] id={} provider={} failed: {e}", item.id, item.provider);
+                // A dropped connection is not the recording's fault.
+                if !provider_reachable(app, &item.provider, item.base_url.as_deref()).await {
+                    online.insert(key, false);
+                    continue;
+                }
+                let missing_audio = matches!(&e, AppError::Io(io) if io.kind() == std::io::ErrorKind::NotFound);
+                let attempts = queue.record_attempt(&item.id).unwrap_or(MAX_ATTEMPTS);
+                if missing_audio || attempts >= MAX_ATTEMPTS {
+                    crate::app_log!("[queue] giving up id={} after {attempts} attempts", item.id);
+                    let _ = queue.remove(&item.id);
+                    changed = true;
+                    let _ = app.emit(
+                        "offline-queue:failed",
+                        serde_json::json!({
+                            "id": item.id,
+                            "audioPath": item.audio_path,
+                            "message": e.to_string(),
+                        }),
+                    );
+                }
+            }
+        }
+    }
+
+    queue.end_drain();
+    if changed {
+        emit_changed(app, &queue);
+    }
+}
+
+/// Transcribe one queued WAV and overwrite its text file and timings.
+async fn transcribe_pending(app: &AppHandle, item: &PendingTranscription) -> Result<SttResult, AppError> {
+    let descriptor = app.state::<SttRegistry>().resolve(&item.provider)?;
+    let samples = wav::decode_pcm16(&fs::read(&item.audio_path)?)?;
+    let options = TranscribeOptions {
+        language: item.language.clone(),
+        model: item.model.clone(),
+        base_url: item.base_url.clone(),
+        timeout_secs: *app.state::<AppState>().cloud_timeout_secs.lock().unwrap(),
+        retry: true,
+        live_result: None,
+        health_probe: false,
+    };
+    let result = transcribe_samples(app, &descriptor, &samples, options).await?;
+
+    fs::write(&item.text_path, &result.text)?;
+    if !result.segments.is_empty() || !result.words.is_empty() {
+        let duration_secs = samples.len() as f32 / SAMPLE_RATE as f32;
+        let path = Transcript::sidecar_path(Path::new(&item.audio_path));
+        Transcript::new(&result, Some(item.provider.clone()), duration_secs).save(&path)?;
+    }
+    Ok(result)
+}
@@ -8,6 +8,7 @@ use crate::state::AppState;
 pub async fn show_preview_window(
     app: AppHandle,
     text: String,
+    alternatives: Option<Vec<String>>,
     state: State<'_, AppState>,
 ) -> Result<(), AppError> {
     // 優先使用熱鍵按下時就鎖定的目標 App（最穩定）。
@@ -23,6 +24,12 @@ pub async fn show_preview_window(
     *state.preview_target_bundle_id.lock().unwrap() = target_bundle;
 
     // Store text in state so the preview window can pull it once mounted
+    let alternatives: Vec<String> = alternatives
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|alt| !alt.trim().is_empty() && alt != &text)
+        .collect();
+    *state.preview_alternatives.lock().unwrap() = alternatives;
     *state.preview_text.lock().unwrap() = Some(text);
 
     // Close existing preview window if open
@@ -51,6 +58,12 @@ pub fn get_preview_text(state: State<'_, AppState>) -> Option<String> {
     state.preview_text.lock().unwrap().clone()
 }
 
+/// Get alternative transcripts for the preview (called by the preview window on mount).
+#[tauri::command]
+pub fn get_preview_alternatives(state: State<'_, AppState>) -> Vec<String> {
+    state.preview_alternatives.lock().unwrap().clone()
+}
+
 /// Get the resolved target app bundle id for preview apply.
 #[tauri::command]
 pub fn get_preview_target_bundle_id(state: State<'_, AppState>) -> Option<String> {
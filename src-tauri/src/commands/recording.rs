@@ -6,20 +6,27 @@ use std::time::{Duration, Instant};
 
 use chrono::Local;
 use serde::Serialize;
-use tauri::{AppHandle, Emitter, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 use crate::audio::capture::AudioCapture;
 use crate::audio::wav;
+use crate::cancel::{Processing, Stage};
 use crate::error::AppError;
-use crate::security::keystore::KeyStore;
 use crate::state::AppState;
 use crate::stt::converter;
-use crate::stt::elevenlabs::ElevenLabsEngine;
-use crate::stt::mistral::MistralEngine;
-use crate::stt::openai_whisper::OpenAiWhisperEngine;
-use crate::stt::openrouter_audio::OpenRouterAudioEngine;
-use crate::stt::{CloudSttEngine, SttConfig, SttProvider};
-use crate::stt::vosk_engine::VoskManager;
+use crate::stt::language;
+use crate::stt::queue::{PendingQueue, PendingTranscription};
+use crate::stt::registry::SttRegistry;
+use crate::stt::routing::{self, EnhancementRoute, LanguageRoute, SttSelection};
+use crate::stt::transcript::Transcript;
+use crate::stt::vosk_engine::{self, VoskManager, VoskStream};
+use crate::stt::{SttAlternative, SttResult, SttSegment, SttWord};
+
+use super::offline_queue::emit_changed;
+use super::stt::{
+    escalate_if_unsure, provider_reachable, route_detected_language, transcribe_samples,
+    transcribe_with_fallback, TranscribeOptions,
+};
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -27,10 +34,38 @@ pub struct RecordingResult {
     pub text: String,
     pub audio_path: Option<String>,
     pub text_path: Option<String>,
+    /// Timed transcript sidecar, written when the engine reported timings.
+    pub transcript_path: Option<String>,
     pub duration_secs: f32,
+    /// Utterance confidence (0.0–1.0) when the engine reports it.
+    pub confidence: Option<f32>,
+    /// Word timings and per-word confidence, for highlighting uncertain words.
+    pub words: Vec<SttWord>,
+    /// Phrase-level timings for navigating longer recordings.
+    pub segments: Vec<SttSegment>,
+    /// N-best transcripts (best first) for one-click swaps in preview.
+    pub alternatives: Vec<SttAlternative>,
+    /// Provider that produced the text; differs from the selected one after a failover.
+    pub provider: Option<String>,
+    /// Language of the utterance: detected in auto mode, otherwise as reported by the engine.
+    pub language: Option<String>,
+    /// The provider was unreachable and the recording waits in the offline
+    /// queue; `text` is the on-device interim transcript, if any.
+    pub queued: bool,
+    /// Enhancement settings from the language route, overriding the global ones.
+    pub enhancement: Option<EnhancementRoute>,
+}
+
+/// Output of the recording background task.
+pub struct CapturedAudio {
+    pub samples: Vec<i16>,
+    /// Transcript from the live Vosk recognizer, when it ran during capture.
+    pub live_result: Option<SttResult>,
 }
 
 const SAMPLE_RATE: u32 = 16000;
+/// Minimum interval between `recording:partial` events.
+const PARTIAL_EMIT_INTERVAL_MS: u128 = 150;
 
 /// Start recording from the default microphone.
 ///
@@ -82,6 +117,16 @@ pub async fn start_recording(
         }
     }
 
+    // Local dictation streams into a live recognizer so the final text is ready on release.
+    let stt_language = state.stt_language.lock().unwrap().clone();
+    let (selection, _) = stt_selection(&state, &app.state::<SttRegistry>(), &stt_language);
+    let stream_vosk = app
+        .state::<SttRegistry>()
+        .resolve(&selection.provider)
+        .map(|p| p.capabilities.live_partials)
+        .unwrap_or(false);
+    let needs_s2t = converter::needs_s2t_conversion(&stt_language);
+
     let stop = Arc::new(AtomicBool::new(false));
     // Signal that start_recording has begun (ready flag for stop to wait on)
     let ready = Arc::new(AtomicBool::new(false));
@@ -99,7 +144,7 @@ pub async fn start_recording(
                 serde_json::json!({"status": "error", "message": msg}),
             );
             ready_clone.store(true, Ordering::Release);
-            return Vec::new();
+            return CapturedAudio { samples: Vec::new(), live_result: None };
         }
 
         let receiver = match capture.take_receiver() {
@@ -112,8 +157,20 @@ pub async fn start_recording(
                     serde_json::json!({"status": "error", "message": msg}),
                 );
                 ready_clone.store(true, Ordering::Release);
-                return Vec::new();
+                return CapturedAudio { samples: Vec::new(), live_result: None };
+            }
+        };
+
+        let mut live: Option<VoskStream> = if stream_vosk {
+            match app_handle.state::<VoskManager>().start_stream(SAMPLE_RATE as f32) {
+                Ok(stream) => Some(stream),
+                Err(e) => {
+                    crate::app_log!("[recording] Live Vosk recognizer unavailable: {e}");
+                    None
+                }
             }
+        } else {
+            None
         };
 
         // Signal that recording has started successfully
@@ -121,6 +178,8 @@ pub async fn start_recording(
 
         let mut buffer: Vec<i16> = Vec::new();
         let mut last_emit = Instant::now();
+        let mut pending_partial: Option<String> = None;
+        let mut last_partial_emit = Instant::now();
 
         loop {
             // Check stop signal BEFORE waiting — critical for quick stop
@@ -139,16 +198,55 @@ pub async fn start_recording(
                         );
                         last_emit = Instant::now();
                     }
+                    if let Some(stream) = live.as_mut() {
+                        match stream.accept(&chunk) {
+                            Ok(Some(partial)) => pending_partial = Some(partial),
+                            Ok(None) => {}
+                            Err(e) => {
+                                // Fall back to a full pass over the buffer in stop_recording.
+                                crate::app_log!("[recording] Live Vosk recognizer failed: {e}");
+                                live = None;
+                            }
+                        }
+                    }
                     buffer.extend(chunk);
                 }
                 Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
                 Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
             }
+
+            if pending_partial.is_some()
+                && last_partial_emit.elapsed().as_millis() >= PARTIAL_EMIT_INTERVAL_MS
+            {
+                let mut text = vosk_engine::join_cjk_tokens(
+                    &pending_partial.take().unwrap_or_default(),
+                    &stt_language,
+                );
+                if needs_s2t {
+                    text = converter::simplified_to_traditional(&text);
+                }
+                let _ = app_handle.emit(
+                    "recording:partial",
+                    serde_json::json!({"text": text}),
+                );
+                last_partial_emit = Instant::now();
+            }
         }
 
         capture.stop();
         crate::app_log!("[recording] Collected {} samples ({:.1}s)", buffer.len(), buffer.len() as f32 / SAMPLE_RATE as f32);
-        buffer
+
+        let live_result = live.and_then(|stream| match stream.finish() {
+            Ok(result) => Some(result),
+            Err(e) => {
+                crate::app_log!("[recording] Live Vosk finalize failed: {e}");
+                None
+            }
+        });
+        CapturedAudio {
+            samples: buffer,
+            live_result,
+        }
     });
 
     // Wait briefly for the blocking task to signal readiness
@@ -184,8 +282,6 @@ pub async fn stop_recording(
     app: AppHandle,
     output_dir: Option<String>,
     state: State<'_, AppState>,
-    vosk: State<'_, VoskManager>,
-    keystore: State<'_, KeyStore>,
 ) -> Result<RecordingResult, AppError> {
     crate::app_log!("[recording] Stopping...");
 
@@ -224,7 +320,10 @@ pub async fn stop_recording(
         tokio::time::sleep(Duration::from_millis(50)).await;
     };
 
-    let buffer = handle
+    let CapturedAudio {
+        samples: buffer,
+        live_result,
+    } = handle
         .await
         .map_err(|e| AppError::Audio(format!("Recording task failed: {e}")))?;
 
@@ -239,7 +338,16 @@ pub async fn stop_recording(
             text: String::new(),
             audio_path: None,
             text_path: None,
+            transcript_path: None,
             duration_secs: 0.0,
+            confidence: None,
+            words: Vec::new(),
+            segments: Vec::new(),
+            alternatives: Vec::new(),
+            provider: None,
+            language: None,
+            queued: false,
+            enhancement: None,
         });
     }
 
@@ -259,199 +367,312 @@ pub async fn stop_recording(
     fs::write(&wav_path, &wav_data)?;
     crate::app_log!("[recording] Saved WAV: {} ({} bytes)", wav_path.display(), wav_data.len());
 
-    let provider = state.stt_provider.lock().unwrap().clone();
     let language = state.stt_language.lock().unwrap().clone();
-    let model = state.stt_model.lock().unwrap().clone();
-    let stt_base_url = state.stt_base_url.lock().unwrap().clone();
+    let (selection, route) = stt_selection(&state, &app.state::<SttRegistry>(), &language);
+    let SttSelection {
+        provider,
+        model,
+        base_url: stt_base_url,
+    } = selection;
+    // Auto mode learns the language from the first transcript.
+    let routes = if route.is_none() && language::is_auto(&language) {
+        state.stt_routes.lock().unwrap().clone()
+    } else {
+        Vec::new()
+    };
     let cloud_timeout_secs = *state.cloud_timeout_secs.lock().unwrap();
+    let fallback_chain = state.stt_fallback_chain.lock().unwrap().clone();
+    let race = state.stt_race.lock().unwrap().clone();
+    let escalation = state.stt_escalation.lock().unwrap().clone();
+    let deadlines = *state.stage_deadlines.lock().unwrap();
     crate::app_log!(
-        "[recording] STT settings provider={} language={} model={:?}",
-        provider, language, model
+        "[recording] STT settings provider={} language={} model={:?} race={:?} route={:?}",
+        provider, language, model, race.as_ref().map(|r| &r.provider),
+        route.as_ref().map(|r| &r.language)
     );
 
-    let processing_message = if provider == "vosk" {
-        "Processing transcription locally...".to_string()
-    } else {
-        format!(
+    let descriptor = app.state::<SttRegistry>().resolve(&provider);
+    let processing_message = match &descriptor {
+        Ok(d) if d.capabilities.local => "Processing transcription locally...".to_string(),
+        _ => format!(
             "Processing via cloud AI ({provider})... If network is slow, this may timeout."
-        )
+        ),
     };
+    let token = app.state::<Processing>().begin();
     let _ = app.emit(
         "recording:status",
         serde_json::json!({"status": "processing", "message": processing_message}),
     );
 
-    let text = match transcribe_with_selected_provider(
-        &app,
-        &buffer,
-        &provider,
-        &language,
-        model,
-        stt_base_url,
-        cloud_timeout_secs,
-        &vosk,
-        &keystore,
-    )
-    .await
-    {
-        Ok(text) => text,
+    let options = TranscribeOptions {
+        language: language.clone(),
+        model: model.clone(),
+        base_url: stt_base_url.clone(),
+        timeout_secs: cloud_timeout_secs,
+        retry: true,
+        live_result,
+        health_probe: false,
+    };
+    let mut detected_route = None;
+    let transcription = token
+        .run(Stage::Transcription, &deadlines, async {
+            let transcription = transcribe_with_fallback(
+                &app,
+                &provider,
+                &buffer,
+                options,
+                &fallback_chain,
+                race.as_ref(),
+            )
+            .await;
+            let transcription = match transcription {
+                Ok(outcome) if !routes.is_empty() => {
+                    let (outcome, matched) =
+                        route_detected_language(&app, outcome, &buffer, &routes, cloud_timeout_secs)
+                            .await;
+                    detected_route = matched;
+                    Ok(outcome)
+                }
+                transcription => transcription,
+            };
+            match (transcription, &escalation) {
+                (Ok(outcome), Some(escalation)) => Ok(escalate_if_unsure(
+                    &app,
+                    outcome,
+                    &buffer,
+                    &language,
+                    cloud_timeout_secs,
+                    escalation,
+                )
+                .await),
+                (transcription, _) => transcription,
+            }
+        })
+        .await;
+    let route = route.or(detected_route);
+    // cancel_processing already told the UI; keep the WAV and skip the offline queue.
+    if let Err(e @ AppError::Cancelled(_)) = transcription {
+        crate::app_log!("[recording] Transcription cancelled; audio kept at {}", wav_path.display());
+        return Err(e);
+    }
+    let txt_path = dir.join(format!("{base_name}.txt"));
+    let (result, used_provider, queued) = match transcription {
+        Ok(outcome) => (outcome.result, Some(outcome.provider), false),
         Err(e) => {
             crate::app_log!("[recording] Transcription failed: {e}");
-            let _ = app.emit(
-                "recording:status",
-                serde_json::json!({"status": "error", "message": format!("Transcription failed: {e}")}),
-            );
-            String::new()
+            let cloud = descriptor.as_ref().is_ok_and(|d| !d.capabilities.local);
+            let queue = app.state::<PendingQueue>();
+            let offline = cloud && !provider_reachable(&app, &provider, stt_base_url.as_deref()).await;
+            let mut interim = None;
+            let mut enqueued = None;
+            if offline {
+                interim = interim_transcript(&app, &buffer, &language, cloud_timeout_secs).await;
+                enqueued = Some(queue.push(PendingTranscription {
+                    id: base_name.clone(),
+                    audio_path: wav_path.display().to_string(),
+                    text_path: txt_path.display().to_string(),
+                    provider: provider.clone(),
+                    model,
+                    base_url: stt_base_url,
+                    language: language.clone(),
+                    queued_at: Local::now().to_rfc3339(),
+                    attempts: 0,
+                    interim_text: interim.as_ref().map(|r| r.text.clone()),
+                }));
+            }
+            match enqueued {
+                Some(Ok(())) => {
+                    crate::app_log!(
+                        "[recording] Offline; queued {base_name} for {provider} ({} pending)",
+                        queue.len()
+                    );
+                    emit_changed(&app, &queue);
+                    let _ = app.emit(
+                        "recording:status",
+                        serde_json::json!({
+                            "status": "queued",
+                            "message": format!("Offline: {provider} will transcribe this recording when the network is back."),
+                        }),
+                    );
+                    match interim {
+                        Some(result) => (result, Some("vosk".to_string()), true),
+                        None => (SttResult::default(), None, true),
+                    }
+                }
+                failed => {
+                    if let Some(Err(queue_error)) = failed {
+                        crate::app_log!("[recording] Failed to queue recording: {queue_error}");
+                    }
+                    let _ = app.emit(
+                        "recording:status",
+                        serde_json::json!({"status": "error", "message": format!("Transcription failed: {e}")}),
+                    );
+                    (SttResult::default(), None, false)
+                }
+            }
         }
     };
 
     // Save transcription text
-    let txt_path = dir.join(format!("{base_name}.txt"));
-    fs::write(&txt_path, &text)?;
+    fs::write(&txt_path, &result.text)?;
     crate::app_log!("[recording] Saved TXT: {}", txt_path.display());
 
-    let _ = app.emit("recording:status", serde_json::json!({"status": "done"}));
+    // Save timings next to the audio when the engine reported any
+    let mut transcript_path = None;
+    if !result.segments.is_empty() || !result.words.is_empty() {
+        let path = Transcript::sidecar_path(&wav_path);
+        match Transcript::new(&result, used_provider.clone(), duration_secs).save(&path) {
+            Ok(()) => {
+                crate::app_log!("[recording] Saved transcript: {}", path.display());
+                transcript_path = Some(path.display().to_string());
+            }
+            Err(e) => crate::app_log!("[recording] Failed to save transcript: {e}"),
+        }
+    }
+
+    if !queued {
+        let _ = app.emit("recording:status", serde_json::json!({"status": "done"}));
+    }
 
     Ok(RecordingResult {
-        text,
+        text: result.text,
         audio_path: Some(wav_path.display().to_string()),
         text_path: Some(txt_path.display().to_string()),
+        transcript_path,
         duration_secs,
+        confidence: result.confidence,
+        words: result.words,
+        segments: result.segments,
+        alternatives: result.alternatives,
+        provider: used_provider,
+        language: result.language_detected,
+        queued,
+        enhancement: route.and_then(|r| r.enhancement),
     })
 }
 
-async fn transcribe_with_selected_provider(
+/// Engine for a recording in `language`: its route's provider when it names
+/// one, otherwise the selected settings. Also returns the matching route.
+///
+/// The model setting belongs to cloud providers, so a selected local engine
+/// keeps its loaded model; only a route switches it to another one.
+pub fn stt_selection(
+    state: &AppState,
+    registry: &SttRegistry,
+    language: &str,
+) -> (SttSelection, Option<LanguageRoute>) {
+    let provider = state.stt_provider.lock().unwrap().clone();
+    let local = registry.resolve(&provider).is_ok_and(|p| p.capabilities.local);
+    let selected = SttSelection {
+        model: state.stt_model.lock().unwrap().clone().filter(|_| !local),
+        provider,
+        base_url: state.stt_base_url.lock().unwrap().clone(),
+    };
+    let routes = state.stt_routes.lock().unwrap();
+    match routing::route_for(&routes, language) {
+        Some(route) => (route.apply(selected), Some(route.clone())),
+        None => (selected, None),
+    }
+}
+
+/// Cancel the transcription, enhancement or insertion in flight; the
+/// pipeline reports a `cancelled` status instead of an error.
+/// Returns `false` when there is nothing to cancel.
+#[tauri::command]
+pub fn cancel_processing(app: AppHandle, processing: State<'_, Processing>) -> bool {
+    let cancelled = processing.cancel();
+    crate::app_log!("[recording] cancel_processing cancelled={cancelled}");
+    if cancelled {
+        let _ = app.emit("recording:status", serde_json::json!({"status": "cancelled"}));
+    }
+    cancelled
+}
+
+/// Whether the current dictation was cancelled, so a failed stage can be
+/// told apart from a cancelled one without reading the error text.
+#[tauri::command]
+pub fn processing_cancelled(processing: State<'_, Processing>) -> bool {
+    processing.current().is_cancelled()
+}
+
+/// Mark the dictation finished once its text is inserted or shown in the
+/// preview, so a later `cancel_processing` has nothing to cancel.
+#[tauri::command]
+pub fn finish_processing(processing: State<'_, Processing>) {
+    processing.finish();
+}
+
+/// On-device transcript to use while the recording waits for the network.
+async fn interim_transcript(
     app: &AppHandle,
     samples: &[i16],
-    provider_raw: &str,
     language: &str,
-    model: Option<String>,
-    base_url: Option<String>,
-    cloud_timeout_secs: u64,
-    vosk: &VoskManager,
-    keystore: &KeyStore,
-) -> Result<String, AppError> {
-    let timeout_secs = cloud_timeout_secs.max(5).min(180);
-
-    let provider: SttProvider = serde_json::from_str(&format!("\"{}\"", provider_raw))
-        .map_err(|_| AppError::Stt(format!("Unsupported STT provider: {provider_raw}")))?;
-
-    let needs_s2t = converter::needs_s2t_conversion(language);
-    let config = SttConfig {
+    timeout_secs: u64,
+) -> Option<SttResult> {
+    let state = app.state::<AppState>();
+    if !*state.offline_interim_transcript.lock().unwrap() || !app.state::<VoskManager>().is_loaded() {
+        return None;
+    }
+    let vosk = app.state::<SttRegistry>().resolve("vosk").ok()?;
+    let options = TranscribeOptions {
         language: language.to_string(),
-        sample_rate: SAMPLE_RATE,
+        model: None,
+        base_url: None,
+        timeout_secs,
+        retry: true,
+        live_result: None,
+        health_probe: false,
     };
-
-    let mut text = match provider {
-        SttProvider::Vosk => {
-            if !vosk.is_loaded() {
-                let _ = app.emit(
-                    "recording:status",
-                    serde_json::json!({"status": "error", "message": "Vosk model not loaded. Please download and load a model in Settings."}),
-                );
-                return Ok(String::new());
-            }
-            crate::app_log!("[recording] Transcribing via Vosk...");
-            vosk.transcribe_samples(samples, SAMPLE_RATE as f32)?.text
-        }
-        SttProvider::ElevenLabs => {
-            crate::app_log!("[recording] Transcribing via ElevenLabs...");
-            let wav_data = wav::encode_wav(samples, SAMPLE_RATE);
-            let api_key = get_api_key(keystore, "elevenlabs")?;
-            let engine = ElevenLabsEngine::new(api_key, model);
-            tokio::time::timeout(
-                Duration::from_secs(timeout_secs),
-                engine.transcribe(&wav_data, &config),
-            )
-            .await
-            .map_err(|_| AppError::Stt("Cloud STT timeout. Check internet and try again.".into()))??
-            .text
-        }
-        SttProvider::OpenAI => {
-            crate::app_log!("[recording] Transcribing via OpenAI...");
-            let wav_data = wav::encode_wav(samples, SAMPLE_RATE);
-            let api_key = get_api_key(keystore, "openai")?;
-            let engine = OpenAiWhisperEngine::new(api_key, model, base_url.clone());
-            tokio::time::timeout(
-                Duration::from_secs(timeout_secs),
-                engine.transcribe(&wav_data, &config),
-            )
-            .await
-            .map_err(|_| AppError::Stt("Cloud STT timeout. Check internet and try again.".into()))??
-            .text
-        }
-        SttProvider::OpenAITranscribe => {
-            crate::app_log!("[recording] Transcribing via OpenAI Transcribe...");
-            let wav_data = wav::encode_wav(samples, SAMPLE_RATE);
-            let api_key = get_api_key(keystore, "openai")?;
-            let transcribe_model = model.or_else(|| Some("gpt-4o-mini-transcribe".to_string()));
-            let engine = OpenAiWhisperEngine::new(api_key, transcribe_model, base_url.clone());
-            tokio::time::timeout(
-                Duration::from_secs(timeout_secs),
-                engine.transcribe(&wav_data, &config),
-            )
-            .await
-            .map_err(|_| AppError::Stt("Cloud STT timeout. Check internet and try again.".into()))??
-            .text
-        }
-        SttProvider::OpenRouter => {
-            crate::app_log!("[recording] Transcribing via OpenRouter Audio...");
-            let wav_data = wav::encode_wav(samples, SAMPLE_RATE);
-            let api_key = get_api_key(keystore, "openrouter")?;
-            let engine = OpenRouterAudioEngine::new(api_key, model, base_url.clone());
-            tokio::time::timeout(
-                Duration::from_secs(timeout_secs),
-                engine.transcribe(&wav_data, &config),
-            )
-            .await
-            .map_err(|_| AppError::Stt("Cloud STT timeout. Check internet and try again.".into()))??
-            .text
-        }
-        SttProvider::CustomOpenAiCompatible => {
-            crate::app_log!("[recording] Transcribing via Custom OpenAI-Compatible Audio...");
-            let wav_data = wav::encode_wav(samples, SAMPLE_RATE);
-            let api_key = get_api_key(keystore, "custom_openai_compatible")?;
-            let endpoint = base_url.clone().ok_or_else(|| {
-                AppError::Stt("Custom provider requires OpenAI-compatible endpoint.".to_string())
-            })?;
-            let engine = OpenRouterAudioEngine::new(api_key, model, Some(endpoint));
-            tokio::time::timeout(
-                Duration::from_secs(timeout_secs),
-                engine.transcribe(&wav_data, &config),
-            )
-            .await
-            .map_err(|_| AppError::Stt("Cloud STT timeout. Check internet and try again.".into()))??
-            .text
-        }
-        SttProvider::Mistral => {
-            crate::app_log!("[recording] Transcribing via Mistral...");
-            let wav_data = wav::encode_wav(samples, SAMPLE_RATE);
-            let api_key = get_api_key(keystore, "mistral")?;
-            let engine = MistralEngine::new(api_key, model);
-            tokio::time::timeout(
-                Duration::from_secs(timeout_secs),
-                engine.transcribe(&wav_data, &config),
-            )
-            .await
-            .map_err(|_| AppError::Stt("Cloud STT timeout. Check internet and try again.".into()))??
-            .text
+    match transcribe_samples(app, &vosk, samples, options).await {
+        Ok(result) if !result.text.trim().is_empty() => Some(result),
+        Ok(_) => None,
+        Err(e) => {
+            crate::app_log!("[recording] Interim Vosk transcript failed: {e}");
+            None
         }
-    };
-
-    if needs_s2t {
-        text = converter::simplified_to_traditional(&text);
     }
+}
 
-    Ok(text)
+/// A recording in the output directory, for the history list.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedRecording {
+    pub audio_path: String,
+    /// Saved transcript text; empty when the recording was not transcribed.
+    pub text: String,
+    /// Whether a timed transcript sidecar exists.
+    pub timed: bool,
 }
 
-fn get_api_key(keystore: &KeyStore, provider: &str) -> Result<String, AppError> {
-    keystore
-        .get_api_key(provider)?
-        .ok_or_else(|| AppError::Stt(format!("No API key configured for {provider}")))
+/// Recordings in the output directory, newest first.
+#[tauri::command]
+pub fn list_recordings(
+    output_dir: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<SavedRecording>, AppError> {
+    let dir = resolve_output_dir(output_dir)?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wav"))
+        .collect();
+    // Names carry the timestamp (`recording_YYYYmmdd_HHMMSS`).
+    paths.sort_unstable();
+    paths.reverse();
+    paths.truncate(limit.unwrap_or(20));
+
+    Ok(paths
+        .into_iter()
+        .map(|path| SavedRecording {
+            text: fs::read_to_string(path.with_extension("txt")).unwrap_or_default(),
+            timed: Transcript::sidecar_path(&path).exists(),
+            audio_path: path.display().to_string(),
+        })
+        .collect())
 }
 
-/// Get the default recordings output directory (auto-creates if missing).
 #[tauri::command]
 pub fn get_recordings_dir() -> Result<String, AppError> {
     let dir = resolve_output_dir(None)?;
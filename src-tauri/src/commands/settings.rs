@@ -1,15 +1,35 @@
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
 use serde::Serialize;
-use tauri::{Manager, State};
+use tauri::{AppHandle, Manager, State};
 
+use crate::cancel::StageDeadlines;
 use crate::error::AppError;
+use crate::network::NetworkConfig;
 use crate::security::keystore::KeyStore;
 use crate::state::AppState;
+use crate::stt::escalation::EscalationConfig;
+use crate::stt::failover::FallbackStep;
+use crate::stt::glossary::Glossary;
+use crate::stt::health::{self, ProbeClip, ProbeVerdict};
+use crate::stt::http::response_request_id;
+use crate::stt::options::SttProviderOptions;
+use crate::stt::race::RaceConfig;
+use crate::stt::registry::{
+    CustomSttProtocol, CustomSttProvider, EngineKind, ProviderDescriptor, SttRegistry,
+};
+use crate::stt::routing::{normalize_routes, LanguageRoute};
+use crate::stt::vosk_engine::VoskManager;
+
+use super::stt::{transcribe_samples, TranscribeOptions};
 
 static NEXT_HEALTH_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
 
+/// Bound on the voice health probe: one short clip, one attempt.
+const HEALTH_PROBE_TIMEOUT: Duration = Duration::from_secs(20);
+
 /// Save an API key for a provider.
 #[tauri::command]
 pub fn save_api_key(
@@ -45,20 +65,38 @@ pub fn delete_api_key(
     keystore.delete_api_key(&provider)
 }
 
+/// Vosk grammar name holding the glossary terms for the current language.
+const GLOSSARY_GRAMMAR: &str = "glossary";
+
 /// Sync frontend settings to Rust state.
 /// Called by the frontend whenever settings change.
 #[tauri::command]
 pub fn sync_settings(
     state: State<'_, AppState>,
+    registry: State<'_, SttRegistry>,
+    vosk: State<'_, VoskManager>,
     widget_position: Option<String>,
     floating_window_enabled: Option<bool>,
     stt_language: Option<String>,
     stt_provider: Option<String>,
     stt_model: Option<String>,
     stt_base_url: Option<String>,
+    stt_protocol: Option<String>,
     cloud_timeout_secs: Option<u64>,
+    stage_deadlines: Option<StageDeadlines>,
     debug_logging_enabled: Option<bool>,
+    custom_stt_providers: Option<Vec<CustomSttProvider>>,
+    stt_provider_options: Option<HashMap<String, SttProviderOptions>>,
+    stt_fallback_chain: Option<Vec<FallbackStep>>,
+    stt_race: Option<RaceConfig>,
+    stt_escalation: Option<EscalationConfig>,
+    stt_routes: Option<Vec<LanguageRoute>>,
+    offline_interim_transcript: Option<bool>,
+    vosk_max_alternatives: Option<u16>,
+    glossary: Option<Glossary>,
+    network: Option<NetworkConfig>,
 ) {
+    let refresh_glossary_grammar = glossary.is_some() || stt_language.is_some();
     crate::app_log!(
         "[settings] sync_settings widget_position={:?} stt_language={:?} stt_provider={:?} stt_model={:?}",
         widget_position, stt_language, stt_provider, stt_model
@@ -91,12 +129,111 @@ pub fn sync_settings(
             Some(trimmed.trim_end_matches('/').to_string())
         };
     }
+    if let Some(protocol) = stt_protocol {
+        *state.stt_protocol.lock().unwrap() = match protocol.as_str() {
+            "transcriptions" => Some(CustomSttProtocol::Transcriptions),
+            "chat_audio" => Some(CustomSttProtocol::ChatAudio),
+            _ => None,
+        };
+    }
     if let Some(timeout) = cloud_timeout_secs {
         *state.cloud_timeout_secs.lock().unwrap() = timeout.clamp(5, 180);
     }
+    if let Some(deadlines) = stage_deadlines {
+        *state.stage_deadlines.lock().unwrap() = deadlines.normalized();
+    }
     if let Some(enabled) = debug_logging_enabled {
         *state.debug_logging_enabled.lock().unwrap() = enabled;
     }
+    if let Some(configs) = custom_stt_providers {
+        let mut providers = Vec::new();
+        for config in configs {
+            match ProviderDescriptor::from_custom(config) {
+                Ok(provider) => providers.push(provider),
+                Err(e) => crate::app_log!("[settings] Ignoring custom STT provider: {e}"),
+            }
+        }
+        registry.set_custom_providers(providers);
+    }
+    if let Some(options) = stt_provider_options {
+        // After the custom providers, so their options can be checked too.
+        let mut accepted = HashMap::new();
+        for (id, options) in options {
+            let options = options.normalized();
+            match check_provider_options(&registry, &id, &options) {
+                Ok(()) => {
+                    accepted.insert(id, options);
+                }
+                Err(e) => crate::app_log!("[settings] Ignoring STT options for {id}: {e}"),
+            }
+        }
+        registry.set_provider_options(accepted);
+    }
+    if let Some(chain) = stt_fallback_chain {
+        *state.stt_fallback_chain.lock().unwrap() = chain;
+    }
+    if let Some(race) = stt_race {
+        *state.stt_race.lock().unwrap() = race.normalized();
+    }
+    if let Some(escalation) = stt_escalation {
+        *state.stt_escalation.lock().unwrap() = escalation.normalized();
+    }
+    if let Some(routes) = stt_routes {
+        *state.stt_routes.lock().unwrap() = normalize_routes(routes);
+    }
+    if let Some(enabled) = offline_interim_transcript {
+        *state.offline_interim_transcript.lock().unwrap() = enabled;
+    }
+    if let Some(count) = vosk_max_alternatives {
+        vosk.set_max_alternatives(count);
+    }
+    if let Some(glossary) = glossary {
+        *state.glossary.lock().unwrap() = glossary.normalized();
+    }
+    if let Some(network) = network {
+        let network = network.normalized();
+        // Keep the settings either way; requests report the error until it is fixed.
+        if let Err(e) = network.client(crate::network::ALL_PROVIDERS, None) {
+            crate::app_log!("[settings] Invalid network settings: {e}");
+        }
+        *state.network.lock().unwrap() = network;
+    }
+    if refresh_glossary_grammar {
+        // Keep a "glossary" grammar in step with the terms, for Vosk grammar mode.
+        let language = state.stt_language.lock().unwrap().clone();
+        let terms = state.glossary.lock().unwrap().terms_for(&language);
+        if terms.is_empty() || vosk.set_grammar(GLOSSARY_GRAMMAR, &terms).is_err() {
+            vosk.remove_grammar(GLOSSARY_GRAMMAR);
+        }
+    }
+}
+
+/// Check advanced options before they are saved, so the settings page can
+/// show why e.g. diarization is not available for a provider.
+#[tauri::command]
+pub fn validate_stt_provider_options(
+    provider: String,
+    options: SttProviderOptions,
+    registry: State<'_, SttRegistry>,
+) -> Result<(), AppError> {
+    check_provider_options(&registry, &provider, &options.normalized())
+}
+
+fn check_provider_options(
+    registry: &SttRegistry,
+    provider_id: &str,
+    options: &SttProviderOptions,
+) -> Result<(), AppError> {
+    let provider = registry.resolve(provider_id)?;
+    // A selectable endpoint settles its protocol per request; the engine
+    // rejects the options then if it turns out to speak chat audio.
+    let provider = match options.engine() {
+        EngineKind::OpenAiTranscriptions if provider.capabilities.selectable_protocol => {
+            provider.with_protocol(CustomSttProtocol::Transcriptions)
+        }
+        _ => provider,
+    };
+    options.validate(&provider)
 }
 
 /// UI debug bridge from frontend.
@@ -132,36 +269,44 @@ pub struct ProviderHealth {
     pub has_key: bool,
     pub latency_ms: Option<u128>,
     pub status: String,
+    /// What the engine heard in the probe clip; `None` for enhancement checks.
+    pub transcript: Option<String>,
 }
 
 #[tauri::command]
 pub async fn check_provider_health(
+    app: AppHandle,
     section: String,
     provider: String,
     model: Option<String>,
     endpoint: Option<String>,
     keystore: State<'_, KeyStore>,
+    registry: State<'_, SttRegistry>,
+    state: State<'_, AppState>,
 ) -> Result<ProviderHealth, AppError> {
-    if provider == "vosk" {
-        return Ok(ProviderHealth {
-            ok: true,
-            has_key: true,
-            latency_ms: None,
-            status: "Local provider ready".into(),
-        });
+    let network = state.network.lock().unwrap().clone();
+    let stt_provider = if section == "enhancement" {
+        None
+    } else {
+        registry.resolve(&provider).ok()
+    };
+
+    if let Some(local) = stt_provider.as_ref().filter(|p| p.capabilities.local) {
+        let clip = health::probe_clip()?;
+        return Ok(check_stt_transcription(&app, local, &clip, model, endpoint).await);
     }
 
     if provider == "ollama" {
-        return check_local_http("http://127.0.0.1:11434/api/tags").await;
+        return check_local_http(&network, &provider, "http://127.0.0.1:11434/api/tags").await;
     }
     if provider == "lmstudio" {
-        return check_local_http("http://127.0.0.1:1234/v1/models").await;
+        return check_local_http(&network, &provider, "http://127.0.0.1:1234/v1/models").await;
     }
 
-    let key_provider = match provider.as_str() {
-        "openai_transcribe" => "openai",
-        _ => provider.as_str(),
-    };
+    let key_provider = stt_provider
+        .as_ref()
+        .and_then(|p| p.key_name.as_deref())
+        .unwrap_or(provider.as_str());
 
     let api_key = keystore.get_api_key(key_provider)?;
     if api_key.is_none() {
@@ -170,44 +315,62 @@ pub async fn check_provider_health(
             has_key: false,
             latency_ms: None,
             status: format!("Missing API key for {key_provider}"),
+            transcript: None,
         });
     }
+    if section != "enhancement" {
+        let Some(descriptor) = &stt_provider else {
+            return Ok(ProviderHealth {
+                ok: false,
+                has_key: true,
+                latency_ms: None,
+                status: format!("Unsupported STT provider: {provider}"),
+                transcript: None,
+            });
+        };
+        let clip = health::probe_clip()?;
+        return Ok(check_stt_transcription(&app, descriptor, &clip, model, endpoint).await);
+    }
 
     let api_key = api_key.unwrap_or_default();
     let timeout = Duration::from_secs(12);
     let started = Instant::now();
-    let client = reqwest::Client::builder()
+    let client = network
+        .client_builder(&provider, endpoint.as_deref())?
         .timeout(timeout)
         .build()
         .map_err(|e| AppError::Enhancement(format!("Health check client error: {e}")))?;
 
-    let response = if section == "enhancement" {
-        check_openai_compatible_chat(&client, &provider, &api_key, model, endpoint).await
-    } else {
-        check_stt_provider(&client, &provider, &api_key, model, endpoint).await
-    };
+    let response = check_openai_compatible_chat(&client, &provider, &api_key, model, endpoint).await;
 
     let elapsed_ms = started.elapsed().as_millis();
-    match response {
-        Ok(()) => Ok(ProviderHealth {
+    Ok(match response {
+        Ok(()) => ProviderHealth {
             ok: true,
             has_key: true,
             latency_ms: Some(elapsed_ms),
             status: format!("OK ({elapsed_ms} ms)"),
-        }),
-        Err(msg) => Ok(ProviderHealth {
+            transcript: None,
+        },
+        Err(msg) => ProviderHealth {
             ok: false,
             has_key: true,
             latency_ms: Some(elapsed_ms),
             status: msg,
-        }),
-    }
+            transcript: None,
+        },
+    })
 }
 
-async fn check_local_http(url: &str) -> Result<ProviderHealth, AppError> {
+async fn check_local_http(
+    network: &NetworkConfig,
+    provider: &str,
+    url: &str,
+) -> Result<ProviderHealth, AppError> {
     let timeout = Duration::from_secs(6);
     let started = Instant::now();
-    let client = reqwest::Client::builder()
+    let client = network
+        .client_builder(provider, Some(url))?
         .timeout(timeout)
         .build()
         .map_err(|e| AppError::Enhancement(format!("Health check client error: {e}")))?;
@@ -219,153 +382,125 @@ async fn check_local_http(url: &str) -> Result<ProviderHealth, AppError> {
             has_key: true,
             latency_ms: Some(elapsed_ms),
             status: format!("Local service ready ({elapsed_ms} ms)"),
+            transcript: None,
         }),
         Ok(r) => Ok(ProviderHealth {
             ok: false,
             has_key: true,
             latency_ms: Some(elapsed_ms),
             status: format!("Local service error: HTTP {}", r.status()),
+            transcript: None,
         }),
         Err(e) => Ok(ProviderHealth {
             ok: false,
             has_key: true,
             latency_ms: Some(elapsed_ms),
             status: format!("Local service unavailable: {e}"),
+            transcript: None,
         }),
     }
 }
 
-async fn check_stt_provider(
-    client: &reqwest::Client,
-    provider: &str,
-    api_key: &str,
+/// Transcribe the probe clip through the configured engine, the same way a
+/// recording is, and check that the phrase comes back.
+///
+/// Catches what a `/models` or text "ping" cannot: a model that does not
+/// take audio, a wrong endpoint protocol, or a local model that fails to run.
+/// One attempt within [`HEALTH_PROBE_TIMEOUT`], whatever the cloud timeout.
+async fn check_stt_transcription(
+    app: &AppHandle,
+    provider: &ProviderDescriptor,
+    clip: &ProbeClip,
     model: Option<String>,
     endpoint: Option<String>,
-) -> Result<(), String> {
+) -> ProviderHealth {
     let local_request_id = NEXT_HEALTH_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+    let options = TranscribeOptions {
+        language: health::PROBE_LANGUAGE.into(),
+        // The model setting is for cloud providers; local engines check the loaded model.
+        model: model.filter(|m| !m.trim().is_empty() && !provider.capabilities.local),
+        base_url: endpoint
+            .map(|v| v.trim().trim_end_matches('/').to_string())
+            .filter(|v| !v.is_empty()),
+        timeout_secs: HEALTH_PROBE_TIMEOUT.as_secs(),
+        retry: false,
+        live_result: None,
+        health_probe: true,
+    };
     let started = Instant::now();
+    let result = tokio::time::timeout(
+        HEALTH_PROBE_TIMEOUT,
+        transcribe_samples(app, provider, &clip.samples, options),
+    )
+    .await
+    .unwrap_or_else(|_| {
+        Err(AppError::Stt(format!("timed out after {}s", HEALTH_PROBE_TIMEOUT.as_secs())))
+    });
+    let elapsed_ms = started.elapsed().as_millis();
 
-    let endpoint = endpoint
-        .map(|v| v.trim().trim_end_matches('/').to_string())
-        .filter(|v| !v.is_empty());
-
-    if let Some(base_url) = endpoint {
-        let model = normalize_compat_model(provider, &base_url, model, "gemini-3-flash");
-        let body = serde_json::json!({
-          "model": model,
-          "messages": [{"role":"user","content":"ping"}],
-          "max_tokens": 1,
-          "temperature": 0
-        });
-        let r = client
-            .post(format!("{base_url}/chat/completions"))
-            .bearer_auth(api_key)
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| format!("OpenAI-compatible STT endpoint network error: {e}"))?;
-        let status = r.status();
-        let latency_ms = started.elapsed().as_millis();
-        let upstream_request_id = get_response_request_id(r.headers());
-        crate::app_log!(
-            "[healthcheck] section=voice request_id={} provider={} status={} latency_ms={} upstream_request_id={} endpoint_mode=custom",
-            local_request_id, provider, status, latency_ms, upstream_request_id
-        );
-        if status.is_success() {
-            return Ok(());
-        }
-        return Err(format!(
-            "OpenAI-compatible STT endpoint API error: HTTP {}",
-            status
-        ));
-    }
-
-    match provider {
-        "custom_openai_compatible" => Err(
-            "Custom OpenAI-compatible provider requires endpoint configuration".to_string(),
-        ),
-        "openai" | "openai_transcribe" => {
-            let r = client
-                .get("https://api.openai.com/v1/models")
-                .bearer_auth(api_key)
-                .send()
-                .await
-                .map_err(|e| format!("OpenAI network error: {e}"))?;
-            let status = r.status();
-            let latency_ms = started.elapsed().as_millis();
-            let upstream_request_id = get_response_request_id(r.headers());
-            crate::app_log!(
-                "[healthcheck] section=voice request_id={} provider={} status={} latency_ms={} upstream_request_id={} endpoint_mode=default",
-                local_request_id, provider, status, latency_ms, upstream_request_id
-            );
-            if status.is_success() {
-                Ok(())
-            } else {
-                Err(format!("OpenAI API error: HTTP {}", status))
-            }
-        }
-        "openrouter" => {
-            let r = client
-                .get("https://openrouter.ai/api/v1/models")
-                .bearer_auth(api_key)
-                .send()
-                .await
-                .map_err(|e| format!("OpenRouter network error: {e}"))?;
-            let status = r.status();
-            let latency_ms = started.elapsed().as_millis();
-            let upstream_request_id = get_response_request_id(r.headers());
-            crate::app_log!(
-                "[healthcheck] section=voice request_id={} provider={} status={} latency_ms={} upstream_request_id={} endpoint_mode=default",
-                local_request_id, provider, status, latency_ms, upstream_request_id
-            );
-            if status.is_success() {
-                Ok(())
-            } else {
-                Err(format!("OpenRouter API error: HTTP {}", status))
-            }
-        }
-        "elevenlabs" => {
-            let r = client
-                .get("https://api.elevenlabs.io/v1/user")
-                .header("xi-api-key", api_key)
-                .send()
-                .await
-                .map_err(|e| format!("ElevenLabs network error: {e}"))?;
-            let status = r.status();
-            let latency_ms = started.elapsed().as_millis();
-            let upstream_request_id = get_response_request_id(r.headers());
+    let result = match result {
+        Ok(result) => result,
+        Err(e) => {
             crate::app_log!(
-                "[healthcheck] section=voice request_id={} provider={} status={} latency_ms={} upstream_request_id={} endpoint_mode=default",
-                local_request_id, provider, status, latency_ms, upstream_request_id
+                "[healthcheck] section=voice request_id={} provider={} mode=transcribe latency_ms={} error={}",
+                local_request_id, provider.id, elapsed_ms, e
             );
-            if status.is_success() {
-                Ok(())
-            } else {
-                Err(format!("ElevenLabs API error: HTTP {}", status))
-            }
+            return ProviderHealth {
+                ok: false,
+                has_key: true,
+                latency_ms: Some(elapsed_ms),
+                status: format!("Probe transcription failed: {e}"),
+                transcript: None,
+            };
         }
-        "mistral" => {
-            let r = client
-                .get("https://api.mistral.ai/v1/models")
-                .bearer_auth(api_key)
-                .send()
-                .await
-                .map_err(|e| format!("Mistral network error: {e}"))?;
-            let status = r.status();
-            let latency_ms = started.elapsed().as_millis();
-            let upstream_request_id = get_response_request_id(r.headers());
-            crate::app_log!(
-                "[healthcheck] section=voice request_id={} provider={} status={} latency_ms={} upstream_request_id={} endpoint_mode=default",
-                local_request_id, provider, status, latency_ms, upstream_request_id
-            );
-            if status.is_success() {
-                Ok(())
-            } else {
-                Err(format!("Mistral API error: HTTP {}", status))
-            }
+    };
+    let heard = result.text.trim().to_string();
+    let verdict = health::assess(clip.phrase, &heard);
+    crate::app_log!(
+        "[healthcheck] section=voice request_id={} provider={} mode=transcribe latency_ms={} verdict={:?} chars={}",
+        local_request_id, provider.id, elapsed_ms, verdict, heard.chars().count()
+    );
+
+    // A local model that mishears English is usually trained for another language.
+    let hint = if provider.capabilities.local {
+        "check that the model is for English or multilingual"
+    } else {
+        "the model may not accept audio"
+    };
+    let (ok, status) = match verdict {
+        ProbeVerdict::Recognized => {
+            (true, format!("OK ({elapsed_ms} ms): heard \"{}\"", preview(&heard)))
         }
-        _ => Err(format!("Unsupported STT provider: {provider}")),
+        ProbeVerdict::Empty => (
+            false,
+            format!("{} returned no text for the probe clip; {hint}", provider.label),
+        ),
+        ProbeVerdict::Mismatch { .. } => (
+            false,
+            format!(
+                "{} did not transcribe the probe clip (heard \"{}\"); {hint}",
+                provider.label,
+                preview(&heard)
+            ),
+        ),
+    };
+    ProviderHealth {
+        ok,
+        has_key: true,
+        latency_ms: Some(elapsed_ms),
+        status,
+        transcript: Some(heard),
+    }
+}
+
+/// First words of a transcript, for status lines.
+fn preview(text: &str) -> String {
+    const MAX_CHARS: usize = 60;
+    if text.chars().count() <= MAX_CHARS {
+        return text.to_string();
     }
+    let cut: String = text.chars().take(MAX_CHARS).collect();
+    format!("{}…", cut.trim_end())
 }
 
 async fn check_openai_compatible_chat(
@@ -414,7 +549,7 @@ async fn check_openai_compatible_chat(
         .map_err(|e| format!("{provider} network error: {e}"))?;
     let status = r.status();
     let latency_ms = started.elapsed().as_millis();
-    let upstream_request_id = get_response_request_id(r.headers());
+    let upstream_request_id = response_request_id(r.headers());
     let endpoint_mode = if endpoint.is_some() { "custom" } else { "default" };
     crate::app_log!(
         "[healthcheck] section=enhancement request_id={} provider={} status={} latency_ms={} upstream_request_id={} endpoint_mode={}",
@@ -427,18 +562,6 @@ async fn check_openai_compatible_chat(
     }
 }
 
-fn get_response_request_id(headers: &reqwest::header::HeaderMap) -> String {
-    const CANDIDATES: [&str; 4] = ["x-request-id", "request-id", "x-correlation-id", "trace-id"];
-    for key in CANDIDATES {
-        if let Some(value) = headers.get(key).and_then(|v| v.to_str().ok()) {
-            if !value.trim().is_empty() {
-                return value.to_string();
-            }
-        }
-    }
-    "n/a".to_string()
-}
-
 fn normalize_compat_model(
     provider: &str,
     base_url: &str,
@@ -1,81 +1,747 @@
-use tauri::State;
+use std::time::{Duration, Instant};
 
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::audio::wav;
+use crate::commands::model_manager;
+use crate::commands::recording::stt_selection;
 use crate::error::AppError;
+use crate::network::HttpClients;
 use crate::security::keystore::KeyStore;
-use crate::stt::elevenlabs::ElevenLabsEngine;
-use crate::stt::mistral::MistralEngine;
-use crate::stt::openai_whisper::OpenAiWhisperEngine;
-use crate::stt::openrouter_audio::OpenRouterAudioEngine;
-use crate::stt::vosk_engine::VoskManager;
+use crate::state::AppState;
+use crate::stt::chunking;
 use crate::stt::converter;
-use crate::stt::{CloudSttEngine, SttConfig, SttProvider, SttResult};
+use crate::stt::escalation::EscalationConfig;
+use crate::stt::failover::{self, FallbackStep};
+use crate::stt::glossary;
+use crate::stt::http::RetryPolicy;
+use crate::stt::language;
+use crate::stt::probe;
+use crate::stt::race::{self, RaceConfig, Racer};
+use crate::stt::registry::{CustomSttProtocol, EngineKind, ProviderDescriptor, SttRegistry};
+use crate::stt::routing::{self, LanguageRoute};
+use crate::stt::sherpa_engine::SherpaManager;
+use crate::stt::vosk_engine::{self, VoskManager};
+use crate::stt::whisper_engine::WhisperManager;
+use crate::stt::{self, CloudSttEngine, SttConfig, SttResult};
+
+const SAMPLE_RATE: u32 = 16000;
+const PROBE_TIMEOUT_SECS: u64 = 15;
+
+/// Per-call settings for [`transcribe_samples`].
+pub struct TranscribeOptions {
+    pub language: String,
+    pub model: Option<String>,
+    /// User-configured endpoint; only used by providers that allow overriding it.
+    pub base_url: Option<String>,
+    pub timeout_secs: u64,
+    /// Retry failed cloud requests within the timeout; off for one-shot checks.
+    pub retry: bool,
+    /// Transcript already produced by the live Vosk stream, reused instead of a second pass.
+    pub live_result: Option<SttResult>,
+    /// Health-check probe: Vosk ignores its grammar mode, and a prewarmed
+    /// connection is left for the next dictation.
+    pub health_probe: bool,
+}
 
 /// Transcribe audio data using the specified STT provider.
 ///
-/// For cloud providers, `audio_data` is WAV-encoded audio.
-/// For Vosk, `audio_data` is raw PCM i16 LE samples at 16kHz.
+/// `audio_data` is either a WAV file or raw PCM i16 LE samples at 16kHz,
+/// for every provider.
 #[tauri::command]
 pub async fn transcribe_audio(
+    app: AppHandle,
     audio_data: Vec<u8>,
-    provider: SttProvider,
+    provider: String,
     language: String,
     model: Option<String>,
-    keystore: State<'_, KeyStore>,
-    vosk: State<'_, VoskManager>,
+    state: State<'_, AppState>,
+    registry: State<'_, SttRegistry>,
 ) -> Result<SttResult, AppError> {
     crate::app_log!(
-        "[stt] transcribe_audio provider={:?} language={} model={:?}",
+        "[stt] transcribe_audio provider={} language={} model={:?}",
         provider, language, model
     );
-    let needs_s2t = converter::needs_s2t_conversion(&language);
+    let descriptor = registry.resolve(&provider)?;
+    let samples = wav::decode_pcm16(&audio_data)?;
 
-    let config = SttConfig {
+    // The configured endpoint belongs to the selected provider, not to ad-hoc calls for others.
+    let base_url = if *state.stt_provider.lock().unwrap() == provider {
+        state.stt_base_url.lock().unwrap().clone()
+    } else {
+        None
+    };
+    let options = TranscribeOptions {
         language,
-        sample_rate: 16000,
+        model,
+        base_url,
+        timeout_secs: *state.cloud_timeout_secs.lock().unwrap(),
+        retry: true,
+        live_result: None,
+        health_probe: false,
     };
 
-    let mut result = match provider {
-        SttProvider::Vosk => {
-            vosk.transcribe(&audio_data, config.sample_rate as f32)
+    transcribe_samples(&app, &descriptor, &samples, options).await
+}
+
+/// Probe the custom OpenAI-compatible endpoint for the protocol it speaks.
+///
+/// Uses the configured endpoint and model unless given; the result is
+/// remembered for transcriptions in auto mode.
+#[tauri::command]
+pub async fn detect_stt_protocol(
+    base_url: Option<String>,
+    model: Option<String>,
+    state: State<'_, AppState>,
+    keystore: State<'_, KeyStore>,
+    clients: State<'_, HttpClients>,
+) -> Result<CustomSttProtocol, AppError> {
+    let base_url = base_url
+        .or_else(|| state.stt_base_url.lock().unwrap().clone())
+        .map(|v| v.trim().trim_end_matches('/').to_string())
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| AppError::Stt("Configure the endpoint before detecting its protocol.".into()))?;
+    let model = model
+        .or_else(|| state.stt_model.lock().unwrap().clone())
+        .filter(|m| !m.trim().is_empty());
+    let api_key = get_api_key(&keystore, "custom_openai_compatible")?;
+    let network = state.network.lock().unwrap().clone();
+    let client = clients.client(&network, "custom_openai_compatible", Some(&base_url))?;
+
+    detect_and_remember(
+        &state,
+        &client,
+        &base_url,
+        &api_key,
+        model.as_deref(),
+        Duration::from_secs(PROBE_TIMEOUT_SECS),
+    )
+    .await
+}
+
+/// Protocol for a provider whose endpoint may speak either one: the
+/// configured choice, else a remembered or fresh detection.
+async fn resolve_protocol(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    api_key: &str,
+    base_url: Option<&str>,
+    model: Option<&str>,
+    timeout: Duration,
+) -> Result<CustomSttProtocol, AppError> {
+    let state = app.state::<AppState>();
+    if let Some(protocol) = *state.stt_protocol.lock().unwrap() {
+        return Ok(protocol);
+    }
+    // Without an endpoint the engine build reports the missing setting.
+    let Some(base_url) = base_url else {
+        return Ok(CustomSttProtocol::ChatAudio);
+    };
+    if let Some(protocol) = state.detected_stt_protocols.lock().unwrap().get(base_url) {
+        return Ok(*protocol);
+    }
+
+    detect_and_remember(&state, client, base_url, api_key, model, timeout).await
+}
+
+async fn detect_and_remember(
+    state: &AppState,
+    client: &reqwest::Client,
+    base_url: &str,
+    api_key: &str,
+    model: Option<&str>,
+    timeout: Duration,
+) -> Result<CustomSttProtocol, AppError> {
+    let protocol = tokio::time::timeout(
+        timeout,
+        probe::detect_protocol(client, base_url, api_key, model),
+    )
+    .await
+    .map_err(|_| AppError::Stt("Protocol detection timed out.".into()))??;
+    crate::app_log!("[stt] detected protocol={protocol:?} base_url={base_url}");
+    state
+        .detected_stt_protocols
+        .lock()
+        .unwrap()
+        .insert(base_url.to_string(), protocol);
+    Ok(protocol)
+}
+
+/// List built-in and declared STT providers.
+#[tauri::command]
+pub fn list_stt_providers(registry: State<'_, SttRegistry>) -> Vec<ProviderDescriptor> {
+    registry.providers()
+}
+
+/// Transcript plus the provider that produced it.
+pub struct TranscribeOutcome {
+    pub result: SttResult,
+    pub provider: String,
+}
+
+/// Transcribe with the selected provider, falling back along `fallbacks` on error.
+///
+/// `options` describe the primary provider; fallback steps use their own
+/// model and timeout and the provider's default endpoint. With `race`, the
+/// primary attempt is raced against a second provider.
+pub async fn transcribe_with_fallback(
+    app: &AppHandle,
+    provider: &str,
+    samples: &[i16],
+    options: TranscribeOptions,
+    fallbacks: &[FallbackStep],
+    race: Option<&RaceConfig>,
+) -> Result<TranscribeOutcome, AppError> {
+    let TranscribeOptions {
+        language,
+        model,
+        base_url,
+        timeout_secs,
+        mut live_result,
+        ..
+    } = options;
+    let steps = failover::plan(provider, model, timeout_secs, fallbacks);
+    let registry = app.state::<SttRegistry>();
+    let mut failures = Vec::new();
+
+    for (index, step) in steps.iter().enumerate() {
+        if index > 0 {
+            let _ = app.emit(
+                "recording:status",
+                serde_json::json!({
+                    "status": "processing",
+                    "message": format!("{} failed, falling back to {}...", steps[index - 1].provider, step.provider),
+                }),
+            );
         }
-        SttProvider::ElevenLabs => {
-            let api_key = get_api_key(&keystore, "elevenlabs")?;
-            let engine = ElevenLabsEngine::new(api_key, model);
-            engine.transcribe(&audio_data, &config).await
+
+        let racer = race.filter(|r| step.primary && r.provider != step.provider);
+        // Bounds local engines too, so one stuck engine cannot stall the chain.
+        let budget = step.budget(samples.len() as f32 / SAMPLE_RATE as f32);
+        let attempt = tokio::time::timeout(budget, async {
+            match registry.resolve(&step.provider) {
+                Ok(descriptor) => {
+                    let options = TranscribeOptions {
+                        language: language.clone(),
+                        model: step.model.clone(),
+                        base_url: if step.primary { base_url.clone() } else { None },
+                        timeout_secs: step.timeout_secs,
+                        retry: true,
+                        live_result: if descriptor.engine == EngineKind::Vosk {
+                            live_result.take()
+                        } else {
+                            None
+                        },
+                        health_probe: false,
+                    };
+                    match racer {
+                        Some(racer) => {
+                            transcribe_raced(
+                                app,
+                                &descriptor,
+                                samples,
+                                options,
+                                racer,
+                                &mut live_result,
+                            )
+                            .await
+                        }
+                        None => transcribe_samples(app, &descriptor, samples, options)
+                            .await
+                            .map(|result| (result, step.provider.clone())),
+                    }
+                }
+                Err(e) => Err(e),
+            }
+        })
+        .await
+        .unwrap_or_else(|_| {
+            Err(AppError::Stt(format!(
+                "{} timed out after {}s",
+                step.provider,
+                budget.as_secs()
+            )))
+        });
+
+        match attempt {
+            Ok((result, provider)) => {
+                if index > 0 {
+                    crate::app_log!(
+                        "[stt] failover provider={} step={} succeeded",
+                        step.provider, index
+                    );
+                }
+                return Ok(TranscribeOutcome { result, provider });
+            }
+            // Without fallbacks, surface the provider's own error unchanged.
+            Err(e) if steps.len() == 1 => return Err(e),
+            Err(e) => {
+                crate::app_log!(
+                    "[stt] failover provider={} step={} failed: {e}",
+                    step.provider, index
+                );
+                failures.push(format!("{}: {e}", step.provider));
+            }
         }
-        SttProvider::OpenAI => {
-            let api_key = get_api_key(&keystore, "openai")?;
-            let engine = OpenAiWhisperEngine::new(api_key, model, None);
-            engine.transcribe(&audio_data, &config).await
+    }
+
+    Err(AppError::Stt(format!(
+        "All STT providers failed. {}",
+        failures.join("; ")
+    )))
+}
+
+/// Race `primary` against the configured racer and return the winning
+/// transcript and provider id. The live Vosk transcript goes to whichever
+/// side runs Vosk. Without a usable racer, `primary` runs alone.
+async fn transcribe_raced(
+    app: &AppHandle,
+    primary: &ProviderDescriptor,
+    samples: &[i16],
+    options: TranscribeOptions,
+    racer: &RaceConfig,
+    live_result: &mut Option<SttResult>,
+) -> Result<(SttResult, String), AppError> {
+    let secondary = match app.state::<SttRegistry>().resolve(&racer.provider) {
+        Ok(secondary) => secondary,
+        Err(e) => {
+            crate::app_log!("[stt] race provider={} skipped: {e}", racer.provider);
+            let result = transcribe_samples(app, primary, samples, options).await?;
+            return Ok((result, primary.id.clone()));
         }
-        SttProvider::OpenAITranscribe => {
-            let api_key = get_api_key(&keystore, "openai")?;
-            let transcribe_model = model.or_else(|| Some("gpt-4o-mini-transcribe".to_string()));
-            let engine = OpenAiWhisperEngine::new(api_key, transcribe_model, None);
-            engine.transcribe(&audio_data, &config).await
+    };
+    let secondary_options = TranscribeOptions {
+        language: options.language.clone(),
+        model: racer.model.clone(),
+        base_url: None,
+        timeout_secs: racer.timeout_secs.unwrap_or(options.timeout_secs),
+        retry: true,
+        live_result: if secondary.engine == EngineKind::Vosk {
+            live_result.take()
+        } else {
+            None
+        },
+        health_probe: false,
+    };
+    crate::app_log!(
+        "[stt] race primary={} secondary={} prefer_primary_ms={}",
+        primary.id, secondary.id, racer.prefer_primary_ms
+    );
+
+    let started = Instant::now();
+    let outcome = race::race(
+        async {
+            transcribe_samples(app, primary, samples, options)
+                .await
+                .map_err(|e| AppError::Stt(format!("{}: {e}", primary.id)))
+        },
+        async {
+            transcribe_samples(app, &secondary, samples, secondary_options)
+                .await
+                .map_err(|e| AppError::Stt(format!("{}: {e}", secondary.id)))
+        },
+        racer.prefer_primary_for(),
+    )
+    .await;
+    let (winner, loser) = match outcome.winner {
+        Racer::Primary => (&primary.id, &secondary.id),
+        Racer::Secondary => (&secondary.id, &primary.id),
+    };
+    match &outcome.result {
+        Ok(_) => crate::app_log!(
+            "[stt] race winner={winner} cancelled={loser} elapsed_ms={}",
+            started.elapsed().as_millis()
+        ),
+        Err(e) => crate::app_log!("[stt] race failed: {e}"),
+    }
+    Ok((outcome.result?, winner.clone()))
+}
+
+/// Re-transcribe with the escalation provider when a local engine's
+/// transcript looks unreliable. The local transcript is kept when the
+/// escalation fails or hears nothing either.
+pub async fn escalate_if_unsure(
+    app: &AppHandle,
+    outcome: TranscribeOutcome,
+    samples: &[i16],
+    language: &str,
+    timeout_secs: u64,
+    escalation: &EscalationConfig,
+) -> TranscribeOutcome {
+    let registry = app.state::<SttRegistry>();
+    let local = registry
+        .resolve(&outcome.provider)
+        .is_ok_and(|p| p.capabilities.local);
+    if !local || outcome.provider == escalation.provider {
+        return outcome;
+    }
+    let Some(reason) = escalation.reason(&outcome.result, samples, SAMPLE_RATE) else {
+        return outcome;
+    };
+    let provider = match registry.resolve(&escalation.provider) {
+        Ok(provider) => provider,
+        Err(e) => {
+            crate::app_log!("[stt] escalation provider={} skipped: {e}", escalation.provider);
+            return outcome;
         }
-        SttProvider::OpenRouter => {
-            let api_key = get_api_key(&keystore, "openrouter")?;
-            let engine = OpenRouterAudioEngine::new(api_key, model, None);
-            engine.transcribe(&audio_data, &config).await
+    };
+
+    crate::app_log!(
+        "[stt] escalation from={} to={} reason={reason}",
+        outcome.provider, provider.id
+    );
+    let _ = app.emit(
+        "recording:status",
+        serde_json::json!({
+            "status": "processing",
+            "message": format!("Unsure local transcript ({reason}), asking {}...", provider.label),
+        }),
+    );
+    let options = TranscribeOptions {
+        language: language.to_string(),
+        model: escalation.model.clone(),
+        base_url: None,
+        timeout_secs,
+        retry: true,
+        live_result: None,
+        health_probe: false,
+    };
+    match transcribe_samples(app, &provider, samples, options).await {
+        Ok(result) if !result.text.trim().is_empty() => TranscribeOutcome {
+            result,
+            provider: provider.id.clone(),
+        },
+        Ok(_) => {
+            crate::app_log!("[stt] escalation provider={} returned no text", provider.id);
+            outcome
         }
-        SttProvider::CustomOpenAiCompatible => {
-            let api_key = get_api_key(&keystore, "custom_openai_compatible")?;
-            Err(AppError::Stt(format!(
-                "Custom OpenAI-compatible STT requires endpoint in current recording pipeline. Provider key exists: {}",
-                !api_key.is_empty()
-            )))
+        Err(e) => {
+            crate::app_log!("[stt] escalation provider={} failed: {e}", provider.id);
+            outcome
+        }
+    }
+}
+
+/// In auto mode, send the audio to the route for the detected language when
+/// that route names a different provider. Returns the route that matched;
+/// the first transcript is kept if the routed provider fails.
+pub async fn route_detected_language(
+    app: &AppHandle,
+    outcome: TranscribeOutcome,
+    samples: &[i16],
+    routes: &[LanguageRoute],
+    timeout_secs: u64,
+) -> (TranscribeOutcome, Option<LanguageRoute>) {
+    if outcome.result.text.trim().is_empty() {
+        return (outcome, None);
+    }
+    let detected = language::resolve(
+        language::AUTO,
+        outcome.result.language_detected.as_deref(),
+        &outcome.result.text,
+    );
+    let Some(route) = routing::route_for(routes, &detected).cloned() else {
+        return (outcome, None);
+    };
+    if !route.routes_stt() || route.provider == outcome.provider {
+        return (outcome, Some(route));
+    }
+    let provider = match app.state::<SttRegistry>().resolve(&route.provider) {
+        Ok(provider) => provider,
+        Err(e) => {
+            crate::app_log!("[stt] route language={detected} provider={} skipped: {e}", route.provider);
+            return (outcome, Some(route));
+        }
+    };
+
+    crate::app_log!(
+        "[stt] route language={detected} from={} to={}",
+        outcome.provider, provider.id
+    );
+    let _ = app.emit(
+        "recording:status",
+        serde_json::json!({
+            "status": "processing",
+            "message": format!("Detected {detected}, transcribing with {}...", provider.label),
+        }),
+    );
+    // The route's own code keeps a configured variant such as zh-TW.
+    let options = TranscribeOptions {
+        language: route.language.clone(),
+        model: route.model.clone(),
+        base_url: route.base_url.clone(),
+        timeout_secs,
+        retry: true,
+        live_result: None,
+        health_probe: false,
+    };
+    match transcribe_samples(app, &provider, samples, options).await {
+        Ok(mut result) if !result.text.trim().is_empty() => {
+            result.language_detected.get_or_insert(detected);
+            let outcome = TranscribeOutcome {
+                result,
+                provider: provider.id.clone(),
+            };
+            (outcome, Some(route))
+        }
+        Ok(_) => {
+            crate::app_log!("[stt] route provider={} returned no text", provider.id);
+            (outcome, Some(route))
+        }
+        Err(e) => {
+            crate::app_log!("[stt] route provider={} failed: {e}", provider.id);
+            (outcome, Some(route))
+        }
+    }
+}
+
+/// Whether the provider's endpoint answers; local providers always do.
+pub async fn provider_reachable(app: &AppHandle, provider_id: &str, base_url: Option<&str>) -> bool {
+    let Ok(provider) = app.state::<SttRegistry>().resolve(provider_id) else {
+        return false;
+    };
+    if provider.capabilities.local {
+        return true;
+    }
+    // Without a known origin there is nothing to probe; let the request decide.
+    let Some(origin) = provider.endpoint_origin(base_url) else {
+        return true;
+    };
+    let network = app.state::<AppState>().network.lock().unwrap().clone();
+    app.state::<HttpClients>()
+        .reachable(&network, &provider.id, &origin)
+        .await
+}
+
+/// Open connections to the cloud providers this dictation may use while
+/// the user speaks, so the upload on release skips DNS, TCP and TLS setup.
+///
+/// Covers the selected provider, or the route for a fixed language, plus
+/// the racer and the fallback chain. In auto mode, routed providers are
+/// warmed too, since the detected language may re-transcribe with one.
+pub async fn prewarm_dictation_providers(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let language = state.stt_language.lock().unwrap().clone();
+    let (selection, _) = stt_selection(&state, &app.state::<SttRegistry>(), &language);
+    let mut targets = vec![(selection.provider.clone(), selection.base_url)];
+    if let Some(race) = state.stt_race.lock().unwrap().as_ref() {
+        targets.push((race.provider.clone(), None));
+    }
+    let fallbacks = state.stt_fallback_chain.lock().unwrap().clone();
+    targets.extend(
+        failover::plan(&selection.provider, None, 0, &fallbacks)
+            .into_iter()
+            .skip(1)
+            .map(|step| (step.provider, None)),
+    );
+    if language::is_auto(&language) {
+        let routes = state.stt_routes.lock().unwrap();
+        targets.extend(
+            routes
+                .iter()
+                .filter(|r| r.routes_stt())
+                .map(|r| (r.provider.clone(), r.base_url.clone())),
+        );
+    }
+    let network = state.network.lock().unwrap().clone();
+
+    let registry = app.state::<SttRegistry>();
+    let mut origins: Vec<(String, String)> = Vec::new();
+    for (id, base_url) in targets {
+        let Ok(provider) = registry.resolve(&id) else {
+            continue;
+        };
+        if origins.iter().any(|(warmed, _)| *warmed == provider.id) {
+            continue;
+        }
+        if let Some(origin) = provider.endpoint_origin(base_url.as_deref()) {
+            origins.push((provider.id, origin));
+        }
+    }
+    let clients = app.state::<HttpClients>();
+    futures_util::future::join_all(
+        origins
+            .iter()
+            .map(|(provider, origin)| clients.prewarm(&network, provider, origin)),
+    )
+    .await;
+}
+
+/// Transcribe 16kHz mono samples with any registered provider.
+///
+/// Every entry point goes through here so endpoints, timeouts and the
+/// Traditional Chinese conversion behave the same everywhere.
+pub async fn transcribe_samples(
+    app: &AppHandle,
+    provider: &ProviderDescriptor,
+    samples: &[i16],
+    options: TranscribeOptions,
+) -> Result<SttResult, AppError> {
+    let user_glossary = app.state::<AppState>().glossary.lock().unwrap().clone();
+    let config = SttConfig {
+        sample_rate: SAMPLE_RATE,
+        glossary: user_glossary.terms_for(&options.language),
+        language: options.language,
+    };
+
+    // A route can name the local model to use; the live transcript came from
+    // the model loaded before it.
+    let mut live_result = options.live_result;
+    if let Some(model_id) = options.model.as_deref().filter(|_| provider.capabilities.local) {
+        if model_manager::select_local_model(app, provider.engine, model_id).await? {
+            live_result = None;
+        }
+    }
+
+    let mut result = match provider.engine {
+        EngineKind::Vosk => {
+            let vosk = app.state::<VoskManager>();
+            if !vosk.is_loaded() {
+                return Err(AppError::Stt(
+                    "Vosk model not loaded. Please download and load a model in Settings.".into(),
+                ));
+            }
+            if let Some(live) = live_result {
+                crate::app_log!("[stt] Using live Vosk transcript");
+                live
+            } else {
+                crate::app_log!("[stt] Transcribing via Vosk...");
+                // A grammar would force the probe phrase onto its own phrases.
+                let stream = if options.health_probe {
+                    vosk.start_free_stream(SAMPLE_RATE as f32)?
+                } else {
+                    vosk.start_stream(SAMPLE_RATE as f32)?
+                };
+                let samples = samples.to_vec();
+                stt::run_blocking("Vosk", move |stop| stream.run(&samples, stop)).await?
+            }
+        }
+        EngineKind::Whisper => {
+            let whisper = app.state::<WhisperManager>();
+            if !whisper.is_loaded() {
+                return Err(AppError::Stt(
+                    "Whisper model not loaded. Please download and load a model in Settings."
+                        .into(),
+                ));
+            }
+            crate::app_log!("[stt] Transcribing via local Whisper...");
+            let job = whisper.prepare(&config.language, &config.glossary)?;
+            let samples = samples.to_vec();
+            stt::run_blocking("Whisper", move |stop| job.run(&samples, stop)).await?
+        }
+        EngineKind::Sherpa => {
+            let sherpa = app.state::<SherpaManager>();
+            if !sherpa.is_loaded() {
+                return Err(AppError::Stt(
+                    "sherpa-onnx model not loaded. Please download and load a model in Settings."
+                        .into(),
+                ));
+            }
+            crate::app_log!("[stt] Transcribing via sherpa-onnx...");
+            let job = sherpa.prepare()?;
+            let samples = samples.to_vec();
+            stt::run_blocking("sherpa-onnx", move |stop| {
+                job.run(&samples, SAMPLE_RATE, stop)
+            })
+            .await?
+        }
+        EngineKind::ElevenLabs
+        | EngineKind::OpenAiTranscriptions
+        | EngineKind::ChatAudio
+        | EngineKind::Mistral => {
+            crate::app_log!("[stt] Transcribing via {}...", provider.label);
+            let keystore = app.state::<KeyStore>();
+            let key_name = provider.key_name.as_deref().unwrap_or(&provider.id);
+            let api_key = get_api_key(&keystore, key_name)?;
+            // Per request: chunked uploads of long recordings are bounded by
+            // the step budget and the transcription stage deadline instead.
+            let request_timeout = Duration::from_secs(options.timeout_secs.clamp(5, 180));
+            let retry = if options.retry {
+                RetryPolicy::within(request_timeout)
+            } else {
+                RetryPolicy::once(request_timeout)
+            };
+            let network = app.state::<AppState>().network.lock().unwrap().clone();
+            let clients = app.state::<HttpClients>();
+            let endpoint = provider.endpoint_origin(options.base_url.as_deref());
+            let client = clients.client(&network, &provider.id, endpoint.as_deref())?;
+            // A probe rides on the warmed connection without claiming it, so
+            // the dictation it was warmed for still counts as prewarmed.
+            let warm = if options.health_probe {
+                None
+            } else {
+                clients.take_prewarm(&provider.id)
+            };
+            if let Some(warm) = warm {
+                crate::app_log!(
+                    "[network] provider={} upload reuses connection warmed {}ms ago",
+                    provider.id,
+                    warm.at.elapsed().as_millis()
+                );
+            }
+            let provider = if provider.capabilities.selectable_protocol {
+                let protocol = resolve_protocol(
+                    app,
+                    &client,
+                    &api_key,
+                    options.base_url.as_deref(),
+                    options.model.as_deref(),
+                    request_timeout,
+                )
+                .await?;
+                provider.clone().with_protocol(protocol)
+            } else {
+                provider.clone()
+            };
+            let engine = provider
+                .build_cloud_engine(api_key, options.model, options.base_url)?
+                .with_retry_policy(retry)
+                .with_client(client);
+            let started = Instant::now();
+            let result = chunking::transcribe_chunked(&engine, samples, &config, request_timeout).await;
+            // Compare against cold uploads, together with `ttfb_ms` on the
+            // `[stt-http]` lines, to see what prewarming saves.
+            crate::app_log!(
+                "[network] provider={} connection={} upload_ms={}",
+                provider.id,
+                if warm.is_some() { "prewarmed" } else { "cold" },
+                started.elapsed().as_millis()
+            );
+            result?
         }
-        SttProvider::Mistral => {
-            let api_key = get_api_key(&keystore, "mistral")?;
-            let engine = MistralEngine::new(api_key, model);
-            engine.transcribe(&audio_data, &config).await
+    };
+
+    // In auto mode, conversion and lexicon follow the language of this utterance.
+    let language = language::resolve(
+        &config.language,
+        result.language_detected.as_deref(),
+        &result.text,
+    );
+    if language::is_auto(&config.language) {
+        crate::app_log!(
+            "[stt] auto language detected={:?} resolved={}",
+            result.language_detected, language
+        );
+        result.language_detected = Some(language.clone());
+    }
+
+    if provider.engine == EngineKind::Vosk {
+        result.text = vosk_engine::join_cjk_tokens(&result.text, &language);
+        for alternative in &mut result.alternatives {
+            alternative.text = vosk_engine::join_cjk_tokens(&alternative.text, &language);
         }
-    }?;
+    }
+    // Vosk in free mode, sherpa-onnx and Mistral take no vocabulary hint;
+    // restore glossary spelling in their output instead. This runs after the
+    // CJK join so Chinese and Japanese terms are matched in unspaced text.
+    if matches!(provider.engine, EngineKind::Vosk | EngineKind::Sherpa | EngineKind::Mistral) {
+        result.text = glossary::apply_spelling(&result.text, &user_glossary.terms_for(&language));
+    }
 
-    // Convert Simplified → Traditional Chinese for zh-TW users
-    if needs_s2t {
-        result.text = converter::simplified_to_traditional(&result.text);
+    // Convert Simplified → Traditional Chinese when zh-TW is configured;
+    // auto mode resolves Chinese to plain "zh" and keeps the engine's script.
+    if converter::needs_s2t_conversion(&language) {
+        converter::result_to_traditional(&mut result);
     }
 
     Ok(result)
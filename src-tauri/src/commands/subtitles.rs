@@ -0,0 +1,52 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::audio::wav;
+use crate::error::AppError;
+use crate::stt::subtitle::{self, SubtitleFormat, SubtitleOptions};
+use crate::stt::transcript::Transcript;
+
+/// Recordings are saved as 16kHz mono WAV.
+const SAMPLE_RATE: f32 = 16000.0;
+
+/// Write subtitles for a saved recording; returns the file path.
+///
+/// Reads the timed transcript saved next to `audio_path`, or for recordings
+/// without one, shows the saved `.txt` text over the WAV's duration. Writes
+/// `<name>.srt` or `<name>.vtt` beside it unless `path` is given.
+#[tauri::command]
+pub fn export_subtitles(
+    audio_path: String,
+    format: SubtitleFormat,
+    options: Option<SubtitleOptions>,
+    path: Option<String>,
+) -> Result<String, AppError> {
+    let audio_path = Path::new(&audio_path);
+    let transcript = load_transcript(audio_path)?;
+
+    let path = match path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => PathBuf::from(path),
+        None => audio_path.with_extension(format.extension()),
+    };
+    let options = options.unwrap_or_default();
+    fs::write(&path, subtitle::render(&transcript, format, &options))?;
+    crate::app_log!("[subtitles] Exported {}", path.display());
+    Ok(path.display().to_string())
+}
+
+fn load_transcript(audio_path: &Path) -> Result<Transcript, AppError> {
+    let sidecar = Transcript::sidecar_path(audio_path);
+    if sidecar.exists() {
+        return Transcript::load(&sidecar);
+    }
+
+    let text = fs::read_to_string(audio_path.with_extension("txt")).unwrap_or_default();
+    if text.trim().is_empty() {
+        return Err(AppError::Io(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("No transcript for {}", audio_path.display()),
+        )));
+    }
+    let samples = wav::decode_pcm16(&fs::read(audio_path)?)?;
+    Ok(Transcript::untimed(&text, samples.len() as f32 / SAMPLE_RATE))
+}
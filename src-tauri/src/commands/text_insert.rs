@@ -1,17 +1,29 @@
 use tauri::{AppHandle, State};
 
+use crate::cancel::{Processing, Stage};
 use crate::error::AppError;
 use crate::state::AppState;
 use crate::text_insertion;
 
 /// Insert text at the current cursor position.
 /// Returns `true` if auto-pasted, `false` if text is on clipboard only.
+///
+/// Bounded by the insertion deadline and stopped by `cancel_processing`.
 #[tauri::command]
 pub async fn insert_text_at_cursor(
     app: AppHandle,
     state: State<'_, AppState>,
+    processing: State<'_, Processing>,
     text: String,
 ) -> Result<bool, AppError> {
+    let deadlines = *state.stage_deadlines.lock().unwrap();
+    processing
+        .current()
+        .run(Stage::Insertion, &deadlines, insert_into_target(&app, &state, &text))
+        .await
+}
+
+async fn insert_into_target(app: &AppHandle, state: &AppState, text: &str) -> Result<bool, AppError> {
     let self_bundle_id = app.config().identifier.clone();
     crate::app_log!("[insert] app bundle identifier: {self_bundle_id}");
     if let Ok(exe) = std::env::current_exe() {
@@ -33,14 +45,14 @@ pub async fn insert_text_at_cursor(
         tokio::time::sleep(std::time::Duration::from_millis(250)).await;
     }
 
-    let mut auto_pasted = text_insertion::insert_text_at_cursor(&text).await?;
+    let mut auto_pasted = text_insertion::insert_text_at_cursor(text).await?;
     if !auto_pasted {
         crate::app_log!("[insert] first direct insert attempt returned clipboard-only, retrying once");
         if let Some(bundle_id) = target_bundle.as_deref() {
             let _ = activate_app_by_bundle_id(bundle_id);
         }
         tokio::time::sleep(std::time::Duration::from_millis(300)).await;
-        auto_pasted = text_insertion::insert_text_at_cursor(&text).await?;
+        auto_pasted = text_insertion::insert_text_at_cursor(text).await?;
     }
 
     if let Some(bundle_id) = target_bundle.as_deref() {
@@ -24,6 +24,12 @@ impl OllamaEngine {
     pub fn lm_studio() -> Self {
         Self::new(Some("http://localhost:1234/v1".to_string()))
     }
+
+    /// Use a client built from the shared network settings.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
 }
 
 impl EnhancementEngine for OllamaEngine {
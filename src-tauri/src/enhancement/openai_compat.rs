@@ -39,6 +39,12 @@ impl OpenAiCompatEngine {
         Self::new(api_key, base_url.to_string())
     }
 
+    /// Use a client built from the shared network settings.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
     fn normalize_model(&self, model: &str) -> String {
         let raw = model.trim();
         if raw == "gemini-3-flash" || raw == "gemini-3-flash-preview" {
@@ -17,6 +17,10 @@ pub enum AppError {
     #[error("Security error: {0}")]
     Security(String),
 
+    /// The user cancelled this stage; not a failure.
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -1,18 +1,26 @@
 mod audio;
+mod cancel;
 mod commands;
 mod enhancement;
 mod error;
 mod hotkey;
 mod logger;
 mod models;
+mod network;
 mod security;
 mod stt;
 mod state;
 mod text_insertion;
 
+use cancel::Processing;
+use network::HttpClients;
 use security::keystore::KeyStore;
 use state::AppState;
+use stt::queue::PendingQueue;
+use stt::registry::SttRegistry;
+use stt::sherpa_engine::SherpaManager;
 use stt::vosk_engine::VoskManager;
+use stt::whisper_engine::WhisperManager;
 use tauri::{
     menu::{MenuBuilder, MenuItemBuilder},
     tray::TrayIconBuilder,
@@ -27,10 +35,16 @@ pub fn run() {
         .manage(AppState::default())
         .manage(KeyStore::new())
         .manage(VoskManager::new())
+        .manage(WhisperManager::new())
+        .manage(SherpaManager::new())
+        .manage(SttRegistry::new())
+        .manage(HttpClients::new())
+        .manage(Processing::new())
         .setup(|app| {
             setup_tray(app)?;
             setup_global_shortcuts(app)?;
             auto_load_vosk_model(app);
+            setup_offline_queue(app)?;
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -39,6 +53,8 @@ pub fn run() {
             commands::get_app_info,
             // STT
             commands::stt::transcribe_audio,
+            commands::stt::list_stt_providers,
+            commands::stt::detect_stt_protocol,
             // Enhancement
             commands::enhancement::enhance_text,
             // Settings / API keys
@@ -46,6 +62,9 @@ pub fn run() {
             commands::settings::has_api_key,
             commands::settings::delete_api_key,
             commands::settings::sync_settings,
+            commands::settings::validate_stt_provider_options,
+            commands::glossary::import_glossary,
+            commands::glossary::export_glossary,
             commands::settings::debug_ui_event,
             commands::settings::check_provider_health,
             commands::settings::open_devtools,
@@ -59,6 +78,7 @@ pub fn run() {
             // Preview
             commands::preview::show_preview_window,
             commands::preview::get_preview_text,
+            commands::preview::get_preview_alternatives,
             commands::preview::get_preview_target_bundle_id,
             commands::preview::close_preview_window,
             commands::preview::apply_preview_text,
@@ -68,6 +88,23 @@ pub fn run() {
             commands::model_manager::unload_vosk_model,
             commands::model_manager::get_vosk_status,
             commands::model_manager::list_downloaded_vosk_models,
+            commands::model_manager::set_vosk_grammar,
+            commands::model_manager::remove_vosk_grammar,
+            commands::model_manager::set_vosk_mode,
+            commands::model_manager::set_vosk_max_alternatives,
+            commands::model_manager::list_whisper_models,
+            commands::model_manager::download_whisper_model,
+            commands::model_manager::load_whisper_model,
+            commands::model_manager::unload_whisper_model,
+            commands::model_manager::get_whisper_status,
+            commands::model_manager::list_downloaded_whisper_models,
+            commands::model_manager::list_sherpa_models,
+            commands::model_manager::download_sherpa_model,
+            commands::model_manager::load_sherpa_model,
+            commands::model_manager::unload_sherpa_model,
+            commands::model_manager::get_sherpa_status,
+            commands::model_manager::list_downloaded_sherpa_models,
+            commands::model_manager::set_sherpa_punctuation,
             // Permissions
             commands::permissions::check_permissions,
             commands::permissions::request_microphone_permission,
@@ -75,7 +112,15 @@ pub fn run() {
             // Recording pipeline
             commands::recording::start_recording,
             commands::recording::stop_recording,
+            commands::recording::cancel_processing,
+            commands::recording::finish_processing,
+            commands::recording::processing_cancelled,
             commands::recording::get_recordings_dir,
+            commands::recording::list_recordings,
+            commands::subtitles::export_subtitles,
+            commands::offline_queue::list_pending_transcriptions,
+            commands::offline_queue::retry_pending_transcriptions,
+            commands::offline_queue::discard_pending_transcription,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -111,6 +156,18 @@ fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Load recordings queued while offline and keep retrying them in the background.
+fn setup_offline_queue(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    let path = app.path().app_data_dir()?.join("pending_transcriptions.json");
+    let queue = PendingQueue::load(path);
+    if queue.len() > 0 {
+        crate::app_log!("[startup] {} recordings waiting for transcription", queue.len());
+    }
+    app.manage(queue);
+    commands::offline_queue::spawn_worker(app.handle().clone());
+    Ok(())
+}
+
 /// Auto-load a Vosk model at startup, preferring one that matches the STT language.
 fn auto_load_vosk_model(app: &tauri::App) {
     let vosk = app.state::<VoskManager>();
@@ -170,6 +227,11 @@ fn setup_global_shortcuts(app: &tauri::App) -> Result<(), Box<dyn std::error::Er
                         match event.state {
                             ShortcutState::Pressed => {
                                 crate::app_log!("[shortcut] Option+Space PRESSED");
+                                // Connect to the cloud providers while the user speaks.
+                                let prewarm_handle = app_handle.clone();
+                                tauri::async_runtime::spawn(async move {
+                                    commands::stt::prewarm_dictation_providers(&prewarm_handle).await;
+                                });
                                 tauri::async_runtime::spawn(async move {
                                     let self_bundle_id = app_handle.config().identifier.clone();
                                     let target_bundle = capture_frontmost_bundle_id_before_recording(&self_bundle_id);
@@ -208,10 +270,8 @@ fn setup_global_shortcuts(app: &tauri::App) -> Result<(), Box<dyn std::error::Er
                                 crate::app_log!("[shortcut] Option+Space RELEASED");
                                 tauri::async_runtime::spawn(async move {
                                     let state = app_handle.state::<AppState>();
-                                    let vosk = app_handle.state::<VoskManager>();
-                                    let keystore = app_handle.state::<KeyStore>();
 
-                                    match commands::recording::stop_recording(app_handle.clone(), None, state, vosk, keystore).await {
+                                    match commands::recording::stop_recording(app_handle.clone(), None, state).await {
                                         Ok(result) => {
                                             crate::app_log!("[shortcut] Recording result: audio={:?}, text_len={}", result.audio_path, result.text.len());
                                             // Hide floating widget
@@ -220,9 +280,18 @@ fn setup_global_shortcuts(app: &tauri::App) -> Result<(), Box<dyn std::error::Er
                                             if !result.text.is_empty() {
                                                 // Emit result to frontend so it can decide: preview or insert
                                                 let _ = app_handle.emit("recording:result", &result);
+                                            } else {
+                                                app_handle.state::<Processing>().finish();
                                             }
                                         }
+                                        // cancel_processing already reported the cancelled status.
+                                        Err(error::AppError::Cancelled(_)) => {
+                                            app_handle.state::<Processing>().finish();
+                                            let _ = commands::floating::hide_floating_widget(app_handle.clone()).await;
+                                            crate::app_log!("[shortcut] Recording processing cancelled");
+                                        }
                                         Err(e) => {
+                                            app_handle.state::<Processing>().finish();
                                             let _ = commands::floating::hide_floating_widget(app_handle.clone()).await;
                                             crate::app_log!("[shortcut] Failed to stop recording: {e}");
                                             let _ = app_handle.emit("recording:status", serde_json::json!({"status": "error", "message": e.to_string()}));
@@ -0,0 +1,326 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+pub const DEFAULT_USER_AGENT: &str = concat!("Voxlore/", env!("CARGO_PKG_VERSION"));
+/// Key of the headers sent to every provider.
+pub const ALL_PROVIDERS: &str = "*";
+/// Idle pooled connections are closed after this long.
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+const PREWARM_TIMEOUT: Duration = Duration::from_secs(5);
+/// Local engines (Ollama, LM Studio) are never reached through the proxy.
+const LOCAL_HOSTS: &str = "localhost,127.0.0.1,::1";
+
+/// Network settings shared by every STT, enhancement and health-check client.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NetworkConfig {
+    /// `http://`, `https://`, `socks5://` or `socks5h://` URL, optionally
+    /// with `user:password@`. Empty for a direct connection.
+    pub proxy_url: Option<String>,
+    /// Comma-separated hosts that bypass the proxy, as in `NO_PROXY`.
+    pub no_proxy: Option<String>,
+    /// PEM bundle trusted in addition to the system roots, for TLS-inspecting proxies.
+    pub ca_bundle_path: Option<String>,
+    /// Replaces the default `Voxlore/<version>`.
+    pub user_agent: Option<String>,
+    /// Extra headers keyed by provider id ("openai", "openrouter", a custom
+    /// provider id) or [`ALL_PROVIDERS`].
+    pub extra_headers: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl NetworkConfig {
+    /// Trim fields and turn blank values into `None`.
+    pub fn normalized(self) -> Self {
+        let clean = |v: Option<String>| {
+            v.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+        };
+        Self {
+            proxy_url: clean(self.proxy_url),
+            no_proxy: clean(self.no_proxy),
+            ca_bundle_path: clean(self.ca_bundle_path),
+            user_agent: clean(self.user_agent),
+            extra_headers: self
+                .extra_headers
+                .into_iter()
+                .map(|(provider, headers)| (provider.trim().to_lowercase(), headers))
+                .filter(|(_, headers)| !headers.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Client builder for `provider` with proxy, root CA, user-agent and headers applied.
+    /// `endpoint` is the URL or origin the client talks to, when known.
+    pub fn client_builder(
+        &self,
+        provider: &str,
+        endpoint: Option<&str>,
+    ) -> Result<reqwest::ClientBuilder, AppError> {
+        let mut builder = reqwest::Client::builder()
+            .user_agent(self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT))
+            .default_headers(self.headers_for(provider, endpoint));
+
+        if let Some(url) = &self.proxy_url {
+            let no_proxy = match &self.no_proxy {
+                Some(hosts) => format!("{LOCAL_HOSTS},{hosts}"),
+                None => LOCAL_HOSTS.to_string(),
+            };
+            let proxy = reqwest::Proxy::all(url)?.no_proxy(reqwest::NoProxy::from_string(&no_proxy));
+            builder = builder.proxy(proxy);
+        }
+        if let Some(path) = &self.ca_bundle_path {
+            let pem = fs::read(path)?;
+            for cert in reqwest::Certificate::from_pem_bundle(&pem)? {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+        Ok(builder)
+    }
+
+    pub fn client(&self, provider: &str, endpoint: Option<&str>) -> Result<reqwest::Client, AppError> {
+        Ok(self.client_builder(provider, endpoint)?.build()?)
+    }
+
+    /// Built-in headers for `provider` at `endpoint`, then the shared extra
+    /// headers, then its own. Invalid names or values are skipped.
+    pub fn headers_for(&self, provider: &str, endpoint: Option<&str>) -> HeaderMap {
+        let provider = provider.trim().to_lowercase();
+        let mut headers = builtin_headers(&provider, endpoint);
+        for key in [ALL_PROVIDERS, provider.as_str()] {
+            let Some(extra) = self.extra_headers.get(key) else {
+                continue;
+            };
+            for (name, value) in extra {
+                match (
+                    HeaderName::from_bytes(name.trim().as_bytes()),
+                    HeaderValue::from_str(value.trim()),
+                ) {
+                    (Ok(name), Ok(value)) => {
+                        headers.insert(name, value);
+                    }
+                    _ => crate::app_log!("[network] Ignoring invalid header {name:?} for {key}"),
+                }
+            }
+        }
+        headers
+    }
+}
+
+/// A connection opened ahead of the upload.
+#[derive(Debug, Clone, Copy)]
+pub struct Prewarm {
+    pub at: Instant,
+}
+
+/// Long-lived clients per provider, kept in Tauri managed state so
+/// connections are reused across dictations.
+///
+/// Clients are rebuilt when the network settings change.
+pub struct HttpClients {
+    inner: Mutex<Pool>,
+}
+
+#[derive(Default)]
+struct Pool {
+    config: NetworkConfig,
+    /// Keyed by provider and whether the endpoint gets OpenRouter attribution.
+    clients: HashMap<(String, bool), reqwest::Client>,
+    prewarmed: HashMap<String, Prewarm>,
+}
+
+impl HttpClients {
+    pub fn new() -> Self {
+        Self {
+            inner: Mutex::new(Pool::default()),
+        }
+    }
+
+    /// Pooled client for `provider` at `endpoint` under `config`.
+    pub fn client(
+        &self,
+        config: &NetworkConfig,
+        provider: &str,
+        endpoint: Option<&str>,
+    ) -> Result<reqwest::Client, AppError> {
+        let mut pool = self.inner.lock().unwrap();
+        if pool.config != *config {
+            *pool = Pool {
+                config: config.clone(),
+                ..Default::default()
+            };
+        }
+        let key = (provider.to_string(), is_openrouter(endpoint));
+        if let Some(client) = pool.clients.get(&key) {
+            return Ok(client.clone());
+        }
+        let client = config
+            .client_builder(provider, endpoint)?
+            .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+            .tcp_keepalive(Duration::from_secs(30))
+            .build()?;
+        pool.clients.insert(key, client.clone());
+        Ok(client)
+    }
+
+    /// Open a connection to `origin` so the next request to `provider` skips
+    /// the handshakes. Any HTTP status counts; only the connection matters.
+    pub async fn prewarm(&self, config: &NetworkConfig, provider: &str, origin: &str) {
+        let client = match self.client(config, provider, Some(origin)) {
+            Ok(client) => client,
+            Err(e) => {
+                crate::app_log!("[network] prewarm provider={provider} skipped: {e}");
+                return;
+            }
+        };
+        let started = Instant::now();
+        match client.head(origin).timeout(PREWARM_TIMEOUT).send().await {
+            Ok(response) => {
+                let latency = started.elapsed();
+                crate::app_log!(
+                    "[network] prewarm provider={} origin={} status={} latency_ms={}",
+                    provider, origin, response.status(), latency.as_millis()
+                );
+                self.inner
+                    .lock()
+                    .unwrap()
+                    .prewarmed
+                    .insert(provider.to_string(), Prewarm { at: started });
+            }
+            Err(e) => crate::app_log!("[network] prewarm provider={provider} origin={origin} failed: {e}"),
+        }
+    }
+
+    /// Whether `origin` answers at all; any HTTP status counts as online.
+    pub async fn reachable(&self, config: &NetworkConfig, provider: &str, origin: &str) -> bool {
+        let Ok(client) = self.client(config, provider, Some(origin)) else {
+            return false;
+        };
+        match client.head(origin).timeout(PREWARM_TIMEOUT).send().await {
+            Ok(_) => true,
+            Err(e) => {
+                crate::app_log!("[network] provider={provider} origin={origin} unreachable: {e}");
+                false
+            }
+        }
+    }
+
+    /// The prewarm for `provider` if its connection should still be open.
+    pub fn take_prewarm(&self, provider: &str) -> Option<Prewarm> {
+        let warm = self.inner.lock().unwrap().prewarmed.remove(provider)?;
+        (warm.at.elapsed() < POOL_IDLE_TIMEOUT).then_some(warm)
+    }
+}
+
+/// Whether `endpoint` is OpenRouter's API, whichever provider points at it.
+fn is_openrouter(endpoint: Option<&str>) -> bool {
+    endpoint.is_some_and(|url| url.contains("openrouter.ai"))
+}
+
+/// Attribution headers OpenRouter asks apps to send, for the built-in
+/// provider and any custom provider or route pointed at openrouter.ai.
+fn builtin_headers(provider: &str, endpoint: Option<&str>) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if provider == "openrouter" || is_openrouter(endpoint) {
+        headers.insert("HTTP-Referer", HeaderValue::from_static("https://voxlore.app"));
+        headers.insert("X-Title", HeaderValue::from_static("Voxlore"));
+    }
+    headers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(entries: &[(&str, &[(&str, &str)])]) -> BTreeMap<String, BTreeMap<String, String>> {
+        entries
+            .iter()
+            .map(|(provider, pairs)| {
+                let pairs = pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+                (provider.to_string(), pairs)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_headers_for_layers_builtin_shared_and_provider() {
+        let config = NetworkConfig {
+            extra_headers: headers(&[
+                ("*", &[("X-Team", "speech")]),
+                ("OpenRouter", &[("X-Title", "Voxlore Corp"), ("bad header", "x")]),
+            ]),
+            ..Default::default()
+        }
+        .normalized();
+
+        let or = config.headers_for("openrouter", None);
+        assert_eq!(or["x-title"], "Voxlore Corp");
+        assert_eq!(or["http-referer"], "https://voxlore.app");
+        assert_eq!(or["x-team"], "speech");
+        assert_eq!(or.len(), 3);
+
+        let openai = config.headers_for("openai", Some("https://api.openai.com"));
+        assert_eq!(openai.len(), 1);
+    }
+
+    #[test]
+    fn test_openrouter_endpoint_gets_attribution_for_any_provider() {
+        let config = NetworkConfig::default();
+        let custom = config.headers_for("custom_openai_compatible", Some("https://openrouter.ai/api/v1"));
+        assert_eq!(custom["http-referer"], "https://voxlore.app");
+        assert_eq!(custom["x-title"], "Voxlore");
+        assert!(config
+            .headers_for("custom_openai_compatible", Some("https://llm.corp.example/v1"))
+            .is_empty());
+
+        let clients = HttpClients::new();
+        clients.client(&config, "custom_openai_compatible", Some("https://openrouter.ai")).unwrap();
+        clients.client(&config, "custom_openai_compatible", Some("https://llm.corp.example")).unwrap();
+        assert_eq!(clients.inner.lock().unwrap().clients.len(), 2);
+    }
+
+    #[test]
+    fn test_pool_reuses_clients_until_config_changes() {
+        let clients = HttpClients::new();
+        let config = NetworkConfig::default();
+        clients.client(&config, "openai", None).unwrap();
+        clients.client(&config, "openai", None).unwrap();
+        clients.client(&config, "mistral", None).unwrap();
+        assert_eq!(clients.inner.lock().unwrap().clients.len(), 2);
+
+        let proxied = NetworkConfig {
+            proxy_url: Some("http://proxy.corp:3128".into()),
+            ..Default::default()
+        };
+        clients.client(&proxied, "openai", None).unwrap();
+        assert_eq!(clients.inner.lock().unwrap().clients.len(), 1);
+        assert!(clients.take_prewarm("openai").is_none());
+    }
+
+    #[test]
+    fn test_client_builder_reports_bad_proxy_and_ca() {
+        assert!(NetworkConfig::default().client("openai", None).is_ok());
+        let proxy = NetworkConfig {
+            proxy_url: Some("http://proxy.corp:3128".into()),
+            ..Default::default()
+        };
+        assert!(proxy.client("openai", None).is_ok());
+
+        let missing_ca = NetworkConfig {
+            ca_bundle_path: Some("/nonexistent/voxlore-ca.pem".into()),
+            ..Default::default()
+        };
+        assert!(matches!(missing_ca.client("openai", None), Err(AppError::Io(_))));
+        let blank = NetworkConfig {
+            proxy_url: Some("  ".into()),
+            ..Default::default()
+        }
+        .normalized();
+        assert_eq!(blank.proxy_url, None);
+    }
+}
@@ -1,13 +1,26 @@
+use std::collections::HashMap;
 use std::sync::atomic::AtomicBool;
 use std::sync::{Arc, Mutex};
 
+use crate::cancel::StageDeadlines;
+use crate::commands::recording::CapturedAudio;
+use crate::network::NetworkConfig;
+use crate::stt::escalation::EscalationConfig;
+use crate::stt::failover::FallbackStep;
+use crate::stt::glossary::Glossary;
+use crate::stt::race::RaceConfig;
+use crate::stt::registry::CustomSttProtocol;
+use crate::stt::routing::LanguageRoute;
+
 pub struct AppState {
     /// Signal to stop the recording background task.
     pub stop_signal: Mutex<Option<Arc<AtomicBool>>>,
     /// Handle to the background task collecting audio samples.
-    pub recording_task: Mutex<Option<tokio::task::JoinHandle<Vec<i16>>>>,
+    pub recording_task: Mutex<Option<tokio::task::JoinHandle<CapturedAudio>>>,
     /// Text to display in the preview window (set before opening, pulled by preview on mount).
     pub preview_text: Mutex<Option<String>>,
+    /// Alternative transcripts offered as one-click swaps in the preview window.
+    pub preview_alternatives: Mutex<Vec<String>>,
     /// Floating widget position synced from frontend settings.
     pub widget_position: Mutex<String>,
     /// Whether floating widget is enabled.
@@ -20,8 +33,28 @@ pub struct AppState {
     pub stt_model: Mutex<Option<String>>,
     /// Optional STT OpenAI-compatible endpoint synced from frontend settings.
     pub stt_base_url: Mutex<Option<String>>,
+    /// Protocol of the custom OpenAI-compatible endpoint; `None` detects it.
+    pub stt_protocol: Mutex<Option<CustomSttProtocol>>,
+    /// Detected protocols by endpoint base URL.
+    pub detected_stt_protocols: Mutex<HashMap<String, CustomSttProtocol>>,
     /// Cloud STT timeout seconds synced from frontend settings.
     pub cloud_timeout_secs: Mutex<u64>,
+    /// Per-stage limits for transcription, enhancement and insertion.
+    pub stage_deadlines: Mutex<StageDeadlines>,
+    /// Providers tried in order when the selected STT provider fails.
+    pub stt_fallback_chain: Mutex<Vec<FallbackStep>>,
+    /// Second provider raced against the selected one, if any.
+    pub stt_race: Mutex<Option<RaceConfig>>,
+    /// Cloud provider asked again when a local transcript looks unreliable.
+    pub stt_escalation: Mutex<Option<EscalationConfig>>,
+    /// Provider, model and enhancement per spoken language.
+    pub stt_routes: Mutex<Vec<LanguageRoute>>,
+    /// Write an on-device Vosk transcript for recordings queued while offline.
+    pub offline_interim_transcript: Mutex<bool>,
+    /// User vocabulary injected into every STT engine.
+    pub glossary: Mutex<Glossary>,
+    /// Proxy, root CA, user-agent and extra headers for outgoing requests.
+    pub network: Mutex<NetworkConfig>,
     /// Frontend debug logging switch.
     pub debug_logging_enabled: Mutex<bool>,
     /// Preview 開啟前的前景 App bundle id，用於 Apply 時還原焦點。
@@ -36,13 +69,24 @@ impl Default for AppState {
             stop_signal: Mutex::new(None),
             recording_task: Mutex::new(None),
             preview_text: Mutex::new(None),
+            preview_alternatives: Mutex::new(Vec::new()),
             widget_position: Mutex::new("bottom-right".into()),
             floating_window_enabled: Mutex::new(false),
             stt_language: Mutex::new("en".into()),
             stt_provider: Mutex::new("vosk".into()),
             stt_model: Mutex::new(None),
             stt_base_url: Mutex::new(None),
+            stt_protocol: Mutex::new(None),
+            detected_stt_protocols: Mutex::new(HashMap::new()),
             cloud_timeout_secs: Mutex::new(45),
+            stage_deadlines: Mutex::new(StageDeadlines::default()),
+            stt_fallback_chain: Mutex::new(Vec::new()),
+            stt_race: Mutex::new(None),
+            stt_escalation: Mutex::new(None),
+            stt_routes: Mutex::new(Vec::new()),
+            offline_interim_transcript: Mutex::new(true),
+            glossary: Mutex::new(Glossary::default()),
+            network: Mutex::new(NetworkConfig::default()),
             debug_logging_enabled: Mutex::new(true),
             preview_target_bundle_id: Mutex::new(None),
             recording_target_bundle_id: Mutex::new(None),
@@ -0,0 +1,409 @@
+use std::ops::Range;
+use std::time::Duration;
+
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+
+use super::{CloudSttEngine, SttConfig, SttResult};
+use crate::audio::wav;
+use crate::error::AppError;
+
+/// Audio shared by neighbouring chunks, so a word on the cut is heard whole at least once.
+const OVERLAP_SECS: f32 = 0.5;
+/// How far before the length limit to look for a pause.
+const SEARCH_WINDOW_SECS: f32 = 5.0;
+/// Energy is measured over 20ms frames.
+const FRAME_SECS: f32 = 0.02;
+const MAX_CONCURRENT_CHUNKS: usize = 3;
+/// Longest repeated run looked for when joining chunk transcripts.
+const MAX_OVERLAP_TOKENS: usize = 12;
+
+/// A slice of the recording sent as one request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    /// Samples sent to the engine, including the overlap on both sides.
+    pub range: Range<usize>,
+    /// Samples this chunk is responsible for; word and segment timings outside it are dropped.
+    pub owned: Range<usize>,
+}
+
+/// Transcribe with `engine`, splitting audio longer than its per-request limit.
+///
+/// Chunks are cut at the quietest frame near the limit, transcribed
+/// concurrently and stitched back in order. `request_timeout` applies to
+/// each request, so long recordings are bounded by the stage deadline
+/// rather than by a single request's limit.
+pub async fn transcribe_chunked<E: CloudSttEngine>(
+    engine: &E,
+    samples: &[i16],
+    config: &SttConfig,
+    request_timeout: Duration,
+) -> Result<SttResult, AppError> {
+    let chunks = plan_chunks(samples, config.sample_rate, engine.max_chunk_secs());
+    if chunks.len() == 1 {
+        let wav_data = wav::encode_wav(samples, config.sample_rate);
+        return transcribe_one(engine, &wav_data, config, request_timeout).await;
+    }
+
+    crate::app_log!(
+        "[stt] Splitting {:.1}s of audio into {} chunks for {}",
+        samples.len() as f32 / config.sample_rate as f32,
+        chunks.len(),
+        engine.provider_name()
+    );
+    let results: Vec<SttResult> = stream::iter(chunks.iter().map(|chunk| async move {
+        let wav_data = wav::encode_wav(&samples[chunk.range.clone()], config.sample_rate);
+        transcribe_one(engine, &wav_data, config, request_timeout).await
+    }))
+    .buffered(MAX_CONCURRENT_CHUNKS)
+    .try_collect()
+    .await?;
+
+    Ok(merge(&chunks, results, config.sample_rate))
+}
+
+async fn transcribe_one<E: CloudSttEngine>(
+    engine: &E,
+    wav_data: &[u8],
+    config: &SttConfig,
+    timeout: Duration,
+) -> Result<SttResult, AppError> {
+    tokio::time::timeout(timeout, engine.transcribe(wav_data, config))
+        .await
+        .map_err(|_| AppError::Stt("Cloud STT timeout. Check internet and try again.".into()))?
+}
+
+/// Split `samples` into chunks of at most `max_chunk_secs` (overlap included).
+pub fn plan_chunks(samples: &[i16], sample_rate: u32, max_chunk_secs: u32) -> Vec<Chunk> {
+    let len = samples.len();
+    let rate = sample_rate as f32;
+    let overlap = (OVERLAP_SECS * rate) as usize;
+    let frame = ((FRAME_SECS * rate) as usize).max(1);
+    let max_len = ((max_chunk_secs as f32 * rate) as usize)
+        .saturating_sub(2 * overlap)
+        .max(frame * 2);
+    let window = ((SEARCH_WINDOW_SECS * rate) as usize).min(max_len / 2);
+
+    let mut cuts = vec![0];
+    let mut pos = 0;
+    while len - pos > max_len {
+        let hi = pos + max_len;
+        pos = quietest_point(samples, hi - window, hi, frame);
+        cuts.push(pos);
+    }
+    cuts.push(len);
+
+    cuts.windows(2)
+        .map(|w| Chunk {
+            range: w[0].saturating_sub(overlap)..(w[1] + overlap).min(len),
+            owned: w[0]..w[1],
+        })
+        .collect()
+}
+
+/// Middle of the lowest-energy frame in `lo..hi`.
+fn quietest_point(samples: &[i16], lo: usize, hi: usize, frame: usize) -> usize {
+    let mut best = (f32::MAX, lo + frame / 2);
+    let mut start = lo;
+    while start + frame <= hi {
+        let rms = wav::calculate_rms(&samples[start..start + frame]);
+        if rms < best.0 {
+            best = (rms, start + frame / 2);
+        }
+        start += frame;
+    }
+    best.1
+}
+
+/// Combine per-chunk results, shifting timings to the whole recording.
+pub fn merge(chunks: &[Chunk], results: Vec<SttResult>, sample_rate: u32) -> SttResult {
+    let rate = sample_rate as f32;
+    let mut merged = SttResult::default();
+    let (mut weighted, mut total) = (0.0f32, 0.0f32);
+
+    for (chunk, result) in chunks.iter().zip(results) {
+        let offset = chunk.range.start as f32 / rate;
+        let owned = chunk.owned.start as f32 / rate..chunk.owned.end as f32 / rate;
+        merged.words.extend(
+            result
+                .words
+                .into_iter()
+                .map(|mut w| {
+                    w.start += offset;
+                    w.end += offset;
+                    w
+                })
+                .filter(|w| owned.contains(&((w.start + w.end) / 2.0))),
+        );
+        merged.segments.extend(
+            result
+                .segments
+                .into_iter()
+                .map(|mut s| {
+                    s.start += offset;
+                    s.end += offset;
+                    s
+                })
+                .filter(|s| owned.contains(&((s.start + s.end) / 2.0))),
+        );
+        if let Some(conf) = result.confidence {
+            let weight = chunk.owned.len() as f32;
+            weighted += conf * weight;
+            total += weight;
+        }
+        if merged.language_detected.is_none() {
+            merged.language_detected = result.language_detected;
+        }
+        merged.text = stitch(&merged.text, &result.text);
+    }
+
+    merged.confidence = (total > 0.0).then(|| weighted / total);
+    merged
+}
+
+/// Join two transcripts, dropping words the overlap made both chunks hear.
+pub fn stitch(prev: &str, next: &str) -> String {
+    let prev = prev.trim_end();
+    let next = next.trim_start();
+    if prev.is_empty() {
+        return next.to_string();
+    }
+    if next.is_empty() {
+        return prev.to_string();
+    }
+
+    let a = tokens(prev);
+    let b = tokens(next);
+    let max = MAX_OVERLAP_TOKENS.min(a.len()).min(b.len());
+    let repeated = (1..=max).rev().find(|&k| {
+        let same = a[a.len() - k..]
+            .iter()
+            .zip(&b[..k])
+            .all(|(x, y)| x.key == y.key);
+        // A single short word is as likely to be said twice as to be an echo.
+        same && (k >= 2 || (!b[0].cjk && b[0].key.chars().count() >= 5))
+    });
+    let mut prev = prev.to_string();
+    let rest = match repeated {
+        Some(k) => {
+            // Punctuation after the last repeated word ("release." or "很好，")
+            // ends a sentence or clause: keep it once, on `prev`.
+            let heard = &next[..b[k - 1].end];
+            let word_end = heard
+                .char_indices()
+                .rev()
+                .find(|(_, c)| c.is_alphanumeric())
+                .map_or(0, |(i, c)| i + c.len_utf8());
+            let after = &next[word_end..];
+            let punct_len = after
+                .find(|c: char| !(c.is_ascii_punctuation() || is_cjk_punctuation(c)))
+                .unwrap_or(after.len());
+            let ends_with_punct = prev
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_ascii_punctuation() || is_cjk_punctuation(c));
+            if !ends_with_punct {
+                prev.push_str(&after[..punct_len]);
+            }
+            after[punct_len..].trim_start()
+        }
+        None => next,
+    };
+    if rest.is_empty() {
+        return prev;
+    }
+
+    let joins_cjk = prev.chars().next_back().is_some_and(is_cjk)
+        && rest.chars().next().is_some_and(is_cjk);
+    if joins_cjk {
+        format!("{prev}{rest}")
+    } else {
+        format!("{prev} {rest}")
+    }
+}
+
+struct Token {
+    /// Lowercased alphanumerics used for comparison.
+    key: String,
+    /// Byte offset just past the token, including attached punctuation.
+    end: usize,
+    cjk: bool,
+}
+
+/// Whitespace-separated words, with each CJK character as its own token.
+fn tokens(text: &str) -> Vec<Token> {
+    let mut out = Vec::new();
+    let mut key = String::new();
+    let mut end = 0;
+    for (i, c) in text.char_indices() {
+        if is_cjk(c) || c.is_whitespace() {
+            if !key.is_empty() {
+                out.push(Token {
+                    key: std::mem::take(&mut key),
+                    end,
+                    cjk: false,
+                });
+            }
+            if is_cjk(c) && !is_cjk_punctuation(c) {
+                out.push(Token {
+                    key: c.to_string(),
+                    end: i + c.len_utf8(),
+                    cjk: true,
+                });
+            }
+        } else {
+            if c.is_alphanumeric() {
+                key.extend(c.to_lowercase());
+            }
+            end = i + c.len_utf8();
+        }
+    }
+    if !key.is_empty() {
+        out.push(Token {
+            key,
+            end,
+            cjk: false,
+        });
+    }
+    out
+}
+
+/// Han, kana and CJK/full-width punctuation: scripts written without spaces.
+pub fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{F900}'..='\u{FAFF}'
+    ) || is_cjk_punctuation(c)
+}
+
+pub fn is_cjk_punctuation(c: char) -> bool {
+    matches!(c,
+        '\u{3000}'..='\u{303F}'
+        | '\u{FF01}'..='\u{FF0F}'
+        | '\u{FF1A}'..='\u{FF20}'
+        | '\u{FF5B}'..='\u{FF65}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stt::SttWord;
+
+    /// Takes `delay` per request and reports how many bytes it was sent.
+    struct SlowEngine {
+        delay: Duration,
+    }
+
+    impl CloudSttEngine for SlowEngine {
+        async fn transcribe(
+            &self,
+            audio_data: &[u8],
+            _config: &SttConfig,
+        ) -> Result<SttResult, AppError> {
+            tokio::time::sleep(self.delay).await;
+            Ok(SttResult {
+                text: audio_data.len().to_string(),
+                ..Default::default()
+            })
+        }
+
+        fn max_chunk_secs(&self) -> u32 {
+            10
+        }
+
+        fn provider_name(&self) -> &str {
+            "slow"
+        }
+    }
+
+    #[test]
+    fn test_plan_chunks_cuts_at_silence() {
+        // 100s of tone with a pause at 56-57s, 60s limit.
+        let rate = 1000;
+        let mut samples = vec![8000i16; 100 * rate];
+        samples[56 * rate..57 * rate].fill(0);
+        let chunks = plan_chunks(&samples, rate as u32, 60);
+
+        assert_eq!(chunks.len(), 2);
+        let cut = chunks[0].owned.end;
+        assert!((56 * rate..57 * rate).contains(&cut));
+        assert_eq!(chunks[1].owned.start, cut);
+        assert!(chunks[0].range.len() <= 60 * rate);
+        assert_eq!(chunks[1].range.start, cut - rate / 2);
+        assert_eq!(plan_chunks(&samples[..10 * rate], rate as u32, 60).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_applies_per_chunk() {
+        let config = SttConfig {
+            sample_rate: 1000,
+            ..Default::default()
+        };
+        // 45s at a 10s limit: nine chunks in three concurrent rounds, each
+        // round within the per-request timeout but not all of them together.
+        let samples = vec![8000i16; 45_000];
+        let engine = SlowEngine {
+            delay: Duration::from_millis(150),
+        };
+        let result =
+            transcribe_chunked(&engine, &samples, &config, Duration::from_millis(250)).await;
+        assert!(result.is_ok(), "{result:?}");
+
+        let stuck = SlowEngine {
+            delay: Duration::from_secs(5),
+        };
+        let err = transcribe_chunked(&stuck, &samples, &config, Duration::from_millis(50))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timeout"), "{err}");
+    }
+
+    #[test]
+    fn test_stitch_drops_repeated_words() {
+        assert_eq!(
+            stitch("we should ship the release", "the release. Then tag it"),
+            "we should ship the release. Then tag it"
+        );
+        assert_eq!(stitch("ship the release.", "the release. Then tag it"), "ship the release. Then tag it");
+        assert_eq!(stitch("今天天氣很好，", "很好，我們出去走走"), "今天天氣很好，我們出去走走");
+        // One short word is not treated as an echo.
+        assert_eq!(stitch("pass me the", "the salt"), "pass me the the salt");
+        assert_eq!(stitch("", "hello"), "hello");
+    }
+
+    #[test]
+    fn test_merge_offsets_and_filters_words() {
+        let rate = 1000;
+        let chunks = vec![
+            Chunk { range: 0..1500, owned: 0..1000 },
+            Chunk { range: 500..2000, owned: 1000..2000 },
+        ];
+        let word = |w: &str, start: f32, end: f32| SttWord {
+            word: w.into(),
+            start,
+            end,
+            confidence: Some(0.5),
+        };
+        let results = vec![
+            SttResult {
+                text: "hello world".into(),
+                words: vec![word("hello", 0.1, 0.5), word("world", 0.9, 1.3)],
+                confidence: Some(0.8),
+                ..Default::default()
+            },
+            SttResult {
+                text: "world again".into(),
+                words: vec![word("world", 0.4, 0.8), word("again", 0.9, 1.2)],
+                confidence: Some(0.6),
+                ..Default::default()
+            },
+        ];
+        let merged = merge(&chunks, results, rate);
+        let words: Vec<&str> = merged.words.iter().map(|w| w.word.as_str()).collect();
+        assert_eq!(words, ["hello", "world", "again"]);
+        assert!((merged.words[2].start - 1.4).abs() < 1e-4);
+        assert!((merged.confidence.unwrap() - 0.7).abs() < 1e-4);
+    }
+}
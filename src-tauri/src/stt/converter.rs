@@ -1,11 +1,41 @@
+use std::sync::OnceLock;
+
 use ferrous_opencc::{config::BuiltinConfig, OpenCC};
 
+use super::SttResult;
+
+/// The S2T converter, loaded once: building it parses the dictionaries,
+/// which is too slow to repeat for every live partial.
+fn s2t() -> Option<&'static OpenCC> {
+    static S2T: OnceLock<Option<OpenCC>> = OnceLock::new();
+    S2T.get_or_init(|| OpenCC::from_config(BuiltinConfig::S2t).ok())
+        .as_ref()
+}
+
 /// Convert Simplified Chinese text to Traditional Chinese.
 /// Vosk models typically output Simplified; this converts for zh-TW users.
 pub fn simplified_to_traditional(text: &str) -> String {
-    match OpenCC::from_config(BuiltinConfig::S2t) {
-        Ok(cc) => cc.convert(text),
-        Err(_) => text.to_string(), // Fallback: return original
+    match s2t() {
+        Some(cc) => cc.convert(text),
+        None => text.to_string(), // Fallback: return original
+    }
+}
+
+/// Convert the transcript, its word and segment timings and alternatives to
+/// Traditional Chinese in place.
+pub fn result_to_traditional(result: &mut SttResult) {
+    let Some(cc) = s2t() else {
+        return;
+    };
+    result.text = cc.convert(&result.text);
+    for word in &mut result.words {
+        word.word = cc.convert(&word.word);
+    }
+    for segment in &mut result.segments {
+        segment.text = cc.convert(&segment.text);
+    }
+    for alternative in &mut result.alternatives {
+        alternative.text = cc.convert(&alternative.text);
     }
 }
 
@@ -14,3 +44,28 @@ pub fn needs_s2t_conversion(language: &str) -> bool {
     let lang = language.to_lowercase();
     lang == "zh-tw" || lang == "zh_tw" || lang == "zh-hant"
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stt::SttSegment;
+
+    #[test]
+    fn test_result_to_traditional_converts_segments() {
+        let mut result = SttResult {
+            text: "这是软件".into(),
+            segments: vec![SttSegment {
+                text: "这是软件".into(),
+                start: 0.0,
+                end: 1.2,
+                confidence: None,
+                speaker: None,
+            }],
+            ..Default::default()
+        };
+        result_to_traditional(&mut result);
+        assert_eq!(result.text, "這是軟件");
+        assert_eq!(result.segments[0].text, result.text);
+        assert!(needs_s2t_conversion("zh-TW") && !needs_s2t_conversion("zh-CN"));
+    }
+}
@@ -1,13 +1,18 @@
 use reqwest::multipart;
 
-use super::{CloudSttEngine, SttConfig, SttResult};
+use super::http::{send_with_retry, RetryPolicy};
+use super::language;
+use super::options::ElevenLabsOptions;
+use super::{CloudSttEngine, SttConfig, SttResult, SttSegment, SttWord};
 use crate::error::AppError;
 
 /// ElevenLabs Scribe v2 STT engine.
 pub struct ElevenLabsEngine {
     api_key: String,
     model: String,
+    options: ElevenLabsOptions,
     client: reqwest::Client,
+    retry: RetryPolicy,
 }
 
 impl ElevenLabsEngine {
@@ -15,37 +20,126 @@ impl ElevenLabsEngine {
         Self {
             api_key,
             model: model.unwrap_or_else(|| "scribe_v2".to_string()),
+            options: ElevenLabsOptions::default(),
             client: reqwest::Client::new(),
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Use a client built from the shared network settings.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub fn with_options(mut self, options: ElevenLabsOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Text, word timings and, when diarized, one segment per speaker turn.
+    fn parse_response(json: &serde_json::Value) -> SttResult {
+        let entries = json["words"].as_array().map(Vec::as_slice).unwrap_or_default();
+        let words = entries
+            .iter()
+            .filter(|w| w["type"].as_str() == Some("word"))
+            .filter_map(|w| {
+                Some(SttWord {
+                    word: w["text"].as_str()?.trim().to_string(),
+                    start: w["start"].as_f64()? as f32,
+                    end: w["end"].as_f64()? as f32,
+                    confidence: w["logprob"].as_f64().map(|lp| lp.exp() as f32),
+                })
+            })
+            .collect();
+
+        let mut segments: Vec<SttSegment> = Vec::new();
+        for entry in entries {
+            let (Some(speaker), Some(text)) =
+                (entry["speaker_id"].as_str(), entry["text"].as_str())
+            else {
+                continue;
+            };
+            let start = entry["start"].as_f64().unwrap_or_default() as f32;
+            let end = entry["end"].as_f64().unwrap_or_default() as f32;
+            match segments.last_mut() {
+                Some(turn) if turn.speaker.as_deref() == Some(speaker) => {
+                    turn.text.push_str(text);
+                    turn.end = end;
+                }
+                _ => segments.push(SttSegment {
+                    text: text.to_string(),
+                    start,
+                    end,
+                    confidence: None,
+                    speaker: Some(speaker.to_string()),
+                }),
+            }
+        }
+        for turn in &mut segments {
+            turn.text = turn.text.trim().to_string();
+        }
+        segments.retain(|turn| !turn.text.is_empty());
+
+        SttResult {
+            text: json["text"].as_str().unwrap_or_default().to_string(),
+            confidence: None,
+            language_detected: json["language_code"].as_str().map(String::from),
+            words,
+            segments,
+            ..Default::default()
         }
     }
 }
 
+/// Scribe accepts keyterms shorter than 50 characters.
+fn keyterms(glossary: &[String]) -> impl Iterator<Item = String> + '_ {
+    glossary
+        .iter()
+        .filter(|term| term.chars().count() < 50)
+        .cloned()
+}
+
 impl CloudSttEngine for ElevenLabsEngine {
     async fn transcribe(&self, audio_data: &[u8], config: &SttConfig) -> Result<SttResult, AppError> {
-        let audio_part = multipart::Part::bytes(audio_data.to_vec())
-            .file_name("audio.wav")
-            .mime_str("audio/wav")
-            .map_err(|e| AppError::Stt(format!("Failed to create multipart: {e}")))?;
-
-        let form = multipart::Form::new()
-            .part("audio", audio_part)
-            .text("model_id", self.model.clone())
-            .text("language_code", config.language.clone());
-
-        let response = self
-            .client
-            .post("https://api.elevenlabs.io/v1/speech-to-text")
-            .header("xi-api-key", &self.api_key)
-            .multipart(form)
-            .send()
-            .await
-            .map_err(|e| AppError::Stt(format!("ElevenLabs request failed: {e}")))?;
-
-        let status = response.status();
-        let body = response
-            .text()
-            .await
-            .map_err(|e| AppError::Stt(format!("Failed to read response: {e}")))?;
+        let reply = send_with_retry("ElevenLabs", &self.retry, || {
+            let audio_part = multipart::Part::bytes(audio_data.to_vec())
+                .file_name("audio.wav")
+                .mime_str("audio/wav")
+                .map_err(|e| AppError::Stt(format!("Failed to create multipart: {e}")))?;
+
+            let mut form = multipart::Form::new()
+                .part("audio", audio_part)
+                .text("model_id", self.model.clone());
+            if let Some(language) = language::request_language(&config.language) {
+                form = form.text("language_code", language.to_string());
+            }
+            for term in keyterms(&config.glossary) {
+                form = form.text("keyterms", term);
+            }
+            if self.options.diarize {
+                form = form.text("diarize", "true");
+                if let Some(speakers) = self.options.num_speakers {
+                    form = form.text("num_speakers", speakers.to_string());
+                }
+            }
+            if let Some(tag) = self.options.tag_audio_events {
+                form = form.text("tag_audio_events", tag.to_string());
+            }
+
+            Ok(self
+                .client
+                .post("https://api.elevenlabs.io/v1/speech-to-text")
+                .header("xi-api-key", &self.api_key)
+                .multipart(form))
+        })
+        .await?;
+        let (status, body) = (reply.status, reply.body);
 
         if !status.is_success() {
             return Err(AppError::Stt(format!(
@@ -56,16 +150,41 @@ impl CloudSttEngine for ElevenLabsEngine {
         let json: serde_json::Value = serde_json::from_str(&body)
             .map_err(|e| AppError::Stt(format!("Failed to parse response: {e}")))?;
 
-        let text = json["text"].as_str().unwrap_or_default().to_string();
-
-        Ok(SttResult {
-            text,
-            confidence: None,
-            language_detected: json["language_code"].as_str().map(String::from),
-        })
+        Ok(Self::parse_response(&json))
     }
 
     fn provider_name(&self) -> &str {
         "ElevenLabs Scribe"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_diarized_response() {
+        let json = serde_json::json!({
+            "text": "Ready? Yes (laughs)",
+            "language_code": "eng",
+            "words": [
+                {"text": "Ready?", "type": "word", "start": 0.0, "end": 0.5, "speaker_id": "speaker_0", "logprob": 0.0},
+                {"text": " ", "type": "spacing", "start": 0.5, "end": 0.6, "speaker_id": "speaker_0"},
+                {"text": "Yes", "type": "word", "start": 0.8, "end": 1.1, "speaker_id": "speaker_1"},
+                {"text": " ", "type": "spacing", "start": 1.1, "end": 1.2, "speaker_id": "speaker_1"},
+                {"text": "(laughs)", "type": "audio_event", "start": 1.2, "end": 1.9, "speaker_id": "speaker_1"}
+            ]
+        });
+        let result = ElevenLabsEngine::parse_response(&json);
+        assert_eq!(result.words.len(), 2);
+        assert_eq!(result.words[0].confidence, Some(1.0));
+        assert_eq!(result.segments.len(), 2);
+        assert_eq!(result.segments[1].text, "Yes (laughs)");
+        assert_eq!(result.segments[1].speaker.as_deref(), Some("speaker_1"));
+        assert_eq!((result.segments[1].start, result.segments[1].end), (0.8, 1.9));
+
+        // Without diarization there are no speaker ids and no segments.
+        let plain = ElevenLabsEngine::parse_response(&serde_json::json!({"text": "hi"}));
+        assert!(plain.segments.is_empty() && plain.words.is_empty());
+    }
+}
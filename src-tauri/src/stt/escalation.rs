@@ -0,0 +1,187 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::chunking::is_cjk;
+use super::SttResult;
+use crate::audio::wav;
+
+/// Energy is measured over 20ms frames.
+const FRAME_SECS: f32 = 0.02;
+/// Frames louder than this (about -40 dBFS) count as voiced.
+const VOICED_RMS: f32 = 0.01;
+/// Less voiced audio than this is not worth a second opinion.
+const MIN_VOICED_SECS: f32 = 0.5;
+
+/// Cloud provider asked again when a local transcript looks unreliable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EscalationConfig {
+    /// Provider for hard cases; blank turns escalation off.
+    pub provider: String,
+    /// Model for the escalation provider; its default when empty.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Escalate below this utterance confidence (0–1).
+    #[serde(default = "default_min_confidence")]
+    pub min_confidence: f32,
+    /// Escalate below this many words per second of voiced audio; each CJK
+    /// character counts as a word.
+    #[serde(default = "default_min_words_per_sec")]
+    pub min_words_per_sec: f32,
+}
+
+fn default_min_confidence() -> f32 {
+    0.6
+}
+
+fn default_min_words_per_sec() -> f32 {
+    0.5
+}
+
+impl EscalationConfig {
+    /// Trim and clamp fields; `None` when escalation is off.
+    pub fn normalized(self) -> Option<Self> {
+        let provider = self.provider.trim().to_string();
+        if provider.is_empty() {
+            return None;
+        }
+        Some(Self {
+            provider,
+            model: self
+                .model
+                .map(|m| m.trim().to_string())
+                .filter(|m| !m.is_empty()),
+            min_confidence: self.min_confidence.clamp(0.0, 1.0),
+            min_words_per_sec: self.min_words_per_sec.max(0.0),
+        })
+    }
+
+    /// Why `result` should be re-transcribed, if it should.
+    pub fn reason(&self, result: &SttResult, samples: &[i16], sample_rate: u32) -> Option<Reason> {
+        let voiced_secs = voiced_secs(samples, sample_rate);
+        if voiced_secs < MIN_VOICED_SECS {
+            return None;
+        }
+        let words = count_words(&result.text);
+        if words == 0 {
+            return Some(Reason::Empty { voiced_secs });
+        }
+        if let Some(confidence) = result.confidence {
+            if confidence < self.min_confidence {
+                return Some(Reason::LowConfidence(confidence));
+            }
+        }
+        if (words as f32) < self.min_words_per_sec * voiced_secs {
+            return Some(Reason::TooShort { words, voiced_secs });
+        }
+        None
+    }
+}
+
+/// What made a local transcript look unreliable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reason {
+    Empty { voiced_secs: f32 },
+    LowConfidence(f32),
+    TooShort { words: usize, voiced_secs: f32 },
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty { voiced_secs } => write!(f, "no text for {voiced_secs:.1}s of speech"),
+            Self::LowConfidence(confidence) => write!(f, "confidence {confidence:.2}"),
+            Self::TooShort { words, voiced_secs } => {
+                write!(f, "{words} words for {voiced_secs:.1}s of speech")
+            }
+        }
+    }
+}
+
+/// Seconds of audio louder than [`VOICED_RMS`].
+fn voiced_secs(samples: &[i16], sample_rate: u32) -> f32 {
+    let frame = ((sample_rate as f32 * FRAME_SECS) as usize).max(1);
+    let voiced = samples
+        .chunks(frame)
+        .filter(|chunk| wav::calculate_rms(chunk) > VOICED_RMS)
+        .count();
+    voiced as f32 * frame as f32 / sample_rate as f32
+}
+
+/// Whitespace-separated words, with each CJK character counted on its own.
+fn count_words(text: &str) -> usize {
+    text.split_whitespace()
+        .map(|token| {
+            let cjk = token.chars().filter(|c| is_cjk(*c)).count();
+            let rest = token.chars().any(|c| !is_cjk(c) && c.is_alphanumeric());
+            cjk + usize::from(rest)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16000;
+
+    fn speech(secs: f32) -> Vec<i16> {
+        (0..(secs * RATE as f32) as usize)
+            .map(|i| ((i as f32 * 0.05).sin() * 8000.0) as i16)
+            .collect()
+    }
+
+    fn result(text: &str, confidence: Option<f32>) -> SttResult {
+        SttResult {
+            text: text.into(),
+            confidence,
+            ..Default::default()
+        }
+    }
+
+    fn config() -> EscalationConfig {
+        EscalationConfig {
+            provider: "openai_transcribe".into(),
+            model: None,
+            min_confidence: 0.6,
+            min_words_per_sec: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_reason_flags_empty_unsure_and_sparse_output() {
+        let audio = speech(4.0);
+        let config = config();
+        assert!(matches!(config.reason(&result("", None), &audio, RATE), Some(Reason::Empty { .. })));
+        assert_eq!(
+            config.reason(&result("ship it today", Some(0.4)), &audio, RATE),
+            Some(Reason::LowConfidence(0.4))
+        );
+        assert!(matches!(
+            config.reason(&result("ok", Some(0.9)), &audio, RATE),
+            Some(Reason::TooShort { words: 1, .. })
+        ));
+        assert_eq!(config.reason(&result("ship it today", Some(0.9)), &audio, RATE), None);
+        // Four Han characters are four words.
+        assert_eq!(config.reason(&result("今天 出貨", None), &audio, RATE), None);
+    }
+
+    #[test]
+    fn test_reason_ignores_silence() {
+        let silence = vec![0i16; RATE as usize * 3];
+        assert_eq!(config().reason(&result("", None), &silence, RATE), None);
+    }
+
+    #[test]
+    fn test_normalized_clamps_and_disables() {
+        let mut blank = config();
+        blank.provider = " ".into();
+        assert_eq!(blank.normalized(), None);
+        let mut wild = config();
+        wild.min_confidence = 3.0;
+        wild.min_words_per_sec = -1.0;
+        let wild = wild.normalized().unwrap();
+        assert_eq!((wild.min_confidence, wild.min_words_per_sec), (1.0, 0.0));
+    }
+}
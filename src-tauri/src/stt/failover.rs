@@ -0,0 +1,126 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// A fallback provider tried when the previous one fails.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FallbackStep {
+    pub provider: String,
+    /// Overrides `cloud_timeout_secs` for this step.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Model for this step; the provider's default when empty.
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// One attempt in the resolved chain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedStep {
+    pub provider: String,
+    pub timeout_secs: u64,
+    pub model: Option<String>,
+    /// The user's selected provider; only it uses the configured model and endpoint.
+    pub primary: bool,
+}
+
+/// Upper bound on attempts, so a long chain cannot hold a dictation hostage.
+pub const MAX_STEPS: usize = 4;
+
+/// Audio covered by one step timeout. Local engines and chunked uploads
+/// both take longer on longer recordings.
+const AUDIO_SECS_PER_TIMEOUT: f32 = 120.0;
+
+impl PlannedStep {
+    /// Time the whole attempt may take on `audio_secs` of audio: one
+    /// timeout per started stretch of [`AUDIO_SECS_PER_TIMEOUT`].
+    pub fn budget(&self, audio_secs: f32) -> Duration {
+        let stretches = (audio_secs / AUDIO_SECS_PER_TIMEOUT).ceil().max(1.0) as u64;
+        Duration::from_secs(self.timeout_secs * stretches)
+    }
+}
+
+/// Resolve the chain: selected provider first, then fallbacks in order.
+///
+/// Duplicates and blank entries are dropped.
+pub fn plan(
+    primary: &str,
+    primary_model: Option<String>,
+    default_timeout_secs: u64,
+    fallbacks: &[FallbackStep],
+) -> Vec<PlannedStep> {
+    let mut steps = vec![PlannedStep {
+        provider: primary.to_string(),
+        timeout_secs: default_timeout_secs,
+        model: primary_model,
+        primary: true,
+    }];
+    for step in fallbacks {
+        let provider = step.provider.trim();
+        if provider.is_empty() || steps.iter().any(|s| s.provider == provider) {
+            continue;
+        }
+        steps.push(PlannedStep {
+            provider: provider.to_string(),
+            timeout_secs: step.timeout_secs.unwrap_or(default_timeout_secs),
+            model: step
+                .model
+                .as_ref()
+                .map(|m| m.trim().to_string())
+                .filter(|m| !m.is_empty()),
+            primary: false,
+        });
+    }
+    steps.truncate(MAX_STEPS);
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(provider: &str, timeout_secs: Option<u64>) -> FallbackStep {
+        FallbackStep {
+            provider: provider.into(),
+            timeout_secs,
+            model: None,
+        }
+    }
+
+    #[test]
+    fn test_plan_orders_and_dedupes() {
+        let steps = plan(
+            "openrouter",
+            Some("google/gemini-3-flash-preview".into()),
+            45,
+            &[
+                step("openrouter", None),
+                step("openai_transcribe", Some(20)),
+                step(" ", None),
+                step("vosk", None),
+            ],
+        );
+        let providers: Vec<&str> = steps.iter().map(|s| s.provider.as_str()).collect();
+        assert_eq!(providers, ["openrouter", "openai_transcribe", "vosk"]);
+        assert!(steps[0].primary && !steps[1].primary);
+        assert_eq!(steps[1].timeout_secs, 20);
+        assert_eq!(steps[2].timeout_secs, 45);
+        assert_eq!(steps[1].model, None);
+    }
+
+    #[test]
+    fn test_budget_scales_with_audio_length() {
+        let steps = plan("whisper_local", None, 30, &[]);
+        assert_eq!(steps[0].budget(0.0), Duration::from_secs(30));
+        assert_eq!(steps[0].budget(120.0), Duration::from_secs(30));
+        assert_eq!(steps[0].budget(300.0), Duration::from_secs(90));
+    }
+
+    #[test]
+    fn test_plan_caps_length() {
+        let fallbacks: Vec<FallbackStep> =
+            ["a", "b", "c", "d", "e"].iter().map(|p| step(p, None)).collect();
+        assert_eq!(plan("vosk", None, 30, &fallbacks).len(), MAX_STEPS);
+    }
+}
@@ -0,0 +1,362 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use super::{chunking, language};
+use crate::error::AppError;
+
+/// Terms per utterance; more than this dilutes prompts and exceeds keyterm limits.
+pub const MAX_TERMS: usize = 100;
+/// Whisper only reads the last 224 tokens of its prompt.
+const MAX_HINT_CHARS: usize = 600;
+/// Key of the list applied to every language.
+pub const ALL_LANGUAGES: &str = "*";
+
+/// User vocabulary (product names, people, identifiers) keyed by language.
+///
+/// Keys are language codes ("en", "zh-tw") or [`ALL_LANGUAGES`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Glossary {
+    #[serde(default)]
+    pub lists: BTreeMap<String, Vec<String>>,
+}
+
+impl Glossary {
+    /// Trim terms, drop duplicates and empty lists, and lowercase language keys.
+    pub fn normalized(self) -> Self {
+        let mut lists: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (language, terms) in self.lists {
+            let language = normalize_language(&language);
+            let list = lists.entry(language).or_default();
+            for term in terms {
+                let term = term.split_whitespace().collect::<Vec<_>>().join(" ");
+                if !term.is_empty() && !list.contains(&term) {
+                    list.push(term);
+                }
+            }
+        }
+        lists.retain(|_, terms| !terms.is_empty());
+        Self { lists }
+    }
+
+    /// Add `other`'s terms to this glossary.
+    pub fn merge(self, other: Glossary) -> Self {
+        let mut lists = self.lists;
+        for (language, terms) in other.lists {
+            lists.entry(language).or_default().extend(terms);
+        }
+        Self { lists }.normalized()
+    }
+
+    /// Terms for an utterance in `language`: the shared list plus every list
+    /// of the same base language ("zh-tw" applies to "zh"). Auto mode gets
+    /// every list, since the language is unknown before transcription.
+    pub fn terms_for(&self, language: &str) -> Vec<String> {
+        let language = normalize_language(language);
+        let base = base_language(&language);
+        let every_list = language::is_auto(&language);
+        let mut terms: Vec<String> = Vec::new();
+        for (key, list) in &self.lists {
+            if !every_list && key != ALL_LANGUAGES && base_language(key) != base {
+                continue;
+            }
+            for term in list {
+                if !terms.contains(term) {
+                    terms.push(term.clone());
+                }
+            }
+        }
+        terms.truncate(MAX_TERMS);
+        terms
+    }
+
+    /// Parse an exported glossary (JSON) or a plain-text list.
+    ///
+    /// Plain text has one term per line, `#` comments, and `[lang]` headers;
+    /// terms before any header go to `default_language`.
+    pub fn parse(content: &str, default_language: &str) -> Result<Self, AppError> {
+        if content.trim_start().starts_with('{') {
+            let glossary: Glossary = serde_json::from_str(content)?;
+            return Ok(glossary.normalized());
+        }
+
+        let mut lists: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut language = default_language.to_string();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                language = header.trim().to_string();
+                continue;
+            }
+            lists.entry(language.clone()).or_default().push(line.to_string());
+        }
+        Ok(Self { lists }.normalized())
+    }
+}
+
+/// Prompt sentence listing `terms`, for engines biased through a text prompt.
+pub fn prompt_hint(terms: &[String]) -> Option<String> {
+    let mut listed: Vec<&str> = Vec::new();
+    let mut len = 0;
+    for term in terms {
+        if len + term.len() + 2 > MAX_HINT_CHARS {
+            break;
+        }
+        len += term.len() + 2;
+        listed.push(term);
+    }
+    if listed.is_empty() {
+        None
+    } else {
+        Some(format!("Vocabulary: {}.", listed.join(", ")))
+    }
+}
+
+/// Restore the glossary spelling of terms an engine split or flattened
+/// (e.g. Vosk's "open router" → "OpenRouter").
+///
+/// Only the matched words are rewritten; the rest of the text, including its
+/// spacing, is left as it was. A term written as one word needs
+/// [`MIN_SINGLE_WORD_CHARS`] letters to be matched, so short terms such as
+/// "Go" do not recase ordinary speech. Punctuation around the matched words
+/// is kept. Terms containing CJK characters are matched anywhere in the text,
+/// since Chinese and Japanese output has no spaces between words.
+pub fn apply_spelling(text: &str, terms: &[String]) -> String {
+    let keyed: Vec<(String, &String)> = terms
+        .iter()
+        .map(|term| (spelling_key(term), term))
+        .filter(|(key, _)| key.chars().count() >= 2)
+        .collect();
+    let (cjk, spaced): (Vec<_>, Vec<_>) =
+        keyed.into_iter().partition(|(_, term)| term.chars().any(chunking::is_cjk));
+    let text = respell_words(text, &spaced);
+    respell_cjk(&text, &cjk)
+}
+
+/// Longest run of words [`apply_spelling`] joins into one term.
+const MAX_WORDS: usize = 4;
+/// Shortest one-word term [`apply_spelling`] recases.
+const MIN_SINGLE_WORD_CHARS: usize = 4;
+/// A term never spans one of these.
+const CLAUSE_BREAKS: [char; 12] = [
+    ',', '.', ';', ':', '!', '?', '，', '。', '；', '：', '！', '？',
+];
+
+fn respell_words(text: &str, keyed: &[(String, &String)]) -> String {
+    if keyed.is_empty() {
+        return text.to_string();
+    }
+    let words = word_spans(text);
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut i = 0;
+    'outer: while i < words.len() {
+        for n in (1..=MAX_WORDS.min(words.len() - i)).rev() {
+            let window: Vec<&str> =
+                words[i..i + n].iter().map(|span| &text[span.clone()]).collect();
+            // A clause break inside the window means separate words.
+            if window[..n - 1].iter().any(|w| w.ends_with(CLAUSE_BREAKS)) {
+                continue;
+            }
+            let key = spelling_key(&window.concat());
+            if n == 1 && key.chars().count() < MIN_SINGLE_WORD_CHARS {
+                continue;
+            }
+            if let Some((_, term)) = keyed.iter().find(|(k, _)| *k == key) {
+                let punctuation = |c: char| !c.is_alphanumeric();
+                let (first, last) = (window[0], window[n - 1]);
+                let lead = first.len() - first.trim_start_matches(punctuation).len();
+                let start = words[i].start + lead;
+                let end = words[i + n - 1].start + last.trim_end_matches(punctuation).len();
+                out.push_str(&text[copied..start]);
+                out.push_str(term);
+                copied = end;
+                i += n;
+                continue 'outer;
+            }
+        }
+        i += 1;
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+/// Byte ranges of the whitespace-separated words of `text`.
+fn word_spans(text: &str) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some(i),
+            (true, Some(s)) => {
+                spans.push(s..i);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        spans.push(s..text.len());
+    }
+    spans
+}
+
+/// Match CJK terms by substring of the text's [`spelling_key`], so
+/// "小米 su7" and "小米su7" both become "小米SU7".
+fn respell_cjk(text: &str, keyed: &[(String, &String)]) -> String {
+    if keyed.is_empty() {
+        return text.to_string();
+    }
+    // Key characters of the text, each with the byte range it came from.
+    let mut key: Vec<(char, Range<usize>)> = Vec::new();
+    for (i, c) in text.char_indices() {
+        if c.is_alphanumeric() {
+            key.extend(c.to_lowercase().map(|lower| (lower, i..i + c.len_utf8())));
+        }
+    }
+    let mut terms: Vec<(Vec<char>, &String)> =
+        keyed.iter().map(|(k, term)| (k.chars().collect(), *term)).collect();
+    terms.sort_by_key(|(k, _)| std::cmp::Reverse(k.len()));
+
+    // A Latin edge of a term must not continue a Latin word in the text.
+    let latin = |c: Option<char>| c.is_some_and(|c| c.is_ascii_alphanumeric());
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut p = 0;
+    'outer: while p < key.len() {
+        for (term_key, term) in &terms {
+            let Some(window) = key.get(p..p + term_key.len()) else {
+                continue;
+            };
+            if !window.iter().map(|(c, _)| c).eq(term_key.iter()) {
+                continue;
+            }
+            let (start, end) = (window[0].1.start, window[window.len() - 1].1.end);
+            if text[start..end].contains(CLAUSE_BREAKS)
+                || (latin(term_key.first().copied()) && latin(text[..start].chars().next_back()))
+                || (latin(term_key.last().copied()) && latin(text[end..].chars().next()))
+            {
+                continue;
+            }
+            out.push_str(&text[copied..start]);
+            out.push_str(term);
+            copied = end;
+            p += term_key.len();
+            continue 'outer;
+        }
+        p += 1;
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+fn spelling_key(text: &str) -> String {
+    text.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn normalize_language(language: &str) -> String {
+    let language = language.trim().to_lowercase().replace('_', "-");
+    if language.is_empty() {
+        ALL_LANGUAGES.to_string()
+    } else {
+        language
+    }
+}
+
+fn base_language(language: &str) -> &str {
+    language.split('-').next().unwrap_or(language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glossary(entries: &[(&str, &[&str])]) -> Glossary {
+        Glossary {
+            lists: entries
+                .iter()
+                .map(|(lang, terms)| {
+                    (lang.to_string(), terms.iter().map(|t| t.to_string()).collect())
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_terms_for_merges_shared_and_language_lists() {
+        let g = glossary(&[
+            ("*", &["Voxlore", "OpenRouter"]),
+            ("zh-TW", &["語音助理", "Voxlore"]),
+            ("ja", &["音声入力"]),
+        ])
+        .normalized();
+        assert_eq!(g.terms_for("zh"), ["Voxlore", "OpenRouter", "語音助理"]);
+        assert_eq!(g.terms_for("en"), ["Voxlore", "OpenRouter"]);
+        assert_eq!(g.terms_for("auto").len(), 4);
+    }
+
+    #[test]
+    fn test_parse_plain_text_and_json() {
+        let g = Glossary::parse("# team\nAlice Chen\n\n[ja]\n音声入力\n", "*").unwrap();
+        assert_eq!(g, glossary(&[("*", &["Alice Chen"]), ("ja", &["音声入力"])]));
+
+        let json = serde_json::to_string(&g).unwrap();
+        assert_eq!(Glossary::parse(&json, "*").unwrap(), g);
+    }
+
+    #[test]
+    fn test_apply_spelling() {
+        let terms = vec!["OpenRouter".to_string(), "GitHub Actions".to_string()];
+        assert_eq!(
+            apply_spelling("send it to open router via github actions", &terms),
+            "send it to OpenRouter via GitHub Actions"
+        );
+        assert_eq!(prompt_hint(&terms).as_deref(), Some("Vocabulary: OpenRouter, GitHub Actions."));
+        assert_eq!(prompt_hint(&[]), None);
+    }
+
+    #[test]
+    fn test_apply_spelling_leaves_short_words_and_keeps_punctuation() {
+        let terms = vec!["Go".to_string(), "Tauri".to_string(), "OpenRouter".to_string()];
+        assert_eq!(apply_spelling("let's go home", &terms), "let's go home");
+        assert_eq!(
+            apply_spelling("Built with tauri, sent to (open router).", &terms),
+            "Built with Tauri, sent to (OpenRouter)."
+        );
+        // "open." ends a sentence, so it is not half of "OpenRouter".
+        assert_eq!(
+            apply_spelling("leave it open. router next", &terms),
+            "leave it open. router next"
+        );
+    }
+
+    #[test]
+    fn test_apply_spelling_keeps_separators() {
+        let terms = vec!["OpenRouter".to_string()];
+        assert_eq!(
+            apply_spelling("first line\n  send to open router\tnow", &terms),
+            "first line\n  send to OpenRouter\tnow"
+        );
+        assert_eq!(apply_spelling("  no  terms  here ", &terms), "  no  terms  here ");
+    }
+
+    #[test]
+    fn test_apply_spelling_matches_cjk_terms_by_substring() {
+        let terms = vec!["語音輸入助理".to_string(), "小米SU7".to_string(), "API".to_string()];
+        assert_eq!(
+            apply_spelling("我在用語音輸入助理，開小米 su7 上班", &terms),
+            "我在用語音輸入助理，開小米SU7 上班"
+        );
+        // Clause punctuation splits a term.
+        assert_eq!(apply_spelling("小米，su7", &terms), "小米，su7");
+        // A Latin edge must not be part of a longer word.
+        assert_eq!(apply_spelling("小米su78", &terms), "小米su78");
+    }
+}
@@ -0,0 +1,83 @@
+use crate::error::AppError;
+
+/// Language spoken in the probe clip.
+pub const PROBE_LANGUAGE: &str = "en";
+
+/// Share of the expected words a transcript must contain to pass.
+const MIN_WORD_RECALL: f32 = 0.5;
+
+/// A short spoken phrase and what it says.
+pub struct ProbeClip {
+    pub samples: Vec<i16>,
+    pub phrase: &'static str,
+}
+
+/// The clip embedded at build time from `resources/stt-probe.wav` and
+/// `resources/stt-probe.txt`; `build.rs` fails when either is missing.
+pub fn probe_clip() -> Result<ProbeClip, AppError> {
+    const AUDIO: &[u8] = include_bytes!("../../resources/stt-probe.wav");
+    const PHRASE: &str = include_str!("../../resources/stt-probe.txt");
+    Ok(ProbeClip {
+        samples: crate::audio::wav::decode_pcm16(AUDIO)?,
+        phrase: PHRASE.trim(),
+    })
+}
+
+/// What a probe transcription says about the configured engine.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProbeVerdict {
+    /// The phrase was recognized.
+    Recognized,
+    /// Nothing came back: the model most likely ignores audio input.
+    Empty,
+    /// Text came back but not the phrase, e.g. a chat model replying to
+    /// the prompt instead of transcribing.
+    Mismatch { recall: f32 },
+}
+
+/// Compare a transcript of the probe clip with the phrase it contains.
+pub fn assess(phrase: &str, transcript: &str) -> ProbeVerdict {
+    let heard = words(transcript);
+    if heard.is_empty() {
+        return ProbeVerdict::Empty;
+    }
+    let expected = words(phrase);
+    if expected.is_empty() {
+        return ProbeVerdict::Recognized;
+    }
+    let found = expected.iter().filter(|w| heard.contains(w)).count();
+    let recall = found as f32 / expected.len() as f32;
+    if recall >= MIN_WORD_RECALL {
+        ProbeVerdict::Recognized
+    } else {
+        ProbeVerdict::Mismatch { recall }
+    }
+}
+
+/// Lowercase words with punctuation stripped.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|w| w.trim_matches('\'').to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assess_tolerates_case_and_punctuation() {
+        let phrase = "The quick brown fox jumps over the lazy dog.";
+        assert_eq!(assess(phrase, "the quick brown fox jumped over a lazy dog"), ProbeVerdict::Recognized);
+        assert_eq!(assess(phrase, "  "), ProbeVerdict::Empty);
+        assert_eq!(assess(phrase, "..."), ProbeVerdict::Empty);
+    }
+
+    #[test]
+    fn test_assess_flags_replies_that_are_not_transcripts() {
+        let phrase = "The quick brown fox jumps over the lazy dog.";
+        let verdict = assess(phrase, "I'm sorry, I can't listen to audio. How can I help?");
+        assert!(matches!(verdict, ProbeVerdict::Mismatch { recall } if recall < MIN_WORD_RECALL));
+    }
+}
@@ -0,0 +1,266 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+
+use crate::error::AppError;
+
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Retry schedule shared by the cloud STT engines.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Total time for all attempts; no retry is scheduled past it.
+    pub budget: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+            budget: Duration::from_secs(45),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Default schedule bounded by `budget` (the cloud STT timeout).
+    pub fn within(budget: Duration) -> Self {
+        Self {
+            budget,
+            ..Default::default()
+        }
+    }
+
+    /// A single attempt bounded by `budget`, for checks that must answer quickly.
+    pub fn once(budget: Duration) -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::within(budget)
+        }
+    }
+
+    /// Exponential backoff for the retry after `attempt`, with jitter in the upper half.
+    fn backoff(&self, attempt: u32, seed: u64) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+            .min(self.max_delay);
+        let half = exp.as_millis() as u64 / 2;
+        Duration::from_millis(half + seed % (half + 1))
+    }
+
+    /// Delay before the next attempt, or `None` when attempts or budget are exhausted.
+    fn next_delay(
+        &self,
+        attempt: u32,
+        retry_after: Option<Duration>,
+        deadline: Instant,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        let delay = retry_after.unwrap_or_else(|| self.backoff(attempt, jitter_seed()));
+        (Instant::now() + delay < deadline).then_some(delay)
+    }
+}
+
+/// Final response of a (possibly retried) request.
+pub struct HttpReply {
+    pub status: StatusCode,
+    pub body: String,
+}
+
+/// Send a request, retrying 429/5xx responses and connection failures.
+///
+/// `build` is called once per attempt because multipart bodies cannot be
+/// cloned. `name` is the display name used in errors ("OpenAI"); its
+/// lowercase form is the `provider=` field of the `[stt-http]` log line.
+pub async fn send_with_retry<F>(
+    name: &str,
+    policy: &RetryPolicy,
+    build: F,
+) -> Result<HttpReply, AppError>
+where
+    F: Fn() -> Result<reqwest::RequestBuilder, AppError>,
+{
+    let provider = name.to_ascii_lowercase();
+    let deadline = Instant::now() + policy.budget;
+    let mut attempt = 1;
+
+    loop {
+        let request = build()?;
+        let request_id = next_request_id();
+        let started = Instant::now();
+
+        match request.send().await {
+            Ok(response) => {
+                // Headers are in: time to first byte, where connection setup shows.
+                let ttfb = started.elapsed();
+                let status = response.status();
+                let upstream_request_id = response_request_id(response.headers());
+                let retry_after = response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| parse_retry_after(v, Utc::now()));
+                let body = match response.text().await {
+                    Ok(body) => body,
+                    // The connection dropped mid-body: as retryable as a failed send.
+                    Err(e) => {
+                        crate::app_log!(
+                            "[stt-http] provider={} request_id={} status={} body=error latency_ms={} attempt={} error={}",
+                            provider, request_id, status, started.elapsed().as_millis(), attempt, e
+                        );
+                        if let Some(delay) = policy.next_delay(attempt, None, deadline) {
+                            crate::app_log!(
+                                "[stt-http] provider={} request_id={} retry_in_ms={}",
+                                provider, request_id, delay.as_millis()
+                            );
+                            tokio::time::sleep(delay).await;
+                            attempt += 1;
+                            continue;
+                        }
+                        return Err(AppError::Stt(format!("Failed to read response: {e}")));
+                    }
+                };
+                crate::app_log!(
+                    "[stt-http] provider={} request_id={} upstream_request_id={} status={} ttfb_ms={} latency_ms={} attempt={}",
+                    provider, request_id, upstream_request_id, status, ttfb.as_millis(), started.elapsed().as_millis(), attempt
+                );
+
+                if is_retryable_status(status) {
+                    if let Some(delay) = policy.next_delay(attempt, retry_after, deadline) {
+                        crate::app_log!(
+                            "[stt-http] provider={} request_id={} retry_in_ms={}",
+                            provider, request_id, delay.as_millis()
+                        );
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                        continue;
+                    }
+                }
+                return Ok(HttpReply { status, body });
+            }
+            Err(e) => {
+                crate::app_log!(
+                    "[stt-http] provider={} request_id={} status=error latency_ms={} attempt={} error={}",
+                    provider, request_id, started.elapsed().as_millis(), attempt, e
+                );
+                if e.is_connect() || e.is_timeout() || e.is_request() {
+                    if let Some(delay) = policy.next_delay(attempt, None, deadline) {
+                        crate::app_log!(
+                            "[stt-http] provider={} request_id={} retry_in_ms={}",
+                            provider, request_id, delay.as_millis()
+                        );
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                        continue;
+                    }
+                }
+                return Err(AppError::Stt(format!("{name} request failed: {e}")));
+            }
+        }
+    }
+}
+
+fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || matches!(status.as_u16(), 500 | 502 | 503 | 504)
+}
+
+/// `Retry-After` as delta-seconds or an HTTP date.
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        at.with_timezone(&Utc)
+            .signed_duration_since(now)
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+fn jitter_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| u64::from(d.subsec_nanos()))
+        .unwrap_or_default()
+}
+
+fn next_request_id() -> u64 {
+    NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Upstream request id from the usual response headers, or `n/a`.
+pub(crate) fn response_request_id(headers: &HeaderMap) -> String {
+    const CANDIDATES: [&str; 4] = ["x-request-id", "request-id", "x-correlation-id", "trace-id"];
+    for key in CANDIDATES {
+        if let Some(value) = headers.get(key).and_then(|v| v.to_str().ok()) {
+            if !value.trim().is_empty() {
+                return value.to_string();
+            }
+        }
+    }
+    "n/a".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = DateTime::parse_from_rfc2822("Wed, 21 Oct 2015 07:28:00 GMT")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(parse_retry_after("3", now), Some(Duration::from_secs(3)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:10 GMT", now),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:27:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_backoff_grows_with_jitter_and_caps() {
+        let policy = RetryPolicy::default();
+        for seed in [0, 7, 999_999] {
+            let first = policy.backoff(1, seed);
+            assert!(first >= Duration::from_millis(250) && first <= Duration::from_millis(500));
+            let third = policy.backoff(3, seed);
+            assert!(third >= Duration::from_millis(1000) && third <= Duration::from_millis(2000));
+            assert!(policy.backoff(20, seed) <= policy.max_delay);
+        }
+    }
+
+    #[test]
+    fn test_next_delay_respects_attempts_and_budget() {
+        let policy = RetryPolicy::within(Duration::from_secs(10));
+        let deadline = Instant::now() + policy.budget;
+        assert_eq!(
+            policy.next_delay(1, Some(Duration::from_secs(2)), deadline),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(policy.next_delay(1, Some(Duration::from_secs(30)), deadline), None);
+        assert_eq!(policy.next_delay(policy.max_attempts, None, deadline), None);
+        let once = RetryPolicy::once(Duration::from_secs(10));
+        assert_eq!(once.next_delay(1, Some(Duration::from_secs(2)), deadline), None);
+        assert!(is_retryable_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!is_retryable_status(StatusCode::UNAUTHORIZED));
+    }
+}
@@ -0,0 +1,117 @@
+/// `stt_language` value that detects the spoken language per utterance.
+pub const AUTO: &str = "auto";
+
+/// Language used for Chinese in auto mode. Detection cannot tell which script
+/// the user wants, so Traditional output is left to a configured `zh-TW`.
+const AUTO_CHINESE: &str = "zh";
+
+pub fn is_auto(language: &str) -> bool {
+    language.trim().eq_ignore_ascii_case(AUTO)
+}
+
+/// Language to send to an engine's API, or `None` to let it detect.
+pub fn request_language(language: &str) -> Option<&str> {
+    (!is_auto(language)).then_some(language)
+}
+
+/// Language of one utterance.
+///
+/// A fixed setting wins. In auto mode the engine's report is used when it
+/// names a known language, otherwise the text itself is inspected.
+pub fn resolve(requested: &str, detected: Option<&str>, text: &str) -> String {
+    if !is_auto(requested) {
+        return requested.to_string();
+    }
+    detected
+        .and_then(from_engine_code)
+        .or_else(|| detect_text_language(text))
+        .unwrap_or("en")
+        .to_string()
+}
+
+/// Map engine language reports ("zh", "zho", "<|yue|>", "English") to app codes.
+fn from_engine_code(code: &str) -> Option<&'static str> {
+    let code = code
+        .trim()
+        .trim_start_matches("<|")
+        .trim_end_matches("|>")
+        .to_lowercase();
+    let base = code.split(['-', '_']).next().unwrap_or_default();
+    Some(match base {
+        "zh" | "zho" | "chi" | "cmn" | "yue" | "chinese" => AUTO_CHINESE,
+        "en" | "eng" | "english" => "en",
+        "ja" | "jpn" | "japanese" => "ja",
+        "ko" | "kor" | "korean" => "ko",
+        "es" | "spa" | "spanish" => "es",
+        "fr" | "fra" | "fre" | "french" => "fr",
+        "de" | "deu" | "ger" | "german" => "de",
+        _ => return None,
+    })
+}
+
+/// Share of the text that must be Han for it to count as Chinese, so a name
+/// or a pasted symbol in an English sentence does not switch the language.
+const MIN_HAN_SHARE: f32 = 0.3;
+/// A Han character carries about as much as a four-letter Latin word.
+const LATIN_LETTERS_PER_HAN: usize = 4;
+
+fn han_share(han: usize, latin: usize) -> f32 {
+    let han = han * LATIN_LETTERS_PER_HAN;
+    han as f32 / (han + latin) as f32
+}
+
+/// Guess the language from its script: kana means Japanese, Hangul Korean,
+/// Han Chinese, and Latin letters English.
+pub fn detect_text_language(text: &str) -> Option<&'static str> {
+    let (mut han, mut kana, mut hangul, mut latin) = (0usize, 0usize, 0usize, 0usize);
+    for c in text.chars() {
+        match c {
+            '\u{3040}'..='\u{30FF}' => kana += 1,
+            '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' => hangul += 1,
+            '\u{3400}'..='\u{4DBF}' | '\u{4E00}'..='\u{9FFF}' | '\u{F900}'..='\u{FAFF}' => han += 1,
+            c if c.is_ascii_alphabetic() => latin += 1,
+            _ => {}
+        }
+    }
+    // Japanese mixes kanji with kana; a few kana are enough to tell it from Chinese.
+    if kana > 0 && kana * 5 >= han {
+        Some("ja")
+    } else if hangul > 0 && hangul >= han {
+        Some("ko")
+    } else if han > 0 && han_share(han, latin) >= MIN_HAN_SHARE {
+        Some(AUTO_CHINESE)
+    } else if latin > 0 {
+        Some("en")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_prefers_fixed_setting_then_engine_then_text() {
+        assert_eq!(resolve("ja", Some("en"), "hello"), "ja");
+        assert_eq!(resolve("auto", Some("zh"), "hello"), "zh");
+        assert_eq!(resolve("auto", Some("<|yue|>"), ""), "zh");
+        assert_eq!(resolve("auto", Some("eng"), ""), "en");
+        assert_eq!(resolve("auto", Some("xx"), "今日はいい天気ですね"), "ja");
+        assert_eq!(resolve("auto", None, ""), "en");
+    }
+
+    #[test]
+    fn test_detect_text_language() {
+        assert_eq!(detect_text_language("我們明天部署新版本"), Some("zh"));
+        assert_eq!(detect_text_language("幫我把 pull request merge 進 main"), Some("zh"));
+        assert_eq!(detect_text_language("Please send the invoice to 王 before Friday"), Some("en"));
+        assert_eq!(detect_text_language("The build passed ✓ and 好 was the only reply"), Some("en"));
+        assert_eq!(detect_text_language("明日の会議は十時からです"), Some("ja"));
+        assert_eq!(detect_text_language("안녕하세요"), Some("ko"));
+        assert_eq!(detect_text_language("Ship the release today"), Some("en"));
+        assert_eq!(detect_text_language("123"), None);
+        assert_eq!(request_language("auto"), None);
+        assert_eq!(request_language("en"), Some("en"));
+    }
+}
@@ -1,13 +1,22 @@
 use reqwest::multipart;
 
+use super::http::{send_with_retry, RetryPolicy};
+use super::language;
+use super::openai_whisper::OpenAiWhisperEngine;
+use super::options::MistralOptions;
 use super::{CloudSttEngine, SttConfig, SttResult};
 use crate::error::AppError;
 
+const DEFAULT_BASE_URL: &str = "https://api.mistral.ai/v1";
+
 /// Mistral Vox STT engine.
 pub struct MistralEngine {
     api_key: String,
     model: String,
+    base_url: String,
+    options: MistralOptions,
     client: reqwest::Client,
+    retry: RetryPolicy,
 }
 
 impl MistralEngine {
@@ -15,37 +24,60 @@ impl MistralEngine {
         Self {
             api_key,
             model: model.unwrap_or_else(|| "mistral-vox-latest".to_string()),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            options: MistralOptions::default(),
             client: reqwest::Client::new(),
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Use a client built from the shared network settings.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Apply advanced options, including the endpoint override.
+    pub fn with_options(mut self, options: MistralOptions) -> Self {
+        if let Some(base_url) = &options.base_url {
+            self.base_url = base_url.clone();
         }
+        self.options = options;
+        self
     }
 }
 
 impl CloudSttEngine for MistralEngine {
     async fn transcribe(&self, audio_data: &[u8], config: &SttConfig) -> Result<SttResult, AppError> {
-        let audio_part = multipart::Part::bytes(audio_data.to_vec())
-            .file_name("audio.wav")
-            .mime_str("audio/wav")
-            .map_err(|e| AppError::Stt(format!("Failed to create multipart: {e}")))?;
-
-        let form = multipart::Form::new()
-            .part("file", audio_part)
-            .text("model", self.model.clone())
-            .text("language", config.language.clone());
+        let reply = send_with_retry("Mistral", &self.retry, || {
+            let audio_part = multipart::Part::bytes(audio_data.to_vec())
+                .file_name("audio.wav")
+                .mime_str("audio/wav")
+                .map_err(|e| AppError::Stt(format!("Failed to create multipart: {e}")))?;
 
-        let response = self
-            .client
-            .post("https://api.mistral.ai/v1/audio/transcriptions")
-            .bearer_auth(&self.api_key)
-            .multipart(form)
-            .send()
-            .await
-            .map_err(|e| AppError::Stt(format!("Mistral request failed: {e}")))?;
+            let mut form = multipart::Form::new()
+                .part("file", audio_part)
+                .text("model", self.model.clone());
+            if let Some(language) = language::request_language(&config.language) {
+                form = form.text("language", language.to_string());
+            }
+            if let Some(granularity) = self.options.timestamp_granularity {
+                form = form.text("timestamp_granularities", granularity.as_str().to_string());
+            }
 
-        let status = response.status();
-        let body = response
-            .text()
-            .await
-            .map_err(|e| AppError::Stt(format!("Failed to read response: {e}")))?;
+            Ok(self
+                .client
+                .post(format!("{}/audio/transcriptions", self.base_url))
+                .bearer_auth(&self.api_key)
+                .multipart(form))
+        })
+        .await?;
+        let (status, body) = (reply.status, reply.body);
 
         if !status.is_success() {
             return Err(AppError::Stt(format!(
@@ -56,13 +88,8 @@ impl CloudSttEngine for MistralEngine {
         let json: serde_json::Value = serde_json::from_str(&body)
             .map_err(|e| AppError::Stt(format!("Failed to parse response: {e}")))?;
 
-        let text = json["text"].as_str().unwrap_or_default().to_string();
-
-        Ok(SttResult {
-            text,
-            confidence: None,
-            language_detected: None,
-        })
+        // Same shape as OpenAI's; `segments` appear when timestamps were requested.
+        Ok(OpenAiWhisperEngine::parse_response(&json))
     }
 
     fn provider_name(&self) -> &str {
@@ -1,9 +1,29 @@
+pub mod chunking;
 pub mod converter;
 pub mod elevenlabs;
+pub mod escalation;
+pub mod failover;
+pub mod glossary;
+pub mod health;
+pub mod http;
+pub mod language;
 pub mod mistral;
 pub mod openai_whisper;
 pub mod openrouter_audio;
+pub mod options;
+pub mod probe;
+pub mod queue;
+pub mod race;
+pub mod registry;
+pub mod routing;
+pub mod sherpa_engine;
+pub mod subtitle;
+pub mod transcript;
 pub mod vosk_engine;
+pub mod whisper_engine;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
@@ -14,6 +34,9 @@ use crate::error::AppError;
 pub struct SttConfig {
     pub language: String,
     pub sample_rate: u32,
+    /// Glossary terms for this utterance's language, injected the engine's native way.
+    #[serde(default)]
+    pub glossary: Vec<String>,
 }
 
 impl Default for SttConfig {
@@ -21,16 +44,100 @@ impl Default for SttConfig {
         Self {
             language: "en".to_string(),
             sample_rate: 16000,
+            glossary: Vec::new(),
         }
     }
 }
 
 /// Result from STT processing.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SttResult {
     pub text: String,
     pub confidence: Option<f32>,
     pub language_detected: Option<String>,
+    /// Word-level timing, empty when the engine does not report it.
+    #[serde(default)]
+    pub words: Vec<SttWord>,
+    /// N-best transcripts, best first (includes the chosen `text`). Empty when unavailable.
+    #[serde(default)]
+    pub alternatives: Vec<SttAlternative>,
+    /// Phrase-level timing, empty when the engine does not report it.
+    #[serde(default)]
+    pub segments: Vec<SttSegment>,
+}
+
+/// A timed stretch of the transcript (seconds), as Whisper segments it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SttSegment {
+    pub text: String,
+    pub start: f32,
+    pub end: f32,
+    pub confidence: Option<f32>,
+    /// Speaker label when the engine diarized the audio.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+}
+
+/// An alternative hypothesis for the whole transcript.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SttAlternative {
+    pub text: String,
+    /// Engine-specific score; only comparable within one result (higher is better).
+    pub confidence: f32,
+}
+
+/// A recognized word with its position in the audio (seconds).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SttWord {
+    pub word: String,
+    pub start: f32,
+    pub end: f32,
+    pub confidence: Option<f32>,
+}
+
+/// Utterance confidence as the duration-weighted mean of word confidences.
+///
+/// Returns `None` when no word carries a confidence score.
+pub fn utterance_confidence(words: &[SttWord]) -> Option<f32> {
+    let mut weighted = 0.0f32;
+    let mut total = 0.0f32;
+    for word in words {
+        if let Some(conf) = word.confidence {
+            // Zero-length words still count, so a single-frame token is not ignored.
+            let weight = (word.end - word.start).max(0.01);
+            weighted += conf * weight;
+            total += weight;
+        }
+    }
+    if total > 0.0 {
+        Some(weighted / total)
+    } else {
+        None
+    }
+}
+
+/// Run a local engine pass on a blocking thread, so failover timeouts,
+/// races and cancellation are not held up by decoding.
+///
+/// Dropping the returned future raises the flag handed to `pass`. Every local
+/// engine checks it while decoding, so a lost race, a timeout or a cancel
+/// stops the pass instead of letting it finish unseen.
+pub async fn run_blocking<F>(engine: &str, pass: F) -> Result<SttResult, AppError>
+where
+    F: FnOnce(&AtomicBool) -> Result<SttResult, AppError> + Send + 'static,
+{
+    struct StopOnDrop(Arc<AtomicBool>);
+    impl Drop for StopOnDrop {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::Release);
+        }
+    }
+
+    let stop = StopOnDrop(Arc::new(AtomicBool::new(false)));
+    let flag = stop.0.clone();
+    tokio::task::spawn_blocking(move || pass(&flag))
+        .await
+        .map_err(|e| AppError::Stt(format!("{engine} task failed: {e}")))?
 }
 
 /// Trait for cloud STT engines that process complete audio buffers.
@@ -39,26 +146,39 @@ pub trait CloudSttEngine: Send + Sync {
     /// Transcribe a complete audio buffer (WAV format).
     async fn transcribe(&self, audio_data: &[u8], config: &SttConfig) -> Result<SttResult, AppError>;
 
+    /// Longest audio sent in one request; longer recordings are split by [`chunking`].
+    fn max_chunk_secs(&self) -> u32 {
+        120
+    }
+
     /// Get the provider name for display.
     #[allow(dead_code)]
     fn provider_name(&self) -> &str;
 }
 
-/// Supported STT providers.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub enum SttProvider {
-    #[serde(rename = "vosk")]
-    Vosk,
-    #[serde(rename = "elevenlabs")]
-    ElevenLabs,
-    #[serde(rename = "openai")]
-    OpenAI,
-    #[serde(rename = "openai_transcribe")]
-    OpenAITranscribe,
-    #[serde(rename = "openrouter")]
-    OpenRouter,
-    #[serde(rename = "custom_openai_compatible")]
-    CustomOpenAiCompatible,
-    #[serde(rename = "mistral")]
-    Mistral,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(start: f32, end: f32, confidence: Option<f32>) -> SttWord {
+        SttWord {
+            word: "w".into(),
+            start,
+            end,
+            confidence,
+        }
+    }
+
+    #[test]
+    fn test_utterance_confidence_weights_by_duration() {
+        let words = vec![word(0.0, 0.9, Some(1.0)), word(0.9, 1.0, Some(0.0))];
+        let conf = utterance_confidence(&words).unwrap();
+        assert!((conf - 0.9).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_utterance_confidence_without_scores() {
+        assert_eq!(utterance_confidence(&[]), None);
+        assert_eq!(utterance_confidence(&[word(0.0, 1.0, None)]), None);
+    }
 }
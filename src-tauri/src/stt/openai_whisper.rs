@@ -1,6 +1,10 @@
 use reqwest::multipart;
 
-use super::{CloudSttEngine, SttConfig, SttResult};
+use super::glossary;
+use super::http::{send_with_retry, RetryPolicy};
+use super::language;
+use super::options::WhisperOptions;
+use super::{CloudSttEngine, SttConfig, SttResult, SttSegment, SttWord};
 use crate::error::AppError;
 
 /// OpenAI Whisper STT engine.
@@ -8,7 +12,9 @@ pub struct OpenAiWhisperEngine {
     api_key: String,
     model: String,
     base_url: String,
+    options: WhisperOptions,
     client: reqwest::Client,
+    retry: RetryPolicy,
 }
 
 impl OpenAiWhisperEngine {
@@ -22,11 +28,96 @@ impl OpenAiWhisperEngine {
             api_key,
             model,
             base_url,
+            options: WhisperOptions::default(),
             client: reqwest::Client::new(),
+            retry: RetryPolicy::default(),
         }
     }
 
-    fn build_prompt(language: &str) -> Option<String> {
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Use a client built from the shared network settings.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub fn with_options(mut self, options: WhisperOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Only the `whisper-*` models return `verbose_json` with timestamps;
+    /// the gpt-4o transcribe models accept `json` alone.
+    fn supports_timestamps(&self) -> bool {
+        self.model.to_ascii_lowercase().contains("whisper")
+    }
+
+    /// Parse an OpenAI-style transcription response; other `/audio/transcriptions`
+    /// APIs (Mistral) share the shape.
+    pub(super) fn parse_response(json: &serde_json::Value) -> SttResult {
+        let segments: Vec<SttSegment> = json["segments"]
+            .as_array()
+            .map(|segments| {
+                segments
+                    .iter()
+                    .filter_map(|s| {
+                        Some(SttSegment {
+                            text: s["text"].as_str()?.trim().to_string(),
+                            start: s["start"].as_f64()? as f32,
+                            end: s["end"].as_f64()? as f32,
+                            confidence: s["avg_logprob"].as_f64().map(|lp| lp.exp() as f32),
+                            speaker: None,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let words: Vec<SttWord> = json["words"]
+            .as_array()
+            .map(|words| {
+                words
+                    .iter()
+                    .filter_map(|w| {
+                        Some(SttWord {
+                            word: w["word"].as_str()?.trim().to_string(),
+                            start: w["start"].as_f64()? as f32,
+                            end: w["end"].as_f64()? as f32,
+                            confidence: None,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Duration-weighted mean of the per-segment confidences.
+        let (weighted, total) = segments
+            .iter()
+            .filter_map(|s| Some((s.confidence?, (s.end - s.start).max(0.0))))
+            .fold((0.0f32, 0.0f32), |(w, t), (c, d)| (w + c * d, t + d));
+
+        SttResult {
+            text: json["text"].as_str().unwrap_or_default().trim().to_string(),
+            confidence: (total > 0.0).then(|| weighted / total),
+            language_detected: json["language"].as_str().map(String::from),
+            words,
+            segments,
+            ..Default::default()
+        }
+    }
+
+    fn build_prompt(language: &str, glossary: &[String]) -> Option<String> {
+        let base = Self::language_prompt(language);
+        match (base, glossary::prompt_hint(glossary)) {
+            (Some(base), Some(hint)) => Some(format!("{base} {hint}")),
+            (base, hint) => base.or(hint),
+        }
+    }
+
+    fn language_prompt(language: &str) -> Option<String> {
         let lang = language.to_ascii_lowercase();
         if lang == "zh" || lang == "zh-tw" {
             Some(
@@ -45,34 +136,42 @@ impl OpenAiWhisperEngine {
 
 impl CloudSttEngine for OpenAiWhisperEngine {
     async fn transcribe(&self, audio_data: &[u8], config: &SttConfig) -> Result<SttResult, AppError> {
-        let audio_part = multipart::Part::bytes(audio_data.to_vec())
-            .file_name("audio.wav")
-            .mime_str("audio/wav")
-            .map_err(|e| AppError::Stt(format!("Failed to create multipart: {e}")))?;
-
-        let mut form = multipart::Form::new()
-            .part("file", audio_part)
-            .text("model", self.model.clone())
-            .text("language", config.language.clone())
-            .text("response_format", "json".to_string());
-        if let Some(prompt) = Self::build_prompt(&config.language) {
-            form = form.text("prompt", prompt);
-        }
+        let prompt = Self::build_prompt(&config.language, &config.glossary);
+        let timestamps = self.supports_timestamps();
+        let reply = send_with_retry("OpenAI", &self.retry, || {
+            let audio_part = multipart::Part::bytes(audio_data.to_vec())
+                .file_name("audio.wav")
+                .mime_str("audio/wav")
+                .map_err(|e| AppError::Stt(format!("Failed to create multipart: {e}")))?;
 
-        let response = self
-            .client
-            .post(format!("{}/audio/transcriptions", self.base_url))
-            .bearer_auth(&self.api_key)
-            .multipart(form)
-            .send()
-            .await
-            .map_err(|e| AppError::Stt(format!("OpenAI request failed: {e}")))?;
-
-        let status = response.status();
-        let body = response
-            .text()
-            .await
-            .map_err(|e| AppError::Stt(format!("Failed to read response: {e}")))?;
+            let mut form = multipart::Form::new()
+                .part("file", audio_part)
+                .text("model", self.model.clone());
+            form = if timestamps {
+                form.text("response_format", "verbose_json".to_string())
+                    .text("timestamp_granularities[]", "segment".to_string())
+                    .text("timestamp_granularities[]", "word".to_string())
+            } else {
+                form.text("response_format", "json".to_string())
+            };
+            if let Some(language) = language::request_language(&config.language) {
+                form = form.text("language", language.to_string());
+            }
+            if let Some(prompt) = &prompt {
+                form = form.text("prompt", prompt.clone());
+            }
+            if let Some(temperature) = self.options.temperature {
+                form = form.text("temperature", temperature.to_string());
+            }
+
+            Ok(self
+                .client
+                .post(format!("{}/audio/transcriptions", self.base_url))
+                .bearer_auth(&self.api_key)
+                .multipart(form))
+        })
+        .await?;
+        let (status, body) = (reply.status, reply.body);
 
         if !status.is_success() {
             return Err(AppError::Stt(format!("OpenAI API error ({status}): {body}")));
@@ -81,16 +180,43 @@ impl CloudSttEngine for OpenAiWhisperEngine {
         let json: serde_json::Value = serde_json::from_str(&body)
             .map_err(|e| AppError::Stt(format!("Failed to parse response: {e}")))?;
 
-        let text = json["text"].as_str().unwrap_or_default().to_string();
-
-        Ok(SttResult {
-            text,
-            confidence: None,
-            language_detected: json["language"].as_str().map(String::from),
-        })
+        Ok(Self::parse_response(&json))
     }
 
     fn provider_name(&self) -> &str {
         "OpenAI Whisper"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_verbose_json() {
+        let json = serde_json::json!({
+            "text": " Ship it today. ",
+            "language": "english",
+            "segments": [
+                {"text": " Ship it", "start": 0.0, "end": 1.0, "avg_logprob": -0.1},
+                {"text": " today.", "start": 1.0, "end": 2.0, "avg_logprob": -0.3}
+            ],
+            "words": [
+                {"word": "Ship", "start": 0.1, "end": 0.4},
+                {"word": "it", "start": 0.5, "end": 0.7},
+                {"word": "today", "start": 1.1, "end": 1.6}
+            ]
+        });
+        let result = OpenAiWhisperEngine::parse_response(&json);
+        assert_eq!(result.text, "Ship it today.");
+        assert_eq!(result.segments.len(), 2);
+        assert_eq!(result.segments[1].text, "today.");
+        assert_eq!(result.words.len(), 3);
+        let expected = ((-0.1f32).exp() + (-0.3f32).exp()) / 2.0;
+        assert!((result.confidence.unwrap() - expected).abs() < 1e-4);
+
+        // Plain `json` responses carry no timing.
+        let plain = OpenAiWhisperEngine::parse_response(&serde_json::json!({"text": "hi"}));
+        assert!(plain.segments.is_empty() && plain.confidence.is_none());
+    }
+}
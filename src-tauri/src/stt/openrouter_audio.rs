@@ -1,9 +1,9 @@
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::Instant;
-
 use base64::Engine as _;
 use serde_json::json;
 
+use super::glossary;
+use super::http::{send_with_retry, RetryPolicy};
+use super::language;
 use super::{CloudSttEngine, SttConfig, SttResult};
 use crate::error::AppError;
 
@@ -14,10 +14,9 @@ pub struct OpenRouterAudioEngine {
     model: String,
     base_url: String,
     client: reqwest::Client,
+    retry: RetryPolicy,
 }
 
-static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
-
 impl OpenRouterAudioEngine {
     pub fn new(api_key: String, model: Option<String>, base_url: Option<String>) -> Self {
         let base_url = base_url
@@ -38,43 +37,49 @@ impl OpenRouterAudioEngine {
             model,
             base_url,
             client: reqwest::Client::new(),
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Use a client built from the shared network settings.
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    fn transcription_prompt(language: &str, glossary: &[String]) -> String {
+        let prompt = Self::language_prompt(language);
+        match glossary::prompt_hint(glossary) {
+            Some(hint) => format!("{prompt}\n{hint}"),
+            None => prompt,
         }
     }
 
-    fn transcription_prompt(language: &str) -> String {
+    fn language_prompt(language: &str) -> String {
         let lang = language.to_ascii_lowercase();
         if lang == "zh" || lang == "zh-tw" {
             "這是 speech-to-text 情境。請直接將已提供音訊轉成「臺灣繁體中文為主」逐字稿，不要解釋。禁止要求影片連結、上傳檔案或補充來源；也不要回覆無法存取影片或音訊。若原音含英文句子、英文術語、產品名、API 名稱、程式碼或縮寫，請保留原文英文，不要翻譯成中文。".to_string()
         } else if lang.starts_with("ja") {
             "日本語の音声をそのまま文字起こししてください。説明は不要です。".to_string()
+        } else if language::is_auto(&lang) {
+            "Transcribe the audio verbatim in the language spoken, without translating. Write Chinese in Traditional characters (Taiwan). Return plain transcript text only. No explanation.".to_string()
         } else if lang.starts_with("en") {
             "Return plain English transcript text only. No explanation.".to_string()
         } else {
             "Return plain transcript text only. No explanation.".to_string()
         }
     }
-
-    fn next_request_id() -> u64 {
-        NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed)
-    }
-
-    fn response_request_id(headers: &reqwest::header::HeaderMap) -> String {
-        const CANDIDATES: [&str; 4] = ["x-request-id", "request-id", "x-correlation-id", "trace-id"];
-        for key in CANDIDATES {
-            if let Some(value) = headers.get(key).and_then(|v| v.to_str().ok()) {
-                if !value.trim().is_empty() {
-                    return value.to_string();
-                }
-            }
-        }
-        "n/a".to_string()
-    }
 }
 
 impl CloudSttEngine for OpenRouterAudioEngine {
     async fn transcribe(&self, audio_data: &[u8], config: &SttConfig) -> Result<SttResult, AppError> {
         let b64_audio = base64::engine::general_purpose::STANDARD.encode(audio_data);
-        let prompt = Self::transcription_prompt(&config.language);
+        let prompt = Self::transcription_prompt(&config.language, &config.glossary);
 
         let body = json!({
             "model": self.model,
@@ -95,34 +100,15 @@ impl CloudSttEngine for OpenRouterAudioEngine {
             "max_tokens": 4096
         });
 
-        let mut request = self
-            .client
-            .post(format!("{}/chat/completions", self.base_url))
-            .bearer_auth(&self.api_key)
-            .json(&body);
-        if self.base_url.contains("openrouter.ai") {
-            request = request
-                .header("HTTP-Referer", "https://voxlore.app")
-                .header("X-Title", "Voxlore");
-        }
-        let started = Instant::now();
-        let response = request
-            .send()
-            .await
-            .map_err(|e| AppError::Stt(format!("OpenRouter request failed: {e}")))?;
-
-        let status = response.status();
-        let request_id = Self::response_request_id(response.headers());
-        let body_text = response
-            .text()
-            .await
-            .map_err(|e| AppError::Stt(format!("Failed to read response: {e}")))?;
-        let latency_ms = started.elapsed().as_millis();
-        let local_request_id = Self::next_request_id();
-        crate::app_log!(
-            "[stt-http] provider=openrouter request_id={} upstream_request_id={} status={} latency_ms={}",
-            local_request_id, request_id, status, latency_ms
-        );
+        let reply = send_with_retry("OpenRouter", &self.retry, || {
+            Ok(self
+                .client
+                .post(format!("{}/chat/completions", self.base_url))
+                .bearer_auth(&self.api_key)
+                .json(&body))
+        })
+        .await?;
+        let (status, body_text) = (reply.status, reply.body);
 
         if !status.is_success() {
             return Err(AppError::Stt(format!(
@@ -143,9 +129,15 @@ impl CloudSttEngine for OpenRouterAudioEngine {
             text,
             confidence: None,
             language_detected: Some(config.language.clone()),
+            ..Default::default()
         })
     }
 
+    /// Chat completions cap the reply at `max_tokens: 4096`; keep each transcript well inside it.
+    fn max_chunk_secs(&self) -> u32 {
+        60
+    }
+
     fn provider_name(&self) -> &str {
         "OpenRouter Audio (Experimental)"
     }
@@ -0,0 +1,225 @@
+use serde::{Deserialize, Serialize};
+
+use super::registry::{EngineKind, ProviderDescriptor};
+use crate::error::AppError;
+
+/// Advanced request settings for one STT provider, tagged with the engine
+/// they are written for so they can be checked against the provider.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "engine", rename_all = "snake_case")]
+pub enum SttProviderOptions {
+    ElevenLabs(ElevenLabsOptions),
+    Mistral(MistralOptions),
+    /// `/audio/transcriptions` endpoints: OpenAI Whisper, Groq, local servers.
+    OpenAiTranscriptions(WhisperOptions),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ElevenLabsOptions {
+    /// Label who is speaking; speaker turns come back as segments.
+    pub diarize: bool,
+    /// Expected number of speakers (1–32); needs `diarize`.
+    pub num_speakers: Option<u32>,
+    /// Tag non-speech sounds such as `(laughter)`; the API's default when unset.
+    pub tag_audio_events: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MistralOptions {
+    /// Ask for segment or word timings.
+    pub timestamp_granularity: Option<TimestampGranularity>,
+    /// Replaces `https://api.mistral.ai/v1`, e.g. for a proxy or a regional endpoint.
+    pub base_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampGranularity {
+    Segment,
+    Word,
+}
+
+impl TimestampGranularity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Segment => "segment",
+            Self::Word => "word",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WhisperOptions {
+    /// Sampling temperature (0–1); lower is more literal.
+    pub temperature: Option<f32>,
+}
+
+impl SttProviderOptions {
+    pub fn engine(&self) -> EngineKind {
+        match self {
+            Self::ElevenLabs(_) => EngineKind::ElevenLabs,
+            Self::Mistral(_) => EngineKind::Mistral,
+            Self::OpenAiTranscriptions(_) => EngineKind::OpenAiTranscriptions,
+        }
+    }
+
+    /// Trim the Mistral endpoint the same way the STT base URL is trimmed.
+    pub fn normalized(self) -> Self {
+        match self {
+            Self::Mistral(options) => Self::Mistral(MistralOptions {
+                base_url: options
+                    .base_url
+                    .map(|u| u.trim().trim_end_matches('/').to_string())
+                    .filter(|u| !u.is_empty()),
+                ..options
+            }),
+            other => other,
+        }
+    }
+
+    /// Check that `provider` speaks the protocol these options are for and
+    /// that every value is in range.
+    pub fn validate(&self, provider: &ProviderDescriptor) -> Result<(), AppError> {
+        let invalid = |message: String| Err(AppError::Stt(format!("{}: {message}", provider.label)));
+        if provider.engine != self.engine() {
+            let fields = self.fields_set();
+            return invalid(format!(
+                "{} options are not supported here ({})",
+                self.label(),
+                if fields.is_empty() { "none set".to_string() } else { fields.join(", ") }
+            ));
+        }
+        match self {
+            Self::ElevenLabs(options) => match options.num_speakers {
+                Some(_) if !options.diarize => invalid("numSpeakers needs diarize".into()),
+                Some(n) if !(1..=32).contains(&n) => {
+                    invalid(format!("numSpeakers must be 1–32, got {n}"))
+                }
+                _ => Ok(()),
+            },
+            Self::Mistral(options) => match options.base_url.as_deref() {
+                Some(url) if reqwest::Url::parse(url)
+                    .map(|u| !matches!(u.scheme(), "http" | "https"))
+                    .unwrap_or(true) =>
+                {
+                    invalid(format!("baseUrl must be an http(s) URL, got '{url}'"))
+                }
+                _ => Ok(()),
+            },
+            Self::OpenAiTranscriptions(options) => match options.temperature {
+                Some(t) if !(0.0..=1.0).contains(&t) => {
+                    invalid(format!("temperature must be 0–1, got {t}"))
+                }
+                _ => Ok(()),
+            },
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::ElevenLabs(_) => "ElevenLabs",
+            Self::Mistral(_) => "Mistral",
+            Self::OpenAiTranscriptions(_) => "Whisper",
+        }
+    }
+
+    /// Names of the options that differ from the defaults.
+    fn fields_set(&self) -> Vec<&'static str> {
+        let mut fields = Vec::new();
+        match self {
+            Self::ElevenLabs(o) => {
+                if o.diarize {
+                    fields.push("diarize");
+                }
+                if o.num_speakers.is_some() {
+                    fields.push("numSpeakers");
+                }
+                if o.tag_audio_events.is_some() {
+                    fields.push("tagAudioEvents");
+                }
+            }
+            Self::Mistral(o) => {
+                if o.timestamp_granularity.is_some() {
+                    fields.push("timestampGranularity");
+                }
+                if o.base_url.is_some() {
+                    fields.push("baseUrl");
+                }
+            }
+            Self::OpenAiTranscriptions(o) => {
+                if o.temperature.is_some() {
+                    fields.push("temperature");
+                }
+            }
+        }
+        fields
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stt::registry::builtin_providers;
+
+    fn provider(id: &str) -> ProviderDescriptor {
+        builtin_providers().into_iter().find(|p| p.id == id).unwrap()
+    }
+
+    #[test]
+    fn test_options_deserialize_by_engine_tag() {
+        let options: SttProviderOptions = serde_json::from_str(
+            r#"{"engine": "eleven_labs", "diarize": true, "numSpeakers": 2}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            options,
+            SttProviderOptions::ElevenLabs(ElevenLabsOptions {
+                diarize: true,
+                num_speakers: Some(2),
+                tag_audio_events: None,
+            })
+        );
+        let mistral: SttProviderOptions = serde_json::from_str(
+            r#"{"engine": "mistral", "timestampGranularity": "segment", "baseUrl": " https://eu.mistral.test/v1/ "}"#,
+        )
+        .unwrap();
+        let SttProviderOptions::Mistral(mistral) = mistral.normalized() else {
+            panic!("expected Mistral options");
+        };
+        assert_eq!(mistral.base_url.as_deref(), Some("https://eu.mistral.test/v1"));
+        assert_eq!(mistral.timestamp_granularity, Some(TimestampGranularity::Segment));
+    }
+
+    #[test]
+    fn test_validate_rejects_other_engines_and_bad_values() {
+        let diarize = SttProviderOptions::ElevenLabs(ElevenLabsOptions {
+            diarize: true,
+            ..Default::default()
+        });
+        assert!(diarize.validate(&provider("elevenlabs")).is_ok());
+        let err = diarize.validate(&provider("mistral")).unwrap_err().to_string();
+        assert!(err.contains("ElevenLabs options are not supported") && err.contains("diarize"), "{err}");
+
+        let speakers = SttProviderOptions::ElevenLabs(ElevenLabsOptions {
+            num_speakers: Some(2),
+            ..Default::default()
+        });
+        assert!(speakers.validate(&provider("elevenlabs")).is_err());
+
+        let hot = SttProviderOptions::OpenAiTranscriptions(WhisperOptions { temperature: Some(1.5) });
+        assert!(hot.validate(&provider("openai")).is_err());
+        let cool = SttProviderOptions::OpenAiTranscriptions(WhisperOptions { temperature: Some(0.2) });
+        assert!(cool.validate(&provider("openai_transcribe")).is_ok());
+        // OpenRouter speaks the chat-audio protocol, which has no temperature field here.
+        assert!(cool.validate(&provider("openrouter")).is_err());
+
+        let ftp = SttProviderOptions::Mistral(MistralOptions {
+            base_url: Some("ftp://mistral.test".into()),
+            ..Default::default()
+        });
+        assert!(ftp.validate(&provider("mistral")).is_err());
+    }
+}
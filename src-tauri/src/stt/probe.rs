@@ -0,0 +1,126 @@
+use base64::Engine as _;
+use reqwest::{multipart, StatusCode};
+use serde_json::json;
+
+use super::registry::CustomSttProtocol;
+use crate::audio::wav;
+use crate::error::AppError;
+
+/// Half a second of silence: enough for a server to accept the request.
+const PROBE_SAMPLES: usize = 8000;
+const PROBE_SAMPLE_RATE: u32 = 16000;
+/// Model sent to `/audio/transcriptions` when none is configured; most
+/// self-hosted servers ignore it or map it to their loaded model.
+const DEFAULT_TRANSCRIPTIONS_MODEL: &str = "whisper-1";
+
+/// What one probe request says about an endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Support {
+    Yes,
+    No,
+    /// Reachable but inconclusive (auth, validation, rate limit).
+    Unknown,
+}
+
+fn support(status: StatusCode) -> Support {
+    match status.as_u16() {
+        200..=299 => Support::Yes,
+        404 | 405 | 501 => Support::No,
+        _ => Support::Unknown,
+    }
+}
+
+/// Find out whether an OpenAI-compatible server takes multipart uploads at
+/// `/audio/transcriptions` or audio parts at `/chat/completions`.
+///
+/// Multipart is tried first: it is what faster-whisper, whisper.cpp server,
+/// LocalAI and vLLM serve, and it costs nothing on hosted APIs.
+pub async fn detect_protocol(
+    client: &reqwest::Client,
+    base_url: &str,
+    api_key: &str,
+    model: Option<&str>,
+) -> Result<CustomSttProtocol, AppError> {
+    let base_url = base_url.trim().trim_end_matches('/');
+    let audio = wav::encode_wav(&[0i16; PROBE_SAMPLES], PROBE_SAMPLE_RATE);
+
+    let part = multipart::Part::bytes(audio.clone())
+        .file_name("probe.wav")
+        .mime_str("audio/wav")
+        .map_err(|e| AppError::Stt(format!("Failed to create multipart: {e}")))?;
+    let form = multipart::Form::new()
+        .part("file", part)
+        .text("model", model.unwrap_or(DEFAULT_TRANSCRIPTIONS_MODEL).to_string())
+        .text("response_format", "json");
+    let transcriptions = client
+        .post(format!("{base_url}/audio/transcriptions"))
+        .bearer_auth(api_key)
+        .multipart(form)
+        .send()
+        .await?
+        .status();
+    crate::app_log!("[stt] protocol probe {base_url}/audio/transcriptions status={transcriptions}");
+    if support(transcriptions) == Support::Yes {
+        return Ok(CustomSttProtocol::Transcriptions);
+    }
+
+    let body = json!({
+        "model": model.unwrap_or_default(),
+        "messages": [{
+            "role": "user",
+            "content": [
+                { "type": "text", "text": "Transcribe the audio." },
+                {
+                    "type": "input_audio",
+                    "input_audio": {
+                        "data": base64::engine::general_purpose::STANDARD.encode(&audio),
+                        "format": "wav"
+                    }
+                }
+            ]
+        }],
+        "max_tokens": 1
+    });
+    let chat = client
+        .post(format!("{base_url}/chat/completions"))
+        .bearer_auth(api_key)
+        .json(&body)
+        .send()
+        .await?
+        .status();
+    crate::app_log!("[stt] protocol probe {base_url}/chat/completions status={chat}");
+
+    decide(support(transcriptions), support(chat)).ok_or_else(|| {
+        AppError::Stt(format!(
+            "Could not detect the STT protocol of {base_url} (audio/transcriptions: HTTP {transcriptions}, chat/completions: HTTP {chat})"
+        ))
+    })
+}
+
+/// Prefer the endpoint that answered; otherwise the one whose route exists.
+fn decide(transcriptions: Support, chat: Support) -> Option<CustomSttProtocol> {
+    match (transcriptions, chat) {
+        (Support::Yes, _) => Some(CustomSttProtocol::Transcriptions),
+        (_, Support::Yes) => Some(CustomSttProtocol::ChatAudio),
+        (Support::Unknown, Support::No) => Some(CustomSttProtocol::Transcriptions),
+        (Support::No, Support::Unknown) => Some(CustomSttProtocol::ChatAudio),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decide_protocol() {
+        let s = |code: u16| support(StatusCode::from_u16(code).unwrap());
+        assert_eq!(decide(s(200), s(404)), Some(CustomSttProtocol::Transcriptions));
+        assert_eq!(decide(s(404), s(200)), Some(CustomSttProtocol::ChatAudio));
+        // A validation error still proves the route exists.
+        assert_eq!(decide(s(422), s(404)), Some(CustomSttProtocol::Transcriptions));
+        assert_eq!(decide(s(405), s(400)), Some(CustomSttProtocol::ChatAudio));
+        assert_eq!(decide(s(401), s(401)), None);
+        assert_eq!(decide(s(404), s(404)), None);
+    }
+}
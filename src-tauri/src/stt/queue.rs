@@ -0,0 +1,161 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+/// Attempts while the provider is reachable before an item is given up on.
+pub const MAX_ATTEMPTS: u32 = 5;
+
+/// A recording saved while the cloud provider was unreachable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTranscription {
+    /// File stem shared by the WAV and its outputs, e.g. `recording_20260101_120000`.
+    pub id: String,
+    pub audio_path: String,
+    pub text_path: String,
+    pub provider: String,
+    pub model: Option<String>,
+    pub base_url: Option<String>,
+    pub language: String,
+    /// RFC 3339 local time.
+    pub queued_at: String,
+    #[serde(default)]
+    pub attempts: u32,
+    /// Text written from the on-device pass while offline, if any.
+    #[serde(default)]
+    pub interim_text: Option<String>,
+}
+
+/// Recordings waiting for the network, persisted as JSON so they survive restarts.
+pub struct PendingQueue {
+    path: PathBuf,
+    items: Mutex<Vec<PendingTranscription>>,
+    draining: AtomicBool,
+}
+
+impl PendingQueue {
+    /// Queue backed by `path`; a missing or unreadable file starts empty.
+    pub fn load(path: PathBuf) -> Self {
+        let items = match fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|e| {
+                crate::app_log!("[queue] Ignoring unreadable {}: {e}", path.display());
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        Self {
+            path,
+            items: Mutex::new(items),
+            draining: AtomicBool::new(false),
+        }
+    }
+
+    pub fn items(&self) -> Vec<PendingTranscription> {
+        self.items.lock().unwrap().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.lock().unwrap().len()
+    }
+
+    /// Add `item`, replacing one with the same id.
+    pub fn push(&self, item: PendingTranscription) -> Result<(), AppError> {
+        let mut items = self.items.lock().unwrap();
+        items.retain(|i| i.id != item.id);
+        items.push(item);
+        save(&self.path, &items)
+    }
+
+    pub fn remove(&self, id: &str) -> Result<Option<PendingTranscription>, AppError> {
+        let mut items = self.items.lock().unwrap();
+        let Some(index) = items.iter().position(|i| i.id == id) else {
+            return Ok(None);
+        };
+        let item = items.remove(index);
+        save(&self.path, &items)?;
+        Ok(Some(item))
+    }
+
+    /// Count a failed attempt; returns the new total.
+    pub fn record_attempt(&self, id: &str) -> Result<u32, AppError> {
+        let mut items = self.items.lock().unwrap();
+        let Some(item) = items.iter_mut().find(|i| i.id == id) else {
+            return Ok(0);
+        };
+        item.attempts += 1;
+        let attempts = item.attempts;
+        save(&self.path, &items)?;
+        Ok(attempts)
+    }
+
+    /// Claim the queue for one drain; `false` if another is running.
+    pub fn begin_drain(&self) -> bool {
+        !self.draining.swap(true, Ordering::AcqRel)
+    }
+
+    pub fn end_drain(&self) {
+        self.draining.store(false, Ordering::Release);
+    }
+}
+
+/// Write through a temporary file so a crash never leaves half a queue.
+fn save(path: &Path, items: &[PendingTranscription]) -> Result<(), AppError> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_vec_pretty(items)?)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str) -> PendingTranscription {
+        PendingTranscription {
+            id: id.into(),
+            audio_path: format!("/tmp/{id}.wav"),
+            text_path: format!("/tmp/{id}.txt"),
+            provider: "openai_transcribe".into(),
+            model: None,
+            base_url: None,
+            language: "en".into(),
+            queued_at: "2026-01-01T12:00:00+00:00".into(),
+            attempts: 0,
+            interim_text: Some("draft".into()),
+        }
+    }
+
+    #[test]
+    fn test_queue_persists_across_loads() {
+        let dir = std::env::temp_dir().join(format!("voxlore-queue-{}", std::process::id()));
+        let path = dir.join("pending.json");
+        let _ = fs::remove_file(&path);
+
+        let queue = PendingQueue::load(path.clone());
+        queue.push(item("a")).unwrap();
+        queue.push(item("b")).unwrap();
+        assert_eq!(queue.record_attempt("a").unwrap(), 1);
+        assert_eq!(queue.remove("b").unwrap().map(|i| i.id), Some("b".into()));
+        assert_eq!(queue.remove("missing").unwrap(), None);
+
+        let reloaded = PendingQueue::load(path.clone());
+        let items = reloaded.items();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].attempts, 1);
+        assert_eq!(items[0].interim_text.as_deref(), Some("draft"));
+
+        assert!(reloaded.begin_drain());
+        assert!(!reloaded.begin_drain());
+        reloaded.end_drain();
+        assert!(reloaded.begin_drain());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
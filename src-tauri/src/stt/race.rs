@@ -0,0 +1,237 @@
+use std::future::Future;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::SttResult;
+use crate::error::AppError;
+
+/// A second provider transcribing the same audio alongside the selected one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RaceConfig {
+    /// Provider raced against the selected one; blank turns racing off.
+    pub provider: String,
+    /// Model for the racer; the provider's default when empty.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Overrides `cloud_timeout_secs` for the racer.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// How long, from the start of the race, the selected provider's answer
+    /// is preferred over an earlier one from the racer. `0` takes the first
+    /// good answer.
+    #[serde(default)]
+    pub prefer_primary_ms: u64,
+}
+
+impl RaceConfig {
+    /// Trim fields; `None` when no racer is configured.
+    pub fn normalized(self) -> Option<Self> {
+        let provider = self.provider.trim().to_string();
+        if provider.is_empty() {
+            return None;
+        }
+        Some(Self {
+            provider,
+            model: self
+                .model
+                .map(|m| m.trim().to_string())
+                .filter(|m| !m.is_empty()),
+            timeout_secs: self.timeout_secs,
+            prefer_primary_ms: self.prefer_primary_ms,
+        })
+    }
+
+    pub fn prefer_primary_for(&self) -> Option<Duration> {
+        (self.prefer_primary_ms > 0).then(|| Duration::from_millis(self.prefer_primary_ms))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Racer {
+    Primary,
+    Secondary,
+}
+
+/// Result of [`race`] and who produced it.
+#[derive(Debug)]
+pub struct RaceOutcome {
+    pub winner: Racer,
+    pub result: Result<SttResult, AppError>,
+}
+
+/// Run both transcriptions concurrently and return the first good answer:
+/// one with text. With `prefer_primary_for`, a good secondary answer is held
+/// until the primary answers or the deadline passes.
+///
+/// The loser is dropped, which aborts its HTTP request or raises the stop
+/// flag of a local pass: Vosk and sherpa-onnx stop at their next chunk and
+/// Whisper at whisper.cpp's next abort check.
+/// Errors should name their provider; both are reported when both fail.
+pub async fn race<P, S>(primary: P, secondary: S, prefer_primary_for: Option<Duration>) -> RaceOutcome
+where
+    P: Future<Output = Result<SttResult, AppError>>,
+    S: Future<Output = Result<SttResult, AppError>>,
+{
+    let deadline = prefer_primary_for.map(|d| tokio::time::Instant::now() + d);
+    let mut deadline_passed = deadline.is_none();
+    tokio::pin!(primary, secondary);
+    let mut primary_done: Option<Result<SttResult, AppError>> = None;
+    let mut secondary_done: Option<Result<SttResult, AppError>> = None;
+
+    loop {
+        if primary_done.as_ref().is_some_and(is_good) {
+            return finish(Racer::Primary, primary_done);
+        }
+        let secondary_good = secondary_done.as_ref().is_some_and(is_good);
+        if secondary_good && (primary_done.is_some() || deadline_passed) {
+            return finish(Racer::Secondary, secondary_done);
+        }
+        match (primary_done.take(), secondary_done.take()) {
+            (Some(p), Some(s)) => return settle(p, s),
+            (p, s) => (primary_done, secondary_done) = (p, s),
+        }
+
+        let waiting_for_primary = secondary_good && !deadline_passed;
+        tokio::select! {
+            biased;
+            result = &mut primary, if primary_done.is_none() => primary_done = Some(result),
+            result = &mut secondary, if secondary_done.is_none() => secondary_done = Some(result),
+            _ = tokio::time::sleep_until(deadline.unwrap_or_else(tokio::time::Instant::now)),
+                if waiting_for_primary => deadline_passed = true,
+        }
+    }
+}
+
+fn is_good(result: &Result<SttResult, AppError>) -> bool {
+    result.as_ref().is_ok_and(|r| !r.text.trim().is_empty())
+}
+
+/// Both answered without text: an empty transcript still beats an error.
+fn settle(primary: Result<SttResult, AppError>, secondary: Result<SttResult, AppError>) -> RaceOutcome {
+    match (primary, secondary) {
+        (Ok(result), _) => finish(Racer::Primary, Some(Ok(result))),
+        (_, Ok(result)) => finish(Racer::Secondary, Some(Ok(result))),
+        (Err(p), Err(s)) => RaceOutcome {
+            winner: Racer::Primary,
+            result: Err(AppError::Stt(format!("Both raced providers failed. {p}; {s}"))),
+        },
+    }
+}
+
+fn finish(winner: Racer, result: Option<Result<SttResult, AppError>>) -> RaceOutcome {
+    RaceOutcome {
+        winner,
+        result: result.expect("finished racer has a result"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn answer(text: &str, after_ms: u64) -> Result<SttResult, AppError> {
+        tokio::time::sleep(Duration::from_millis(after_ms)).await;
+        Ok(SttResult {
+            text: text.into(),
+            ..Default::default()
+        })
+    }
+
+    async fn fail(after_ms: u64) -> Result<SttResult, AppError> {
+        tokio::time::sleep(Duration::from_millis(after_ms)).await;
+        Err(AppError::Stt("offline".into()))
+    }
+
+    #[tokio::test]
+    async fn test_first_good_answer_wins() {
+        let outcome = race(answer("cloud", 200), answer("local", 10), None).await;
+        assert_eq!(outcome.winner, Racer::Secondary);
+        assert_eq!(outcome.result.unwrap().text, "local");
+
+        // An empty or failed answer does not end the race.
+        let outcome = race(answer("cloud", 60), answer(" ", 10), None).await;
+        assert_eq!(outcome.winner, Racer::Primary);
+        let outcome = race(fail(10), answer("local", 60), None).await;
+        assert_eq!(outcome.winner, Racer::Secondary);
+    }
+
+    #[tokio::test]
+    async fn test_waits_for_primary_until_deadline() {
+        let prefer = Some(Duration::from_millis(150));
+        let outcome = race(answer("cloud", 60), answer("local", 10), prefer).await;
+        assert_eq!(outcome.result.unwrap().text, "cloud");
+
+        let outcome = race(answer("cloud", 1_000), answer("local", 10), prefer).await;
+        assert_eq!(outcome.winner, Racer::Secondary);
+
+        // A failing primary releases the held answer before the deadline.
+        let started = tokio::time::Instant::now();
+        let outcome = race(fail(20), answer("local", 10), Some(Duration::from_secs(5))).await;
+        assert_eq!(outcome.winner, Racer::Secondary);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_blocking_engine_loses_to_fast_one_and_is_stopped() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        // Decodes in 10ms chunks for up to 2s, like a Vosk pass over a long recording.
+        let stopped = Arc::new(AtomicBool::new(false));
+        let observed = stopped.clone();
+        let slow = crate::stt::run_blocking("slow", move |stop| {
+            for _ in 0..200 {
+                if stop.load(Ordering::Acquire) {
+                    observed.store(true, Ordering::Release);
+                    return Err(AppError::Stt("stopped".into()));
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            Ok(SttResult {
+                text: "slow".into(),
+                ..Default::default()
+            })
+        });
+
+        let started = std::time::Instant::now();
+        let outcome = race(slow, answer("fast", 20), None).await;
+        assert_eq!(outcome.winner, Racer::Secondary);
+        assert_eq!(outcome.result.unwrap().text, "fast");
+        assert!(started.elapsed() < Duration::from_millis(500));
+
+        // The dropped loser ends its pass instead of decoding to the end.
+        for _ in 0..50 {
+            if stopped.load(Ordering::Acquire) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(stopped.load(Ordering::Acquire));
+    }
+
+    #[tokio::test]
+    async fn test_reports_both_failures() {
+        let outcome = race(fail(10), fail(20), None).await;
+        let message = outcome.result.unwrap_err().to_string();
+        assert!(message.contains("Both raced providers failed"), "{message}");
+        let outcome = race(answer("", 10), fail(20), None).await;
+        assert_eq!(outcome.result.unwrap().text, "");
+    }
+
+    #[test]
+    fn test_normalized_disables_blank_racer() {
+        let config = |provider: &str| RaceConfig {
+            provider: provider.into(),
+            model: Some(" ".into()),
+            timeout_secs: None,
+            prefer_primary_ms: 0,
+        };
+        assert_eq!(config("  ").normalized(), None);
+        let racer = config(" vosk ").normalized().unwrap();
+        assert_eq!(racer.provider, "vosk");
+        assert_eq!(racer.model, None);
+        assert_eq!(racer.prefer_primary_for(), None);
+    }
+}
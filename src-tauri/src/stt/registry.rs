@@ -0,0 +1,568 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::stt::elevenlabs::ElevenLabsEngine;
+use crate::stt::http::RetryPolicy;
+use crate::stt::mistral::MistralEngine;
+use crate::stt::openai_whisper::OpenAiWhisperEngine;
+use crate::stt::openrouter_audio::OpenRouterAudioEngine;
+use crate::stt::options::SttProviderOptions;
+use crate::stt::{CloudSttEngine, SttConfig, SttResult};
+
+/// Engine implementation that serves a provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EngineKind {
+    Vosk,
+    Whisper,
+    Sherpa,
+    ElevenLabs,
+    /// Multipart upload to `{base_url}/audio/transcriptions`.
+    OpenAiTranscriptions,
+    /// Base64 audio sent to `{base_url}/chat/completions`.
+    ChatAudio,
+    Mistral,
+}
+
+/// What the settings UI and pipeline may assume about a provider.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCapabilities {
+    /// Runs on-device; no API key or network needed.
+    pub local: bool,
+    /// Emits `recording:partial` while capturing.
+    pub live_partials: bool,
+    /// The user-configured STT endpoint replaces `base_url`.
+    pub custom_base_url: bool,
+    /// Unusable until an endpoint is configured.
+    pub requires_base_url: bool,
+    /// The endpoint may speak either OpenAI-compatible protocol; see `stt_protocol`.
+    pub selectable_protocol: bool,
+}
+
+/// Everything needed to build an engine for a provider id.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderDescriptor {
+    /// Value stored in `stt_provider` (e.g. "openai_transcribe").
+    pub id: String,
+    pub label: String,
+    pub engine: EngineKind,
+    /// Keystore entry holding the API key; `None` for local engines.
+    pub key_name: Option<String>,
+    pub default_model: Option<String>,
+    pub base_url: Option<String>,
+    pub capabilities: ProviderCapabilities,
+    /// Declared in settings rather than built in.
+    pub custom: bool,
+    /// Advanced request options from settings, attached by [`SttRegistry::resolve`].
+    #[serde(default)]
+    pub options: Option<SttProviderOptions>,
+}
+
+/// An extra OpenAI-compatible provider declared in settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomSttProvider {
+    pub id: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    pub base_url: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub protocol: CustomSttProtocol,
+    /// Keystore entry for the API key; defaults to `id`.
+    #[serde(default)]
+    pub key_name: Option<String>,
+}
+
+/// Wire protocol of an OpenAI-compatible endpoint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomSttProtocol {
+    /// `/audio/transcriptions` (Groq, local whisper servers, ...).
+    #[default]
+    Transcriptions,
+    /// `/chat/completions` with an audio content part.
+    ChatAudio,
+}
+
+impl CustomSttProtocol {
+    fn engine(self) -> EngineKind {
+        match self {
+            Self::Transcriptions => EngineKind::OpenAiTranscriptions,
+            Self::ChatAudio => EngineKind::ChatAudio,
+        }
+    }
+}
+
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+const OPENROUTER_BASE_URL: &str = "https://openrouter.ai/api/v1";
+const ELEVENLABS_ORIGIN: &str = "https://api.elevenlabs.io";
+const MISTRAL_ORIGIN: &str = "https://api.mistral.ai";
+
+fn builtin(
+    id: &str,
+    label: &str,
+    engine: EngineKind,
+    key_name: Option<&str>,
+    default_model: Option<&str>,
+    base_url: Option<&str>,
+    capabilities: ProviderCapabilities,
+) -> ProviderDescriptor {
+    ProviderDescriptor {
+        id: id.to_string(),
+        label: label.to_string(),
+        engine,
+        key_name: key_name.map(str::to_string),
+        default_model: default_model.map(str::to_string),
+        base_url: base_url.map(str::to_string),
+        capabilities,
+        custom: false,
+        options: None,
+    }
+}
+
+/// Providers that ship with the app.
+pub fn builtin_providers() -> Vec<ProviderDescriptor> {
+    let local = ProviderCapabilities {
+        local: true,
+        ..Default::default()
+    };
+    let overridable = ProviderCapabilities {
+        custom_base_url: true,
+        ..Default::default()
+    };
+    vec![
+        builtin(
+            "vosk",
+            "Vosk (Local)",
+            EngineKind::Vosk,
+            None,
+            None,
+            None,
+            ProviderCapabilities {
+                live_partials: true,
+                ..local.clone()
+            },
+        ),
+        builtin(
+            "whisper_local",
+            "Whisper (Local)",
+            EngineKind::Whisper,
+            None,
+            None,
+            None,
+            local.clone(),
+        ),
+        builtin(
+            "sherpa_local",
+            "Paraformer / SenseVoice (Local)",
+            EngineKind::Sherpa,
+            None,
+            None,
+            None,
+            local,
+        ),
+        builtin(
+            "elevenlabs",
+            "ElevenLabs",
+            EngineKind::ElevenLabs,
+            Some("elevenlabs"),
+            Some("scribe_v2"),
+            None,
+            ProviderCapabilities::default(),
+        ),
+        builtin(
+            "openai",
+            "OpenAI",
+            EngineKind::OpenAiTranscriptions,
+            Some("openai"),
+            Some("whisper-1"),
+            Some(OPENAI_BASE_URL),
+            overridable.clone(),
+        ),
+        builtin(
+            "openai_transcribe",
+            "OpenAI Transcribe",
+            EngineKind::OpenAiTranscriptions,
+            Some("openai"),
+            Some("gpt-4o-mini-transcribe"),
+            Some(OPENAI_BASE_URL),
+            overridable.clone(),
+        ),
+        builtin(
+            "openrouter",
+            "OpenRouter",
+            EngineKind::ChatAudio,
+            Some("openrouter"),
+            Some("google/gemini-3-flash-preview"),
+            Some(OPENROUTER_BASE_URL),
+            overridable,
+        ),
+        builtin(
+            "custom_openai_compatible",
+            "Custom OpenAI-Compatible",
+            EngineKind::ChatAudio,
+            Some("custom_openai_compatible"),
+            None,
+            None,
+            ProviderCapabilities {
+                custom_base_url: true,
+                requires_base_url: true,
+                selectable_protocol: true,
+                ..Default::default()
+            },
+        ),
+        builtin(
+            "mistral",
+            "Mistral",
+            EngineKind::Mistral,
+            Some("mistral"),
+            Some("mistral-vox-latest"),
+            None,
+            ProviderCapabilities::default(),
+        ),
+    ]
+}
+
+impl ProviderDescriptor {
+    /// Validate a declared provider and turn it into a descriptor.
+    pub fn from_custom(config: CustomSttProvider) -> Result<Self, AppError> {
+        let id = config.id.trim().to_string();
+        let valid_id = !id.is_empty()
+            && id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+        if !valid_id {
+            return Err(AppError::Stt(format!(
+                "Invalid STT provider id '{id}': use lowercase letters, digits, '_' or '-'"
+            )));
+        }
+        if builtin_providers().iter().any(|p| p.id == id) {
+            return Err(AppError::Stt(format!(
+                "STT provider id '{id}' is reserved for a built-in provider"
+            )));
+        }
+        let base_url = config.base_url.trim().trim_end_matches('/').to_string();
+        if !(base_url.starts_with("http://") || base_url.starts_with("https://")) {
+            return Err(AppError::Stt(format!(
+                "STT provider '{id}' needs an http(s) base URL"
+            )));
+        }
+
+        let engine = config.protocol.engine();
+        Ok(Self {
+            label: config
+                .label
+                .filter(|l| !l.trim().is_empty())
+                .unwrap_or_else(|| id.clone()),
+            engine,
+            key_name: Some(
+                config
+                    .key_name
+                    .filter(|k| !k.trim().is_empty())
+                    .unwrap_or_else(|| id.clone()),
+            ),
+            default_model: config.model.filter(|m| !m.trim().is_empty()),
+            base_url: Some(base_url),
+            capabilities: ProviderCapabilities::default(),
+            custom: true,
+            options: None,
+            id,
+        })
+    }
+
+    /// Switch a provider with a selectable protocol to `protocol`.
+    pub fn with_protocol(mut self, protocol: CustomSttProtocol) -> Self {
+        if self.capabilities.selectable_protocol {
+            self.engine = protocol.engine();
+        }
+        self
+    }
+
+    /// `scheme://host[:port]` the cloud engine uploads to, for connection
+    /// prewarming. `None` for local providers or an unusable endpoint.
+    pub fn endpoint_origin(&self, base_url: Option<&str>) -> Option<String> {
+        let url = match self.engine {
+            EngineKind::ElevenLabs => ELEVENLABS_ORIGIN,
+            EngineKind::Mistral => match &self.options {
+                Some(SttProviderOptions::Mistral(o)) => {
+                    o.base_url.as_deref().unwrap_or(MISTRAL_ORIGIN)
+                }
+                _ => MISTRAL_ORIGIN,
+            },
+            EngineKind::OpenAiTranscriptions | EngineKind::ChatAudio => base_url
+                .filter(|_| self.capabilities.custom_base_url)
+                .or(self.base_url.as_deref())?,
+            EngineKind::Vosk | EngineKind::Whisper | EngineKind::Sherpa => return None,
+        };
+        let url = reqwest::Url::parse(url).ok()?;
+        let origin = url.origin();
+        origin.is_tuple().then(|| origin.ascii_serialization())
+    }
+
+    /// Build the cloud engine for this provider.
+    ///
+    /// `model` and `base_url` come from the user's settings; they fall back to
+    /// the descriptor's defaults, and `base_url` is only honored when the
+    /// provider allows overriding its endpoint. Attached options must match
+    /// the engine, so e.g. diarization set for a chat-audio provider fails here.
+    pub fn build_cloud_engine(
+        &self,
+        api_key: String,
+        model: Option<String>,
+        base_url: Option<String>,
+    ) -> Result<CloudEngine, AppError> {
+        let model = model.or_else(|| self.default_model.clone());
+        let base_url = base_url
+            .filter(|_| self.capabilities.custom_base_url)
+            .or_else(|| self.base_url.clone());
+        if self.capabilities.requires_base_url && base_url.is_none() {
+            return Err(AppError::Stt(format!(
+                "{} requires an OpenAI-compatible endpoint.",
+                self.label
+            )));
+        }
+        if let Some(options) = &self.options {
+            options.validate(self)?;
+        }
+
+        let options = self.options.clone();
+        Ok(match self.engine {
+            EngineKind::ElevenLabs => {
+                let engine = ElevenLabsEngine::new(api_key, model);
+                CloudEngine::ElevenLabs(match options {
+                    Some(SttProviderOptions::ElevenLabs(o)) => engine.with_options(o),
+                    _ => engine,
+                })
+            }
+            EngineKind::OpenAiTranscriptions => {
+                let engine = OpenAiWhisperEngine::new(api_key, model, base_url);
+                CloudEngine::OpenAi(match options {
+                    Some(SttProviderOptions::OpenAiTranscriptions(o)) => engine.with_options(o),
+                    _ => engine,
+                })
+            }
+            EngineKind::ChatAudio => {
+                CloudEngine::ChatAudio(OpenRouterAudioEngine::new(api_key, model, base_url))
+            }
+            EngineKind::Mistral => {
+                let engine = MistralEngine::new(api_key, model);
+                CloudEngine::Mistral(match options {
+                    Some(SttProviderOptions::Mistral(o)) => engine.with_options(o),
+                    _ => engine,
+                })
+            }
+            EngineKind::Vosk | EngineKind::Whisper | EngineKind::Sherpa => {
+                return Err(AppError::Stt(format!(
+                    "{} is a local provider, not a cloud engine",
+                    self.label
+                )));
+            }
+        })
+    }
+}
+
+/// A constructed cloud engine.
+///
+/// `CloudSttEngine` uses `async fn`, so engines are dispatched through
+/// this enum rather than a trait object.
+pub enum CloudEngine {
+    ElevenLabs(ElevenLabsEngine),
+    OpenAi(OpenAiWhisperEngine),
+    ChatAudio(OpenRouterAudioEngine),
+    Mistral(MistralEngine),
+}
+
+impl CloudEngine {
+    pub fn with_retry_policy(self, retry: RetryPolicy) -> Self {
+        match self {
+            Self::ElevenLabs(engine) => Self::ElevenLabs(engine.with_retry_policy(retry)),
+            Self::OpenAi(engine) => Self::OpenAi(engine.with_retry_policy(retry)),
+            Self::ChatAudio(engine) => Self::ChatAudio(engine.with_retry_policy(retry)),
+            Self::Mistral(engine) => Self::Mistral(engine.with_retry_policy(retry)),
+        }
+    }
+
+    pub fn with_client(self, client: reqwest::Client) -> Self {
+        match self {
+            Self::ElevenLabs(engine) => Self::ElevenLabs(engine.with_client(client)),
+            Self::OpenAi(engine) => Self::OpenAi(engine.with_client(client)),
+            Self::ChatAudio(engine) => Self::ChatAudio(engine.with_client(client)),
+            Self::Mistral(engine) => Self::Mistral(engine.with_client(client)),
+        }
+    }
+}
+
+impl CloudSttEngine for CloudEngine {
+    async fn transcribe(
+        &self,
+        audio_data: &[u8],
+        config: &SttConfig,
+    ) -> Result<SttResult, AppError> {
+        match self {
+            Self::ElevenLabs(engine) => engine.transcribe(audio_data, config).await,
+            Self::OpenAi(engine) => engine.transcribe(audio_data, config).await,
+            Self::ChatAudio(engine) => engine.transcribe(audio_data, config).await,
+            Self::Mistral(engine) => engine.transcribe(audio_data, config).await,
+        }
+    }
+
+    fn max_chunk_secs(&self) -> u32 {
+        match self {
+            Self::ElevenLabs(engine) => engine.max_chunk_secs(),
+            Self::OpenAi(engine) => engine.max_chunk_secs(),
+            Self::ChatAudio(engine) => engine.max_chunk_secs(),
+            Self::Mistral(engine) => engine.max_chunk_secs(),
+        }
+    }
+
+    fn provider_name(&self) -> &str {
+        match self {
+            Self::ElevenLabs(engine) => engine.provider_name(),
+            Self::OpenAi(engine) => engine.provider_name(),
+            Self::ChatAudio(engine) => engine.provider_name(),
+            Self::Mistral(engine) => engine.provider_name(),
+        }
+    }
+}
+
+/// Built-in plus user-declared STT providers, kept in Tauri managed state.
+pub struct SttRegistry {
+    custom: Mutex<Vec<ProviderDescriptor>>,
+    /// Advanced options by provider id.
+    options: Mutex<HashMap<String, SttProviderOptions>>,
+}
+
+impl SttRegistry {
+    pub fn new() -> Self {
+        Self {
+            custom: Mutex::new(Vec::new()),
+            options: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Replace the declared providers.
+    pub fn set_custom_providers(&self, providers: Vec<ProviderDescriptor>) {
+        *self.custom.lock().unwrap() = providers;
+    }
+
+    /// Replace the advanced options, keyed by provider id.
+    pub fn set_provider_options(&self, options: HashMap<String, SttProviderOptions>) {
+        *self.options.lock().unwrap() = options;
+    }
+
+    /// All providers, built-ins first.
+    pub fn providers(&self) -> Vec<ProviderDescriptor> {
+        let mut providers = builtin_providers();
+        providers.extend(self.custom.lock().unwrap().iter().cloned());
+        providers
+    }
+
+    /// Look up a provider by the id stored in settings, with its options.
+    pub fn resolve(&self, id: &str) -> Result<ProviderDescriptor, AppError> {
+        let mut provider = self
+            .providers()
+            .into_iter()
+            .find(|p| p.id == id)
+            .ok_or_else(|| AppError::Stt(format!("Unsupported STT provider: {id}")))?;
+        provider.options = self.options.lock().unwrap().get(id).cloned();
+        Ok(provider)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom(id: &str, base_url: &str) -> CustomSttProvider {
+        CustomSttProvider {
+            id: id.into(),
+            label: None,
+            base_url: base_url.into(),
+            model: Some("whisper-large-v3".into()),
+            protocol: CustomSttProtocol::Transcriptions,
+            key_name: None,
+        }
+    }
+
+    #[test]
+    fn test_custom_provider_validation() {
+        let groq =
+            ProviderDescriptor::from_custom(custom("groq", "https://api.groq.com/openai/v1/"))
+                .unwrap();
+        assert_eq!(groq.engine, EngineKind::OpenAiTranscriptions);
+        assert_eq!(groq.key_name.as_deref(), Some("groq"));
+        assert_eq!(
+            groq.base_url.as_deref(),
+            Some("https://api.groq.com/openai/v1")
+        );
+
+        assert!(ProviderDescriptor::from_custom(custom("openai", "https://x.test")).is_err());
+        assert!(ProviderDescriptor::from_custom(custom("My Provider", "https://x.test")).is_err());
+        assert!(ProviderDescriptor::from_custom(custom("groq", "api.groq.com")).is_err());
+    }
+
+    #[test]
+    fn test_resolve_includes_custom_providers() {
+        let registry = SttRegistry::new();
+        assert!(registry.resolve("groq").is_err());
+        registry.set_custom_providers(vec![ProviderDescriptor::from_custom(custom(
+            "groq",
+            "https://api.groq.com/openai/v1",
+        ))
+        .unwrap()]);
+        assert!(registry.resolve("groq").unwrap().custom);
+        assert_eq!(registry.resolve("vosk").unwrap().engine, EngineKind::Vosk);
+    }
+
+    #[test]
+    fn test_base_url_override_requires_capability() {
+        let providers = builtin_providers();
+        let find = |id: &str| providers.iter().find(|p| p.id == id).unwrap().clone();
+
+        let custom = find("custom_openai_compatible");
+        assert!(custom.build_cloud_engine("k".into(), None, None).is_err());
+        assert!(custom
+            .build_cloud_engine("k".into(), None, Some("https://x.test/v1".into()))
+            .is_ok());
+        assert!(find("vosk")
+            .build_cloud_engine(String::new(), None, None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_with_protocol_only_switches_selectable_providers() {
+        let providers = builtin_providers();
+        let find = |id: &str| providers.iter().find(|p| p.id == id).unwrap().clone();
+
+        let custom = find("custom_openai_compatible").with_protocol(CustomSttProtocol::Transcriptions);
+        assert_eq!(custom.engine, EngineKind::OpenAiTranscriptions);
+        let openrouter = find("openrouter").with_protocol(CustomSttProtocol::Transcriptions);
+        assert_eq!(openrouter.engine, EngineKind::ChatAudio);
+    }
+
+    #[test]
+    fn test_endpoint_origin() {
+        let providers = builtin_providers();
+        let find = |id: &str| providers.iter().find(|p| p.id == id).unwrap().clone();
+
+        assert_eq!(
+            find("openrouter").endpoint_origin(None).as_deref(),
+            Some("https://openrouter.ai")
+        );
+        assert_eq!(
+            find("custom_openai_compatible")
+                .endpoint_origin(Some("http://10.0.0.5:8000/v1"))
+                .as_deref(),
+            Some("http://10.0.0.5:8000")
+        );
+        assert_eq!(find("custom_openai_compatible").endpoint_origin(None), None);
+        assert_eq!(find("vosk").endpoint_origin(None), None);
+    }
+}
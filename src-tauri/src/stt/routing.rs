@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+
+use super::language;
+
+/// Provider, model and enhancement settings for one spoken language.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageRoute {
+    /// App language code, e.g. `en`, `zh-TW`, `ja`. A bare code such as
+    /// `zh` also covers its regional variants.
+    pub language: String,
+    /// STT provider for this language; blank keeps the selected one.
+    #[serde(default)]
+    pub provider: String,
+    /// Model for the routed provider; its default when empty. Local engines
+    /// (Vosk, Whisper, sherpa-onnx) load this model before transcribing.
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Endpoint override for the routed provider.
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Enhancement used for this language instead of the global settings.
+    #[serde(default)]
+    pub enhancement: Option<EnhancementRoute>,
+}
+
+/// Enhancement override carried by a [`LanguageRoute`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnhancementRoute {
+    /// `false` skips enhancement for this language.
+    pub enabled: bool,
+    /// Blank keeps the global provider, model and endpoint.
+    #[serde(default)]
+    pub provider: String,
+    #[serde(default)]
+    pub model: String,
+    #[serde(default)]
+    pub base_url: Option<String>,
+}
+
+/// STT engine choice for one recording.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SttSelection {
+    pub provider: String,
+    pub model: Option<String>,
+    pub base_url: Option<String>,
+}
+
+impl LanguageRoute {
+    /// Trim fields; `None` when the route names no language.
+    pub fn normalized(self) -> Option<Self> {
+        let language = self.language.trim().to_string();
+        if language.is_empty() || language::is_auto(&language) {
+            return None;
+        }
+        Some(Self {
+            language,
+            provider: self.provider.trim().to_string(),
+            model: trimmed(self.model),
+            base_url: trimmed_url(self.base_url),
+            enhancement: self.enhancement.map(|e| EnhancementRoute {
+                enabled: e.enabled,
+                provider: e.provider.trim().to_string(),
+                model: e.model.trim().to_string(),
+                base_url: trimmed_url(e.base_url),
+            }),
+        })
+    }
+
+    /// Whether the route picks its own STT provider.
+    pub fn routes_stt(&self) -> bool {
+        !self.provider.is_empty()
+    }
+
+    /// `selection` with this route's provider applied. The model and
+    /// endpoint belong to the routed provider, so they are not inherited.
+    pub fn apply(&self, selection: SttSelection) -> SttSelection {
+        if !self.routes_stt() {
+            return selection;
+        }
+        SttSelection {
+            provider: self.provider.clone(),
+            model: self.model.clone(),
+            base_url: self.base_url.clone(),
+        }
+    }
+}
+
+/// Normalize routes, keeping the first one for each language.
+pub fn normalize_routes(routes: Vec<LanguageRoute>) -> Vec<LanguageRoute> {
+    let mut out: Vec<LanguageRoute> = Vec::new();
+    for route in routes.into_iter().filter_map(LanguageRoute::normalized) {
+        if !out.iter().any(|r| r.language.eq_ignore_ascii_case(&route.language)) {
+            out.push(route);
+        }
+    }
+    out
+}
+
+/// Route for `language`: an exact match, else one for its base language
+/// (`zh` for `zh-TW`). A bare detected code such as `zh` falls back to the
+/// first regional route (`zh-TW`). Auto mode has no route until the language
+/// is known.
+pub fn route_for<'a>(routes: &'a [LanguageRoute], language: &str) -> Option<&'a LanguageRoute> {
+    let language = language.trim();
+    if language::is_auto(language) {
+        return None;
+    }
+    let base = base_language(language);
+    routes
+        .iter()
+        .find(|r| r.language.eq_ignore_ascii_case(language))
+        .or_else(|| routes.iter().find(|r| r.language.eq_ignore_ascii_case(base)))
+        .or_else(|| {
+            routes
+                .iter()
+                .find(|r| language == base && base_language(&r.language).eq_ignore_ascii_case(base))
+        })
+}
+
+/// Model a local engine must load to honour `requested`, or `None` when no
+/// model was asked for or it is already the loaded one.
+pub fn model_to_load<'a>(requested: Option<&'a str>, loaded: Option<&str>) -> Option<&'a str> {
+    requested.filter(|model| loaded != Some(*model))
+}
+
+fn base_language(language: &str) -> &str {
+    language.split(['-', '_']).next().unwrap_or_default()
+}
+
+fn trimmed(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+fn trimmed_url(value: Option<String>) -> Option<String> {
+    trimmed(value).map(|v| v.trim_end_matches('/').to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(language: &str, provider: &str) -> LanguageRoute {
+        LanguageRoute {
+            language: language.into(),
+            provider: provider.into(),
+            model: None,
+            base_url: None,
+            enhancement: None,
+        }
+    }
+
+    #[test]
+    fn test_route_for_prefers_exact_then_base_language() {
+        let routes = normalize_routes(vec![
+            route(" en ", "vosk"),
+            route("zh", "openrouter"),
+            route("zh-TW", "openrouter"),
+            route("ZH-tw", "mistral"),
+            route("auto", "openai_transcribe"),
+            route("", "elevenlabs"),
+        ]);
+        assert_eq!(routes.len(), 3);
+        assert_eq!(route_for(&routes, "zh-TW").map(|r| r.language.as_str()), Some("zh-TW"));
+        assert_eq!(route_for(&routes, "zh-CN").map(|r| r.language.as_str()), Some("zh"));
+        assert_eq!(route_for(&routes, "en").map(|r| r.provider.as_str()), Some("vosk"));
+        assert_eq!(route_for(&routes, "ja"), None);
+        assert_eq!(route_for(&routes, "auto"), None);
+
+        // Auto mode detects a bare "zh"; a regional route still applies.
+        let regional = normalize_routes(vec![route("zh-TW", "openrouter")]);
+        assert_eq!(route_for(&regional, "zh").map(|r| r.language.as_str()), Some("zh-TW"));
+        assert_eq!(route_for(&regional, "zh-CN"), None);
+    }
+
+    #[test]
+    fn test_apply_replaces_whole_selection() {
+        let selected = SttSelection {
+            provider: "openai_transcribe".into(),
+            model: Some("gpt-4o-transcribe".into()),
+            base_url: Some("https://proxy.example".into()),
+        };
+        let mut ja = route("ja", "elevenlabs");
+        ja.model = Some("scribe_v1".into());
+        let routed = ja.apply(selected.clone());
+        assert_eq!(routed.provider, "elevenlabs");
+        assert_eq!(routed.model.as_deref(), Some("scribe_v1"));
+        assert_eq!(routed.base_url, None);
+
+        // Enhancement-only routes keep the selected engine.
+        assert_eq!(route("ja", "").apply(selected.clone()), selected);
+    }
+
+    #[test]
+    fn test_local_routes_select_their_model() {
+        let mut en = route("en", "vosk");
+        en.model = Some("vosk-model-small-en-us-0.15".into());
+        let routes = normalize_routes(vec![en]);
+        let model = routes[0].model.as_deref();
+        assert_eq!(model, Some("vosk-model-small-en-us-0.15"));
+
+        assert_eq!(model_to_load(model, None), model);
+        assert_eq!(model_to_load(model, Some("vosk-model-small-cn-0.22")), model);
+        assert_eq!(model_to_load(model, model), None);
+        assert_eq!(model_to_load(None, Some("vosk-model-small-cn-0.22")), None);
+    }
+}
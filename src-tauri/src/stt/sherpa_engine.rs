@@ -0,0 +1,466 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Mutex;
+
+#[cfg(feature = "sherpa-stt")]
+use std::sync::Arc;
+
+#[cfg(feature = "sherpa-stt")]
+use crate::stt::chunking;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::stt::{SttResult, SttWord};
+
+/// What a sherpa-onnx model does.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SherpaModelKind {
+    /// Non-autoregressive Mandarin recognizer; outputs no punctuation.
+    Paraformer,
+    /// Multilingual recognizer (zh/en/ja/ko/yue) with built-in punctuation.
+    SenseVoice,
+    /// CT-Transformer punctuation restorer used after Paraformer.
+    Punctuation,
+}
+
+/// A downloadable sherpa-onnx model archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SherpaModel {
+    /// Also the directory name inside the archive.
+    pub id: String,
+    pub name: String,
+    pub kind: SherpaModelKind,
+    pub language: String,
+    pub size_mb: u32,
+    pub url: String,
+    pub description: String,
+    /// ONNX file relative to the model directory.
+    pub model_file: String,
+    /// Token table; absent for punctuation models.
+    pub tokens_file: Option<String>,
+}
+
+/// Status of the sherpa-onnx engine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SherpaModelStatus {
+    pub loaded: bool,
+    pub model_id: Option<String>,
+    pub model_path: Option<String>,
+    pub kind: Option<SherpaModelKind>,
+    pub punctuation_model_id: Option<String>,
+    pub punctuation_enabled: bool,
+}
+
+const RELEASE_BASE_URL: &str = "https://github.com/k2-fsa/sherpa-onnx/releases/download";
+
+/// Upper bound for ONNX runtime threads.
+const MAX_THREADS: usize = 4;
+/// Longest piece of audio decoded in one call, so a stop is noticed within one piece.
+#[cfg_attr(not(feature = "sherpa-stt"), allow(dead_code))]
+const DECODE_CHUNK_SECS: u32 = 20;
+
+/// Models published by the sherpa-onnx project.
+pub fn available_models() -> Vec<SherpaModel> {
+    vec![
+        SherpaModel {
+            id: "sherpa-onnx-paraformer-zh-2024-03-09".into(),
+            name: "Paraformer Chinese".into(),
+            kind: SherpaModelKind::Paraformer,
+            language: "zh".into(),
+            size_mb: 950,
+            url: format!(
+                "{RELEASE_BASE_URL}/asr-models/sherpa-onnx-paraformer-zh-2024-03-09.tar.bz2"
+            ),
+            description: "Mandarin + English, very accurate, pair with punctuation".into(),
+            model_file: "model.int8.onnx".into(),
+            tokens_file: Some("tokens.txt".into()),
+        },
+        SherpaModel {
+            id: "sherpa-onnx-sense-voice-zh-en-ja-ko-yue-2024-07-17".into(),
+            name: "SenseVoice Small".into(),
+            kind: SherpaModelKind::SenseVoice,
+            language: "zh".into(),
+            size_mb: 1000,
+            url: format!(
+                "{RELEASE_BASE_URL}/asr-models/sherpa-onnx-sense-voice-zh-en-ja-ko-yue-2024-07-17.tar.bz2"
+            ),
+            description: "Chinese, English, Japanese, Korean, Cantonese with punctuation".into(),
+            model_file: "model.int8.onnx".into(),
+            tokens_file: Some("tokens.txt".into()),
+        },
+        SherpaModel {
+            id: "sherpa-onnx-punct-ct-transformer-zh-en-vocab272727-2024-04-12".into(),
+            name: "Chinese/English Punctuation".into(),
+            kind: SherpaModelKind::Punctuation,
+            language: "zh".into(),
+            size_mb: 260,
+            url: format!(
+                "{RELEASE_BASE_URL}/punctuation-models/sherpa-onnx-punct-ct-transformer-zh-en-vocab272727-2024-04-12.tar.bz2"
+            ),
+            description: "Adds punctuation to Paraformer output".into(),
+            model_file: "model.onnx".into(),
+            tokens_file: None,
+        },
+    ]
+}
+
+/// Loaded recognizer; both kinds decode with `&mut self`.
+#[cfg(feature = "sherpa-stt")]
+enum Recognizer {
+    Paraformer(sherpa_rs::paraformer::ParaformerRecognizer),
+    SenseVoice(sherpa_rs::sense_voice::SenseVoiceRecognizer),
+}
+
+/// Manages sherpa-onnx recognizer and punctuation models.
+///
+/// The recognizers are not reentrant, so each sits behind its own mutex
+/// and transcriptions are serialized on a blocking thread.
+pub struct SherpaManager {
+    #[cfg(feature = "sherpa-stt")]
+    recognizer: Mutex<Option<Arc<Mutex<Recognizer>>>>,
+    #[cfg(feature = "sherpa-stt")]
+    punctuation: Mutex<Option<Arc<Mutex<sherpa_rs::punctuate::Punctuation>>>>,
+    model_id: Mutex<Option<String>>,
+    model_path: Mutex<Option<PathBuf>>,
+    kind: Mutex<Option<SherpaModelKind>>,
+    punctuation_model_id: Mutex<Option<String>>,
+    punctuation_enabled: Mutex<bool>,
+}
+
+impl SherpaManager {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(feature = "sherpa-stt")]
+            recognizer: Mutex::new(None),
+            #[cfg(feature = "sherpa-stt")]
+            punctuation: Mutex::new(None),
+            model_id: Mutex::new(None),
+            model_path: Mutex::new(None),
+            kind: Mutex::new(None),
+            punctuation_model_id: Mutex::new(None),
+            punctuation_enabled: Mutex::new(true),
+        }
+    }
+
+    /// Load a recognizer model from its extracted directory.
+    pub fn load_model(&self, model: &SherpaModel, model_dir: &Path) -> Result<(), AppError> {
+        let tokens_file = match (model.kind, &model.tokens_file) {
+            (SherpaModelKind::Punctuation, _) | (_, None) => {
+                return Err(AppError::Stt(format!(
+                    "{} is not a speech recognition model",
+                    model.id
+                )));
+            }
+            (_, Some(tokens)) => tokens,
+        };
+        let model_file = existing_file(model_dir, &model.model_file)?;
+        let tokens_file = existing_file(model_dir, tokens_file)?;
+
+        #[cfg(feature = "sherpa-stt")]
+        {
+            let recognizer = match model.kind {
+                SherpaModelKind::Paraformer => {
+                    let config = sherpa_rs::paraformer::ParaformerConfig {
+                        model: model_file,
+                        tokens: tokens_file,
+                        num_threads: Some(thread_count()),
+                        ..Default::default()
+                    };
+                    Recognizer::Paraformer(
+                        sherpa_rs::paraformer::ParaformerRecognizer::new(config).map_err(|e| {
+                            AppError::Stt(format!("Failed to load Paraformer model: {e}"))
+                        })?,
+                    )
+                }
+                SherpaModelKind::SenseVoice => {
+                    // Language is fixed per recognizer; auto keeps one instance for every STT language.
+                    let config = sherpa_rs::sense_voice::SenseVoiceConfig {
+                        model: model_file,
+                        tokens: tokens_file,
+                        language: "auto".into(),
+                        use_itn: true,
+                        num_threads: Some(thread_count()),
+                        ..Default::default()
+                    };
+                    Recognizer::SenseVoice(
+                        sherpa_rs::sense_voice::SenseVoiceRecognizer::new(config).map_err(|e| {
+                            AppError::Stt(format!("Failed to load SenseVoice model: {e}"))
+                        })?,
+                    )
+                }
+                SherpaModelKind::Punctuation => unreachable!("rejected above"),
+            };
+            *self.recognizer.lock().unwrap() = Some(Arc::new(Mutex::new(recognizer)));
+        }
+        #[cfg(not(feature = "sherpa-stt"))]
+        {
+            let _ = (model_file, tokens_file);
+        }
+
+        *self.model_id.lock().unwrap() = Some(model.id.clone());
+        *self.model_path.lock().unwrap() = Some(model_dir.to_path_buf());
+        *self.kind.lock().unwrap() = Some(model.kind);
+
+        Ok(())
+    }
+
+    /// Load the punctuation model applied after recognizers without built-in punctuation.
+    pub fn load_punctuation(&self, model: &SherpaModel, model_dir: &Path) -> Result<(), AppError> {
+        if model.kind != SherpaModelKind::Punctuation {
+            return Err(AppError::Stt(format!("{} is not a punctuation model", model.id)));
+        }
+        let model_file = existing_file(model_dir, &model.model_file)?;
+
+        #[cfg(feature = "sherpa-stt")]
+        {
+            let config = sherpa_rs::punctuate::PunctuationConfig {
+                model: model_file,
+                num_threads: Some(thread_count()),
+                ..Default::default()
+            };
+            let punctuation = sherpa_rs::punctuate::Punctuation::new(config)
+                .map_err(|e| AppError::Stt(format!("Failed to load punctuation model: {e}")))?;
+            *self.punctuation.lock().unwrap() = Some(Arc::new(Mutex::new(punctuation)));
+        }
+        #[cfg(not(feature = "sherpa-stt"))]
+        {
+            let _ = model_file;
+        }
+
+        *self.punctuation_model_id.lock().unwrap() = Some(model.id.clone());
+        Ok(())
+    }
+
+    /// Toggle punctuation restoration (only affects Paraformer output).
+    pub fn set_punctuation_enabled(&self, enabled: bool) {
+        *self.punctuation_enabled.lock().unwrap() = enabled;
+    }
+
+    /// Check if a recognizer is currently loaded.
+    pub fn is_loaded(&self) -> bool {
+        #[cfg(feature = "sherpa-stt")]
+        {
+            self.recognizer.lock().unwrap().is_some()
+        }
+        #[cfg(not(feature = "sherpa-stt"))]
+        {
+            false
+        }
+    }
+
+    /// Get the current model status.
+    pub fn status(&self) -> SherpaModelStatus {
+        SherpaModelStatus {
+            loaded: self.is_loaded(),
+            model_id: self.model_id.lock().unwrap().clone(),
+            model_path: self
+                .model_path
+                .lock()
+                .unwrap()
+                .as_ref()
+                .map(|p| p.display().to_string()),
+            kind: *self.kind.lock().unwrap(),
+            punctuation_model_id: self.punctuation_model_id.lock().unwrap().clone(),
+            punctuation_enabled: *self.punctuation_enabled.lock().unwrap(),
+        }
+    }
+
+    /// Snapshot the loaded models for one transcription, run on a blocking thread.
+    pub fn prepare(&self) -> Result<SherpaJob, AppError> {
+        #[cfg(feature = "sherpa-stt")]
+        {
+            let recognizer = self
+                .recognizer
+                .lock()
+                .unwrap()
+                .clone()
+                .ok_or_else(|| AppError::Stt("No sherpa-onnx model loaded".into()))?;
+            let punctuation = if *self.punctuation_enabled.lock().unwrap() {
+                self.punctuation.lock().unwrap().clone()
+            } else {
+                None
+            };
+
+            Ok(SherpaJob {
+                recognizer,
+                punctuation,
+            })
+        }
+
+        #[cfg(not(feature = "sherpa-stt"))]
+        {
+            Err(AppError::Stt(
+                "sherpa-onnx feature not enabled. Rebuild with --features sherpa-stt".into(),
+            ))
+        }
+    }
+
+    /// Unload the recognizer and punctuation models to free memory.
+    pub fn unload_model(&self) {
+        #[cfg(feature = "sherpa-stt")]
+        {
+            *self.recognizer.lock().unwrap() = None;
+            *self.punctuation.lock().unwrap() = None;
+        }
+        *self.model_id.lock().unwrap() = None;
+        *self.model_path.lock().unwrap() = None;
+        *self.kind.lock().unwrap() = None;
+        *self.punctuation_model_id.lock().unwrap() = None;
+    }
+}
+
+/// One transcription on the loaded sherpa-onnx models.
+pub struct SherpaJob {
+    #[cfg(feature = "sherpa-stt")]
+    recognizer: Arc<Mutex<Recognizer>>,
+    #[cfg(feature = "sherpa-stt")]
+    punctuation: Option<Arc<Mutex<sherpa_rs::punctuate::Punctuation>>>,
+}
+
+impl SherpaJob {
+    /// Transcribe 16kHz mono i16 samples, giving up once `stop` is raised.
+    ///
+    /// sherpa-onnx offers no way to interrupt a decode, so long audio is
+    /// decoded in `DECODE_CHUNK_SECS` pieces and `stop` is checked between them.
+    pub fn run(
+        self,
+        samples: &[i16],
+        sample_rate: u32,
+        stop: &AtomicBool,
+    ) -> Result<SttResult, AppError> {
+        #[cfg(feature = "sherpa-stt")]
+        {
+            use std::sync::atomic::Ordering;
+
+            let chunks = chunking::plan_chunks(samples, sample_rate, DECODE_CHUNK_SECS);
+            let mut results = Vec::with_capacity(chunks.len());
+            let mut punctuate = false;
+            let mut recognizer = self.recognizer.lock().unwrap();
+            for chunk in &chunks {
+                if stop.load(Ordering::Acquire) {
+                    return Err(AppError::Stt("sherpa-onnx pass stopped".into()));
+                }
+                let audio: Vec<f32> =
+                    samples[chunk.range.clone()].iter().map(|&s| s as f32 / 32768.0).collect();
+
+                let (text, lang, tokens, timestamps) = match &mut *recognizer {
+                    Recognizer::Paraformer(recognizer) => {
+                        let result = recognizer.transcribe(sample_rate, &audio);
+                        punctuate = true;
+                        (result.text, result.lang, result.tokens, result.timestamps)
+                    }
+                    Recognizer::SenseVoice(recognizer) => {
+                        let result = recognizer.transcribe(sample_rate, &audio);
+                        (result.text, result.lang, result.tokens, result.timestamps)
+                    }
+                };
+                let lang = lang.trim_start_matches("<|").trim_end_matches("|>").to_string();
+                results.push(SttResult {
+                    text: text.trim().to_string(),
+                    language_detected: (!lang.is_empty()).then_some(lang),
+                    words: token_words(&tokens, &timestamps),
+                    ..Default::default()
+                });
+            }
+            drop(recognizer);
+
+            // Both models emit Simplified characters for Mandarin; zh-TW is
+            // converted with every other engine's output in `transcribe_samples`.
+            let mut result = chunking::merge(&chunks, results, sample_rate);
+            if punctuate && !result.text.is_empty() {
+                if let Some(punctuation) = &self.punctuation {
+                    result.text = punctuation.lock().unwrap().add_punctuation(&result.text);
+                    result.text = result.text.trim().to_string();
+                }
+            }
+            Ok(result)
+        }
+
+        #[cfg(not(feature = "sherpa-stt"))]
+        {
+            let _ = (samples, sample_rate, stop);
+            Err(AppError::Stt(
+                "sherpa-onnx feature not enabled. Rebuild with --features sherpa-stt".into(),
+            ))
+        }
+    }
+}
+
+/// Unpack a downloaded `.tar.bz2` model archive into `dest_dir`.
+pub fn extract_archive(archive: &Path, dest_dir: &Path) -> Result<(), AppError> {
+    #[cfg(feature = "sherpa-stt")]
+    {
+        let file = std::fs::File::open(archive)?;
+        let mut tar = tar::Archive::new(bzip2::read::BzDecoder::new(file));
+        tar.unpack(dest_dir)?;
+        Ok(())
+    }
+
+    #[cfg(not(feature = "sherpa-stt"))]
+    {
+        let _ = (archive, dest_dir);
+        Err(AppError::Stt(
+            "sherpa-onnx feature not enabled. Rebuild with --features sherpa-stt".into(),
+        ))
+    }
+}
+
+/// sherpa-onnx reports token start times only; each token ends where the next begins.
+#[cfg_attr(not(feature = "sherpa-stt"), allow(dead_code))]
+fn token_words(tokens: &[String], timestamps: &[f32]) -> Vec<SttWord> {
+    if tokens.len() != timestamps.len() {
+        return Vec::new();
+    }
+    tokens
+        .iter()
+        .zip(timestamps)
+        .enumerate()
+        .filter(|(_, (token, _))| !token.trim().is_empty())
+        .map(|(i, (token, &start))| SttWord {
+            word: token.trim().to_string(),
+            start,
+            end: timestamps.get(i + 1).copied().unwrap_or(start),
+            confidence: None,
+        })
+        .collect()
+}
+
+fn existing_file(dir: &Path, name: &str) -> Result<String, AppError> {
+    let path = dir.join(name);
+    if !path.is_file() {
+        return Err(AppError::Stt(format!(
+            "Model file does not exist: {}",
+            path.display()
+        )));
+    }
+    path.to_str()
+        .map(str::to_string)
+        .ok_or_else(|| AppError::Stt("Invalid model path encoding".into()))
+}
+
+#[cfg_attr(not(feature = "sherpa-stt"), allow(dead_code))]
+fn thread_count() -> i32 {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(2)
+        .min(MAX_THREADS) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_words_end_at_next_token() {
+        let tokens = vec!["你".to_string(), " ".to_string(), "好".to_string()];
+        let words = token_words(&tokens, &[0.1, 0.3, 0.4]);
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0].word, "你");
+        assert!((words[0].end - 0.3).abs() < 1e-6);
+        assert!((words[1].end - 0.4).abs() < 1e-6);
+        assert!(token_words(&tokens, &[0.1]).is_empty());
+    }
+}
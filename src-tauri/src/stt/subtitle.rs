@@ -0,0 +1,348 @@
+use serde::{Deserialize, Serialize};
+
+use super::chunking::is_cjk;
+use super::transcript::Transcript;
+use super::SttWord;
+
+/// A pause this long between words starts a new cue.
+const MAX_WORD_GAP_SECS: f32 = 1.0;
+/// Cues shorter than this are stretched so they stay readable.
+const MIN_CUE_SECS: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleFormat {
+    Srt,
+    Vtt,
+}
+
+impl SubtitleFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+        }
+    }
+}
+
+/// Layout limits for subtitle cues.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SubtitleOptions {
+    /// Line width in columns; CJK characters count as two.
+    pub max_line_chars: usize,
+    pub max_lines: usize,
+    /// Longest cue built from word timings.
+    pub max_cue_secs: f32,
+}
+
+impl Default for SubtitleOptions {
+    fn default() -> Self {
+        Self {
+            max_line_chars: 42,
+            max_lines: 2,
+            max_cue_secs: 6.0,
+        }
+    }
+}
+
+/// One subtitle: a time range and its wrapped lines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue {
+    pub start: f32,
+    pub end: f32,
+    pub lines: Vec<String>,
+}
+
+/// Render a timed transcript as SRT or WebVTT.
+pub fn render(transcript: &Transcript, format: SubtitleFormat, options: &SubtitleOptions) -> String {
+    let cues = build_cues(transcript, options);
+    let mut out = String::new();
+    if format == SubtitleFormat::Vtt {
+        out.push_str("WEBVTT\n\n");
+    }
+    for (i, cue) in cues.iter().enumerate() {
+        if format == SubtitleFormat::Srt {
+            out.push_str(&format!("{}\n", i + 1));
+        }
+        out.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            timestamp(cue.start, format),
+            timestamp(cue.end, format),
+            cue.lines.join("\n")
+        ));
+    }
+    out
+}
+
+/// Cues from Whisper segments when present (they carry punctuation),
+/// otherwise from word timings (Vosk, Sherpa).
+pub fn build_cues(transcript: &Transcript, options: &SubtitleOptions) -> Vec<Cue> {
+    let options = SubtitleOptions {
+        max_line_chars: options.max_line_chars.max(8),
+        max_lines: options.max_lines.max(1),
+        max_cue_secs: options.max_cue_secs.max(1.0),
+    };
+    let mut cues = Vec::new();
+    if !transcript.segments.is_empty() {
+        for segment in &transcript.segments {
+            split_timed_text(&segment.text, segment.start, segment.end, &options, &mut cues);
+        }
+    } else {
+        for group in group_words(&transcript.words, &options) {
+            let text = join_words(group);
+            let (start, end) = (group[0].start, group[group.len() - 1].end);
+            split_timed_text(&text, start, end, &options, &mut cues);
+        }
+    }
+
+    // Keep cues readable without overlapping the next one.
+    for i in 0..cues.len() {
+        let next_start = cues.get(i + 1).map(|c| c.start).unwrap_or(f32::MAX);
+        let cue = &mut cues[i];
+        cue.start = cue.start.max(0.0);
+        if cue.end - cue.start < MIN_CUE_SECS {
+            cue.end = (cue.start + MIN_CUE_SECS).min(next_start.max(cue.start));
+        }
+    }
+    cues
+}
+
+/// Wrap `text` and spread `start..end` over cues of at most `max_lines` lines,
+/// in proportion to their width.
+fn split_timed_text(text: &str, start: f32, end: f32, options: &SubtitleOptions, cues: &mut Vec<Cue>) {
+    let lines = wrap(text, options.max_line_chars);
+    if lines.is_empty() {
+        return;
+    }
+    let total: usize = lines.iter().map(|l| width(l)).sum::<usize>().max(1);
+    let span = (end - start).max(0.0);
+    let mut at = start;
+    let mut done = 0;
+    for group in lines.chunks(options.max_lines) {
+        done += group.iter().map(|l| width(l)).sum::<usize>();
+        let cue_end = start + span * done as f32 / total as f32;
+        cues.push(Cue {
+            start: at,
+            end: cue_end,
+            lines: group.to_vec(),
+        });
+        at = cue_end;
+    }
+}
+
+/// Words grouped into cues at pauses and at the width and duration limits.
+fn group_words<'a>(words: &'a [SttWord], options: &SubtitleOptions) -> Vec<&'a [SttWord]> {
+    let max_width = options.max_line_chars * options.max_lines;
+    let mut groups = Vec::new();
+    let mut first = 0;
+    let mut group_width = 0;
+    for (i, word) in words.iter().enumerate() {
+        if i > first {
+            let prev = &words[i - 1];
+            let split = word.start - prev.end > MAX_WORD_GAP_SECS
+                || word.end - words[first].start > options.max_cue_secs
+                || group_width + 1 + width(&word.word) > max_width;
+            if split {
+                groups.push(&words[first..i]);
+                first = i;
+                group_width = 0;
+            }
+        }
+        group_width += width(&word.word) + usize::from(i > first);
+    }
+    if first < words.len() {
+        groups.push(&words[first..]);
+    }
+    groups
+}
+
+/// Join recognizer words, without spaces between CJK words.
+fn join_words(words: &[SttWord]) -> String {
+    let mut text = String::new();
+    for word in words {
+        let word = word.word.trim();
+        let joins_cjk = text.chars().next_back().is_some_and(is_cjk)
+            && word.chars().next().is_some_and(is_cjk);
+        if !text.is_empty() && !joins_cjk {
+            text.push(' ');
+        }
+        text.push_str(word);
+    }
+    text
+}
+
+/// Greedy line wrapping. Latin text breaks at spaces; CJK text between any
+/// two characters, except that closing punctuation never starts a line and
+/// opening brackets never end one.
+pub fn wrap(text: &str, max_width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for unit in units(text) {
+        let spaced = unit.space_before && !line.is_empty();
+        let needed = width(&line) + usize::from(spaced) + width(&unit.text);
+        let glued = unit.text.chars().next().is_some_and(is_closing)
+            || line.chars().next_back().is_some_and(is_opening);
+        if needed > max_width && !line.is_empty() && !glued {
+            lines.push(std::mem::take(&mut line));
+        } else if spaced {
+            line.push(' ');
+        }
+        line.push_str(&unit.text);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+struct Unit {
+    text: String,
+    space_before: bool,
+}
+
+/// Latin words (with attached punctuation) and single CJK characters.
+fn units(text: &str) -> Vec<Unit> {
+    let mut out: Vec<Unit> = Vec::new();
+    let mut word = String::new();
+    let mut space_before = false;
+    for c in text.chars() {
+        if c.is_whitespace() || is_cjk(c) {
+            if !word.is_empty() {
+                out.push(Unit {
+                    text: std::mem::take(&mut word),
+                    space_before,
+                });
+                space_before = false;
+            }
+            if c.is_whitespace() {
+                space_before = true;
+            } else {
+                out.push(Unit {
+                    text: c.to_string(),
+                    space_before,
+                });
+                space_before = false;
+            }
+        } else {
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        out.push(Unit {
+            text: word,
+            space_before,
+        });
+    }
+    out
+}
+
+/// Display columns: full-width characters take two.
+fn width(text: &str) -> usize {
+    text.chars().map(|c| if is_cjk(c) || is_wide(c) { 2 } else { 1 }).sum()
+}
+
+fn is_wide(c: char) -> bool {
+    matches!(c, '\u{AC00}'..='\u{D7AF}' | '\u{FF01}'..='\u{FF60}')
+}
+
+fn is_closing(c: char) -> bool {
+    matches!(
+        c,
+        '。' | '、' | '，' | '．' | '！' | '？' | '：' | '；' | '）' | '」' | '』' | '】' | '〉' | '》'
+            | 'ー' | '…' | ',' | '.' | '!' | '?' | ':' | ';' | ')'
+    )
+}
+
+fn is_opening(c: char) -> bool {
+    matches!(c, '（' | '「' | '『' | '【' | '〈' | '《' | '(')
+}
+
+/// `HH:MM:SS,mmm` for SRT, `HH:MM:SS.mmm` for WebVTT.
+fn timestamp(secs: f32, format: SubtitleFormat) -> String {
+    let ms = (secs.max(0.0) * 1000.0).round() as u64;
+    let separator = match format {
+        SubtitleFormat::Srt => ',',
+        SubtitleFormat::Vtt => '.',
+    };
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stt::SttSegment;
+
+    fn transcript(segments: Vec<SttSegment>, words: Vec<SttWord>) -> Transcript {
+        Transcript {
+            version: 1,
+            text: String::new(),
+            language: None,
+            provider: None,
+            duration_secs: 0.0,
+            segments,
+            words,
+        }
+    }
+
+    #[test]
+    fn test_wrap_breaks_latin_at_spaces_and_cjk_anywhere() {
+        assert_eq!(
+            wrap("we should ship the release today", 16),
+            ["we should ship", "the release", "today"]
+        );
+        // Ten columns hold five Han characters; the comma may not start a line.
+        assert_eq!(wrap("今天天氣好，我們走", 10), ["今天天氣好，", "我們走"]);
+        assert_eq!(wrap("請幫我 merge 這個 PR", 12), ["請幫我 merge", "這個 PR"]);
+    }
+
+    #[test]
+    fn test_render_srt_and_vtt_from_segments() {
+        let t = transcript(
+            vec![
+                SttSegment { text: "Hello there.".into(), start: 0.0, end: 1.5, confidence: None, speaker: None },
+                SttSegment { text: "How are you?".into(), start: 61.25, end: 63.0, confidence: None, speaker: None },
+            ],
+            Vec::new(),
+        );
+        let options = SubtitleOptions::default();
+        assert_eq!(
+            render(&t, SubtitleFormat::Srt, &options),
+            "1\n00:00:00,000 --> 00:00:01,500\nHello there.\n\n2\n00:01:01,250 --> 00:01:03,000\nHow are you?\n\n"
+        );
+        assert!(render(&t, SubtitleFormat::Vtt, &options)
+            .starts_with("WEBVTT\n\n00:00:00.000 --> 00:00:01.500\nHello there.\n"));
+    }
+
+    #[test]
+    fn test_word_cues_split_at_pauses_and_join_cjk() {
+        let word = |w: &str, start: f32, end: f32| SttWord {
+            word: w.into(),
+            start,
+            end,
+            confidence: None,
+        };
+        let t = transcript(
+            Vec::new(),
+            vec![
+                word("我們", 0.0, 0.4),
+                word("明天", 0.4, 0.8),
+                word("deploy", 0.8, 1.2),
+                word("好", 3.0, 3.2),
+            ],
+        );
+        let cues = build_cues(&t, &SubtitleOptions::default());
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].lines, ["我們明天 deploy"]);
+        assert_eq!(cues[1].lines, ["好"]);
+        // Short cues are stretched to stay readable.
+        assert!((cues[1].end - 3.5).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,114 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::stt::{SttResult, SttSegment, SttWord};
+
+const TRANSCRIPT_VERSION: u32 = 1;
+
+/// Timed transcript saved next to a recording's WAV as `<name>.json`.
+///
+/// The `.txt` file keeps the plain text; this sidecar keeps what is needed
+/// to navigate the audio and to export subtitles later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transcript {
+    pub version: u32,
+    pub text: String,
+    pub language: Option<String>,
+    pub provider: Option<String>,
+    pub duration_secs: f32,
+    #[serde(default)]
+    pub segments: Vec<SttSegment>,
+    #[serde(default)]
+    pub words: Vec<SttWord>,
+}
+
+impl Transcript {
+    pub fn new(result: &SttResult, provider: Option<String>, duration_secs: f32) -> Self {
+        Self {
+            version: TRANSCRIPT_VERSION,
+            text: result.text.clone(),
+            language: result.language_detected.clone(),
+            provider,
+            duration_secs,
+            segments: result.segments.clone(),
+            words: result.words.clone(),
+        }
+    }
+
+    /// A recording saved without timings (by an engine that reports none, or
+    /// before sidecars existed): its whole text over its whole duration.
+    pub fn untimed(text: &str, duration_secs: f32) -> Self {
+        let text = text.trim().to_string();
+        Self {
+            version: TRANSCRIPT_VERSION,
+            segments: vec![SttSegment {
+                text: text.clone(),
+                start: 0.0,
+                end: duration_secs,
+                confidence: None,
+                speaker: None,
+            }],
+            text,
+            language: None,
+            provider: None,
+            duration_secs,
+            words: Vec::new(),
+        }
+    }
+
+    /// Sidecar location for a recording.
+    pub fn sidecar_path(audio_path: &Path) -> PathBuf {
+        audio_path.with_extension("json")
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AppError> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transcript_round_trip() {
+        let result = SttResult {
+            text: "hello world".into(),
+            segments: vec![SttSegment {
+                text: "hello world".into(),
+                start: 0.0,
+                end: 1.2,
+                confidence: Some(0.9),
+                speaker: None,
+            }],
+            ..Default::default()
+        };
+        let audio = std::env::temp_dir().join("voxlore_transcript_test.wav");
+        let path = Transcript::sidecar_path(&audio);
+        assert_eq!(path.extension().unwrap(), "json");
+
+        Transcript::new(&result, Some("openai".into()), 1.5).save(&path).unwrap();
+        let loaded = Transcript::load(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        assert_eq!(loaded.text, "hello world");
+        assert_eq!(loaded.segments.len(), 1);
+        assert_eq!(loaded.provider.as_deref(), Some("openai"));
+    }
+
+    #[test]
+    fn test_untimed_spans_the_recording() {
+        let transcript = Transcript::untimed(" 你好，世界 \n", 2.5);
+        assert_eq!(transcript.text, "你好，世界");
+        assert_eq!(transcript.segments.len(), 1);
+        assert_eq!((transcript.segments[0].start, transcript.segments[0].end), (0.0, 2.5));
+    }
+}
@@ -1,4 +1,6 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
 
 #[cfg(feature = "vosk-stt")]
@@ -7,7 +9,9 @@ use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 
 use crate::error::AppError;
-use crate::stt::SttResult;
+use crate::stt::chunking::{is_cjk, is_cjk_punctuation};
+use crate::stt::language;
+use crate::stt::{utterance_confidence, SttAlternative, SttResult, SttWord};
 
 /// Status of the Vosk model.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,8 +20,29 @@ pub struct VoskModelStatus {
     pub loaded: bool,
     pub model_id: Option<String>,
     pub model_path: Option<String>,
+    pub mode: VoskMode,
+    /// Names of the registered grammars.
+    pub grammars: Vec<String>,
+    pub max_alternatives: u16,
 }
 
+/// How the recognizer decodes speech.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum VoskMode {
+    /// Full vocabulary of the loaded model.
+    #[default]
+    Free,
+    /// Only the phrases of a registered grammar (e.g. "commands", "names").
+    Grammar { name: String },
+}
+
+/// Upper bound for n-best output; larger lists are slow and rarely useful.
+const MAX_ALTERNATIVES_LIMIT: u16 = 10;
+
+/// Out-of-grammar speech maps to this token instead of the closest phrase.
+const UNKNOWN_PHRASE: &str = "[unk]";
+
 /// Manages the Vosk model lifecycle and transcription.
 ///
 /// The model is expensive to load (can be hundreds of MB), so we load
@@ -27,6 +52,11 @@ pub struct VoskManager {
     model: Mutex<Option<Arc<vosk::Model>>>,
     model_id: Mutex<Option<String>>,
     model_path: Mutex<Option<PathBuf>>,
+    mode: Mutex<VoskMode>,
+    /// Phrase lists per use-case, used by grammar mode.
+    grammars: Mutex<HashMap<String, Vec<String>>>,
+    /// N-best count for new recognizers (0 = single best with per-word confidence).
+    max_alternatives: Mutex<u16>,
 }
 
 impl VoskManager {
@@ -36,6 +66,9 @@ impl VoskManager {
             model: Mutex::new(None),
             model_id: Mutex::new(None),
             model_path: Mutex::new(None),
+            mode: Mutex::new(VoskMode::Free),
+            grammars: Mutex::new(HashMap::new()),
+            max_alternatives: Mutex::new(0),
         }
     }
 
@@ -89,66 +122,78 @@ impl VoskManager {
                 .unwrap()
                 .as_ref()
                 .map(|p| p.display().to_string()),
+            mode: self.mode.lock().unwrap().clone(),
+            grammars: self.grammar_names(),
+            max_alternatives: *self.max_alternatives.lock().unwrap(),
         }
     }
 
-    /// Transcribe raw PCM i16 LE audio data at the given sample rate.
+    /// Set how many alternatives new recognizers return.
     ///
-    /// `audio_data` is raw bytes: pairs of little-endian i16 samples.
-    pub fn transcribe(&self, audio_data: &[u8], sample_rate: f32) -> Result<SttResult, AppError> {
-        #[cfg(feature = "vosk-stt")]
-        {
-            let model = self
-                .model
-                .lock()
-                .unwrap()
-                .clone()
-                .ok_or_else(|| AppError::Stt("No Vosk model loaded".into()))?;
+    /// Vosk omits per-word confidence in n-best output, so enabling this
+    /// trades word highlighting for one-click swaps between hypotheses.
+    pub fn set_max_alternatives(&self, count: u16) {
+        *self.max_alternatives.lock().unwrap() = count.min(MAX_ALTERNATIVES_LIMIT);
+    }
 
-            // Convert raw bytes to i16 samples (little-endian)
-            let samples: Vec<i16> = audio_data
-                .chunks_exact(2)
-                .map(|chunk| i16::from_le_bytes([chunk[0], chunk[1]]))
-                .collect();
+    /// Register (or replace) the phrase list for a use-case.
+    pub fn set_grammar(&self, name: &str, phrases: &[String]) -> Result<(), AppError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::Stt("Grammar name must not be empty".into()));
+        }
+        let phrases = normalize_phrases(phrases);
+        if phrases.is_empty() {
+            return Err(AppError::Stt(format!("Grammar '{name}' has no phrases")));
+        }
+        self.grammars
+            .lock()
+            .unwrap()
+            .insert(name.to_string(), phrases);
+        Ok(())
+    }
 
-            let mut recognizer = vosk::Recognizer::new(&model, sample_rate)
-                .ok_or_else(|| AppError::Stt("Failed to create Vosk recognizer".into()))?;
+    /// Remove a grammar; falls back to free mode if it was the active one.
+    pub fn remove_grammar(&self, name: &str) {
+        self.grammars.lock().unwrap().remove(name);
+        let mut mode = self.mode.lock().unwrap();
+        if matches!(&*mode, VoskMode::Grammar { name: active } if active == name) {
+            *mode = VoskMode::Free;
+        }
+    }
 
-            // Feed audio in chunks to allow internal processing
-            const CHUNK_SIZE: usize = 4000; // ~250ms at 16kHz
-            for chunk in samples.chunks(CHUNK_SIZE) {
-                recognizer
-                    .accept_waveform(chunk)
-                    .map_err(|e| AppError::Stt(format!("Vosk waveform error: {e}")))?;
-            }
+    /// Phrases registered for a grammar.
+    pub fn grammar(&self, name: &str) -> Option<Vec<String>> {
+        self.grammars.lock().unwrap().get(name).cloned()
+    }
 
-            let result = recognizer.final_result();
-            let text = match result.clone().single() {
-                Some(r) => r.text.to_string(),
-                None => match result.multiple() {
-                    Some(multi) => multi
-                        .alternatives
-                        .first()
-                        .map(|a| a.text.to_string())
-                        .unwrap_or_default(),
-                    None => String::new(),
-                },
-            };
+    fn grammar_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.grammars.lock().unwrap().keys().cloned().collect();
+        names.sort();
+        names
+    }
 
-            Ok(SttResult {
-                text,
-                confidence: None,
-                language_detected: None,
-            })
+    /// Switch between free dictation and a registered grammar.
+    pub fn set_mode(&self, mode: VoskMode) -> Result<(), AppError> {
+        if let VoskMode::Grammar { name } = &mode {
+            if !self.grammars.lock().unwrap().contains_key(name) {
+                return Err(AppError::Stt(format!("Unknown Vosk grammar: {name}")));
+            }
         }
+        *self.mode.lock().unwrap() = mode;
+        Ok(())
+    }
 
-        #[cfg(not(feature = "vosk-stt"))]
-        {
-            let _ = (audio_data, sample_rate);
-            Err(AppError::Stt(
-                "Vosk feature not enabled. Rebuild with --features vosk-stt".into(),
-            ))
-        }
+    /// Transcribe raw PCM i16 LE audio data at the given sample rate.
+    ///
+    /// `audio_data` is raw bytes: pairs of little-endian i16 samples.
+    pub fn transcribe(&self, audio_data: &[u8], sample_rate: f32) -> Result<SttResult, AppError> {
+        // Convert raw bytes to i16 samples (little-endian)
+        let samples: Vec<i16> = audio_data
+            .chunks_exact(2)
+            .map(|chunk| i16::from_le_bytes([chunk[0], chunk[1]]))
+            .collect();
+        self.transcribe_samples(&samples, sample_rate)
     }
 
     /// Transcribe i16 samples directly (for use with AudioCapture output).
@@ -157,6 +202,61 @@ impl VoskManager {
         samples: &[i16],
         sample_rate: f32,
     ) -> Result<SttResult, AppError> {
+        let stream = self.start_stream(sample_rate)?;
+        feed_all(stream, samples, &AtomicBool::new(false))
+    }
+
+    /// Transcribe i16 samples against an ad-hoc phrase list, regardless of the current mode.
+    pub fn transcribe_samples_with_grammar(
+        &self,
+        samples: &[i16],
+        sample_rate: f32,
+        phrases: &[String],
+    ) -> Result<SttResult, AppError> {
+        let stream = self.start_grammar_stream(sample_rate, phrases)?;
+        feed_all(stream, samples, &AtomicBool::new(false))
+    }
+
+    /// Create a live recognizer on the loaded model, honoring the current mode.
+    ///
+    /// The returned stream is fed from the recording loop so the final text
+    /// is ready as soon as capture stops, instead of running a second pass.
+    pub fn start_stream(&self, sample_rate: f32) -> Result<VoskStream, AppError> {
+        let mode = self.mode.lock().unwrap().clone();
+        match mode {
+            VoskMode::Free => self.open_stream(sample_rate, None),
+            VoskMode::Grammar { name } => {
+                let phrases = self
+                    .grammar(&name)
+                    .ok_or_else(|| AppError::Stt(format!("Unknown Vosk grammar: {name}")))?;
+                self.open_stream(sample_rate, Some(&phrases))
+            }
+        }
+    }
+
+    /// Create a recognizer without a grammar, whatever the current mode.
+    pub fn start_free_stream(&self, sample_rate: f32) -> Result<VoskStream, AppError> {
+        self.open_stream(sample_rate, None)
+    }
+
+    /// Create a recognizer restricted to `phrases`.
+    pub fn start_grammar_stream(
+        &self,
+        sample_rate: f32,
+        phrases: &[String],
+    ) -> Result<VoskStream, AppError> {
+        let phrases = normalize_phrases(phrases);
+        if phrases.is_empty() {
+            return Err(AppError::Stt("Grammar has no phrases".into()));
+        }
+        self.open_stream(sample_rate, Some(&phrases))
+    }
+
+    fn open_stream(
+        &self,
+        sample_rate: f32,
+        phrases: Option<&[String]>,
+    ) -> Result<VoskStream, AppError> {
         #[cfg(feature = "vosk-stt")]
         {
             let model = self
@@ -166,39 +266,35 @@ impl VoskManager {
                 .clone()
                 .ok_or_else(|| AppError::Stt("No Vosk model loaded".into()))?;
 
-            let mut recognizer = vosk::Recognizer::new(&model, sample_rate)
+            let recognizer = match phrases {
+                Some(phrases) => {
+                    let mut grammar: Vec<&str> = phrases.iter().map(String::as_str).collect();
+                    grammar.push(UNKNOWN_PHRASE);
+                    vosk::Recognizer::new_with_grammar(&model, sample_rate, &grammar)
+                }
+                None => vosk::Recognizer::new(&model, sample_rate),
+            };
+            let mut recognizer = recognizer
                 .ok_or_else(|| AppError::Stt("Failed to create Vosk recognizer".into()))?;
-
-            const CHUNK_SIZE: usize = 4000;
-            for chunk in samples.chunks(CHUNK_SIZE) {
-                recognizer
-                    .accept_waveform(chunk)
-                    .map_err(|e| AppError::Stt(format!("Vosk waveform error: {e}")))?;
+            recognizer.set_words(true);
+            let max_alternatives = *self.max_alternatives.lock().unwrap();
+            if max_alternatives > 0 {
+                recognizer.set_max_alternatives(max_alternatives);
             }
 
-            let result = recognizer.final_result();
-            let text = match result.clone().single() {
-                Some(r) => r.text.to_string(),
-                None => match result.multiple() {
-                    Some(multi) => multi
-                        .alternatives
-                        .first()
-                        .map(|a| a.text.to_string())
-                        .unwrap_or_default(),
-                    None => String::new(),
-                },
-            };
-
-            Ok(SttResult {
-                text,
-                confidence: None,
-                language_detected: None,
+            Ok(VoskStream {
+                recognizer,
+                _model: model,
+                segments: Vec::new(),
+                segment_alternatives: Vec::new(),
+                words: Vec::new(),
+                last_partial: String::new(),
             })
         }
 
         #[cfg(not(feature = "vosk-stt"))]
         {
-            let _ = (samples, sample_rate);
+            let _ = (sample_rate, phrases);
             Err(AppError::Stt(
                 "Vosk feature not enabled. Rebuild with --features vosk-stt".into(),
             ))
@@ -215,3 +311,327 @@ impl VoskManager {
         *self.model_path.lock().unwrap() = None;
     }
 }
+
+/// Incremental Vosk recognition session.
+///
+/// Utterances finalized by Vosk's endpointing are collected as they happen,
+/// so nothing is lost when a long recording contains pauses.
+pub struct VoskStream {
+    // Field order matters: the recognizer must drop before the model it borrows from.
+    #[cfg(feature = "vosk-stt")]
+    recognizer: vosk::Recognizer,
+    #[cfg(feature = "vosk-stt")]
+    _model: Arc<vosk::Model>,
+    segments: Vec<String>,
+    /// N-best list per finalized segment, parallel to `segments`.
+    segment_alternatives: Vec<Vec<SttAlternative>>,
+    words: Vec<SttWord>,
+    last_partial: String,
+}
+
+impl VoskStream {
+    /// Decode a whole recording, checking `stop` between chunks so a
+    /// dropped pass ends early. Meant for a blocking thread.
+    pub fn run(self, samples: &[i16], stop: &AtomicBool) -> Result<SttResult, AppError> {
+        feed_all(self, samples, stop)
+    }
+
+    /// Feed a chunk of samples.
+    ///
+    /// Returns the running transcript (finalized segments + current partial)
+    /// only when it changed since the previous call.
+    pub fn accept(&mut self, samples: &[i16]) -> Result<Option<String>, AppError> {
+        #[cfg(feature = "vosk-stt")]
+        {
+            let state = self
+                .recognizer
+                .accept_waveform(samples)
+                .map_err(|e| AppError::Stt(format!("Vosk waveform error: {e}")))?;
+
+            let current = match state {
+                vosk::DecodingState::Finalized => {
+                    let parsed = parse_result(self.recognizer.result());
+                    self.push_segment(parsed);
+                    String::new()
+                }
+                vosk::DecodingState::Running => {
+                    strip_unknown(self.recognizer.partial_result().partial)
+                }
+                vosk::DecodingState::Failed => {
+                    return Err(AppError::Stt("Vosk decoding failed".into()));
+                }
+            };
+
+            let running = join_segments(&self.segments, &current);
+            if running == self.last_partial {
+                return Ok(None);
+            }
+            self.last_partial = running.clone();
+            Ok(Some(running))
+        }
+
+        #[cfg(not(feature = "vosk-stt"))]
+        {
+            let _ = samples;
+            Ok(None)
+        }
+    }
+
+    /// Flush the recognizer and return the full transcript.
+    #[cfg_attr(not(feature = "vosk-stt"), allow(unused_mut))]
+    pub fn finish(mut self) -> Result<SttResult, AppError> {
+        #[cfg(feature = "vosk-stt")]
+        {
+            let parsed = parse_result(self.recognizer.final_result());
+            self.push_segment(parsed);
+        }
+
+        Ok(SttResult {
+            text: join_segments(&self.segments, ""),
+            confidence: utterance_confidence(&self.words),
+            language_detected: None,
+            words: self.words,
+            alternatives: combine_alternatives(&self.segment_alternatives),
+            segments: Vec::new(),
+        })
+    }
+
+    /// Append a finalized utterance; word times are already relative to stream start.
+    #[cfg_attr(not(feature = "vosk-stt"), allow(dead_code))]
+    fn push_segment(&mut self, parsed: ParsedSegment) {
+        let text = strip_unknown(&parsed.text);
+        if !text.is_empty() {
+            let mut words = parsed.words;
+            words.retain(|w| w.word != UNKNOWN_PHRASE);
+            self.segments.push(text);
+            self.segment_alternatives.push(parsed.alternatives);
+            self.words.extend(words);
+        }
+    }
+}
+
+/// Owned copy of one finalized Vosk utterance.
+#[cfg_attr(not(feature = "vosk-stt"), allow(dead_code))]
+struct ParsedSegment {
+    text: String,
+    words: Vec<SttWord>,
+    alternatives: Vec<SttAlternative>,
+}
+
+/// Copy text, word timings and alternatives out of a result borrowed from the recognizer.
+#[cfg(feature = "vosk-stt")]
+fn parse_result(result: vosk::CompleteResult<'_>) -> ParsedSegment {
+    match result {
+        vosk::CompleteResult::Single(r) => ParsedSegment {
+            text: r.text.to_string(),
+            words: r
+                .result
+                .iter()
+                .map(|w| SttWord {
+                    word: w.word.to_string(),
+                    start: w.start,
+                    end: w.end,
+                    confidence: Some(w.conf),
+                })
+                .collect(),
+            alternatives: Vec::new(),
+        },
+        vosk::CompleteResult::Multiple(multi) => {
+            let alternatives = multi
+                .alternatives
+                .iter()
+                .map(|a| SttAlternative {
+                    text: strip_unknown(a.text),
+                    confidence: a.confidence,
+                })
+                .collect();
+            match multi.alternatives.first() {
+                Some(best) => ParsedSegment {
+                    text: best.text.to_string(),
+                    words: best
+                        .result
+                        .iter()
+                        .map(|w| SttWord {
+                            word: w.word.to_string(),
+                            start: w.start,
+                            end: w.end,
+                            confidence: None,
+                        })
+                        .collect(),
+                    alternatives,
+                },
+                None => ParsedSegment {
+                    text: String::new(),
+                    words: Vec::new(),
+                    alternatives: Vec::new(),
+                },
+            }
+        }
+    }
+}
+
+fn feed_all(mut stream: VoskStream, samples: &[i16], stop: &AtomicBool) -> Result<SttResult, AppError> {
+    const CHUNK_SIZE: usize = 4000; // ~250ms at 16kHz
+    for chunk in samples.chunks(CHUNK_SIZE) {
+        if stop.load(Ordering::Acquire) {
+            return Err(AppError::Stt("Vosk pass stopped".into()));
+        }
+        stream.accept(chunk)?;
+    }
+    stream.finish()
+}
+
+/// Vosk vocabularies are lowercase, and phrases are embedded into a JSON
+/// array without escaping, so quotes and backslashes are stripped.
+fn normalize_phrases(phrases: &[String]) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for phrase in phrases {
+        let cleaned: String = phrase
+            .chars()
+            .filter(|c| *c != '"' && *c != '\\')
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .to_lowercase();
+        if !cleaned.is_empty() && cleaned != UNKNOWN_PHRASE && !out.contains(&cleaned) {
+            out.push(cleaned);
+        }
+    }
+    out
+}
+
+/// Drop `[unk]` tokens that grammar mode emits for out-of-grammar speech.
+fn strip_unknown(text: &str) -> String {
+    text.split_whitespace()
+        .filter(|token| *token != UNKNOWN_PHRASE)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Build whole-transcript alternatives from per-segment n-best lists.
+///
+/// Rank `n` takes each segment's n-th hypothesis (or its best one when the
+/// segment has fewer), so a single-utterance dictation maps 1:1 to Vosk's list.
+fn combine_alternatives(per_segment: &[Vec<SttAlternative>]) -> Vec<SttAlternative> {
+    let depth = per_segment.iter().map(Vec::len).max().unwrap_or(0);
+    if depth < 2 {
+        return Vec::new();
+    }
+
+    let mut out: Vec<SttAlternative> = Vec::new();
+    for rank in 0..depth {
+        let mut parts: Vec<&str> = Vec::new();
+        let mut score = 0.0f32;
+        for alternatives in per_segment.iter().filter(|a| !a.is_empty()) {
+            let chosen = alternatives.get(rank).unwrap_or(&alternatives[0]);
+            if !chosen.text.is_empty() {
+                parts.push(&chosen.text);
+            }
+            score += chosen.confidence;
+        }
+        let text = parts.join(" ");
+        if !out.iter().any(|a| a.text == text) {
+            out.push(SttAlternative {
+                text,
+                confidence: score,
+            });
+        }
+    }
+    out
+}
+
+/// Remove the spaces Vosk's Chinese and Japanese models put between tokens
+/// ("你 好 世 界" → "你好世界"), keeping them around Latin words and numbers.
+///
+/// Other languages are returned unchanged; auto mode is treated as CJK
+/// since only spaces between two CJK characters are dropped.
+pub fn join_cjk_tokens(text: &str, language: &str) -> String {
+    let base = language.trim().to_lowercase();
+    let base = base.split(['-', '_']).next().unwrap_or_default();
+    if !(language::is_auto(language) || matches!(base, "zh" | "ja" | "yue")) {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    for token in text.split_whitespace() {
+        if let (Some(prev), Some(next)) = (out.chars().next_back(), token.chars().next()) {
+            let glued = (is_cjk(prev) && is_cjk(next))
+                || is_cjk_punctuation(prev)
+                || is_cjk_punctuation(next);
+            if !glued {
+                out.push(' ');
+            }
+        }
+        out.push_str(token);
+    }
+    out
+}
+
+fn join_segments(segments: &[String], current: &str) -> String {
+    let mut parts: Vec<&str> = segments.iter().map(String::as_str).collect();
+    let current = current.trim();
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_phrases() {
+        let phrases = vec![
+            "  Open  Settings ".to_string(),
+            "open settings".to_string(),
+            "say \"hi\"".to_string(),
+            "[unk]".to_string(),
+            "".to_string(),
+        ];
+        assert_eq!(normalize_phrases(&phrases), vec!["open settings", "say hi"]);
+    }
+
+    #[test]
+    fn test_combine_alternatives() {
+        let alt = |text: &str, confidence: f32| SttAlternative {
+            text: text.into(),
+            confidence,
+        };
+        let per_segment = vec![
+            vec![alt("call alice", 10.0), alt("call alex", 8.0)],
+            vec![alt("tomorrow", 5.0)],
+        ];
+        let combined = combine_alternatives(&per_segment);
+        let texts: Vec<&str> = combined.iter().map(|a| a.text.as_str()).collect();
+        assert_eq!(texts, vec!["call alice tomorrow", "call alex tomorrow"]);
+        assert_eq!(combined[1].confidence, 13.0);
+
+        assert!(combine_alternatives(&[vec![alt("only", 1.0)]]).is_empty());
+    }
+
+    #[test]
+    fn test_join_cjk_tokens_mixed_zh_en() {
+        assert_eq!(join_cjk_tokens("你 好 世 界", "zh"), "你好世界");
+        assert_eq!(
+            join_cjk_tokens("請 幫 我 把 pull request merge 進 main 分 支 ， 然 後 跑 test", "zh-TW"),
+            "請幫我把 pull request merge 進 main 分支，然後跑 test"
+        );
+        assert_eq!(
+            join_cjk_tokens("部 署 到 k8s 叢 集 的 第 3 個 node", "zh"),
+            "部署到 k8s 叢集的第 3 個 node"
+        );
+        assert_eq!(join_cjk_tokens("今日 は API を 呼ぶ", "ja"), "今日は API を呼ぶ");
+        assert_eq!(join_cjk_tokens("我 們  deploy 吧", "auto"), "我們 deploy 吧");
+        // Languages written with spaces are left alone.
+        assert_eq!(join_cjk_tokens("ship the 2 fixes", "en"), "ship the 2 fixes");
+        assert_eq!(join_cjk_tokens("안녕 하세요", "ko"), "안녕 하세요");
+    }
+
+    #[test]
+    fn test_strip_unknown() {
+        assert_eq!(strip_unknown("[unk] call alice [unk]"), "call alice");
+        assert_eq!(strip_unknown("[unk]"), "");
+    }
+}
@@ -0,0 +1,354 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Mutex;
+
+#[cfg(feature = "whisper-stt")]
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::stt::{glossary, SttResult};
+
+/// A downloadable ggml Whisper model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhisperModel {
+    pub id: String,
+    pub name: String,
+    /// File name on disk (and in the upstream repository).
+    pub file_name: String,
+    pub size_mb: u32,
+    pub url: String,
+    pub description: String,
+}
+
+/// Status of the local Whisper model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhisperModelStatus {
+    pub loaded: bool,
+    pub model_id: Option<String>,
+    pub model_path: Option<String>,
+}
+
+const MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+
+/// Upper bound for decoder threads; more rarely helps on laptop CPUs.
+const MAX_THREADS: usize = 8;
+
+/// Multilingual ggml models published by the whisper.cpp project.
+pub fn available_models() -> Vec<WhisperModel> {
+    [
+        ("whisper-tiny", "Whisper Tiny", "ggml-tiny.bin", 75, "Fastest, lowest accuracy"),
+        ("whisper-base", "Whisper Base", "ggml-base.bin", 142, "Good balance for short dictation"),
+        ("whisper-small", "Whisper Small", "ggml-small.bin", 466, "Noticeably better Chinese accuracy"),
+        (
+            "whisper-large-v3-turbo-q5",
+            "Whisper Large v3 Turbo (Q5)",
+            "ggml-large-v3-turbo-q5_0.bin",
+            547,
+            "Best accuracy, needs a fast CPU",
+        ),
+    ]
+    .into_iter()
+    .map(|(id, name, file_name, size_mb, description)| WhisperModel {
+        id: id.to_string(),
+        name: name.to_string(),
+        file_name: file_name.to_string(),
+        size_mb,
+        url: format!("{MODEL_BASE_URL}/{file_name}"),
+        description: description.to_string(),
+    })
+    .collect()
+}
+
+/// Manages the whisper.cpp model lifecycle.
+///
+/// Like Vosk, the context is loaded once and kept in Tauri managed state;
+/// each transcription creates its own decoding state on top of it.
+pub struct WhisperManager {
+    #[cfg(feature = "whisper-stt")]
+    context: Mutex<Option<Arc<whisper_rs::WhisperContext>>>,
+    model_id: Mutex<Option<String>>,
+    model_path: Mutex<Option<PathBuf>>,
+}
+
+impl WhisperManager {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(feature = "whisper-stt")]
+            context: Mutex::new(None),
+            model_id: Mutex::new(None),
+            model_path: Mutex::new(None),
+        }
+    }
+
+    /// Load a ggml model file from disk.
+    pub fn load_model(&self, model_id: &str, model_file: &Path) -> Result<(), AppError> {
+        if !model_file.is_file() {
+            return Err(AppError::Stt(format!(
+                "Model file does not exist: {}",
+                model_file.display()
+            )));
+        }
+
+        #[cfg(feature = "whisper-stt")]
+        {
+            let path_str = model_file
+                .to_str()
+                .ok_or_else(|| AppError::Stt("Invalid model path encoding".into()))?;
+
+            let context = whisper_rs::WhisperContext::new_with_params(
+                path_str,
+                whisper_rs::WhisperContextParameters::default(),
+            )
+            .map_err(|e| AppError::Stt(format!("Failed to load Whisper model: {e}")))?;
+
+            *self.context.lock().unwrap() = Some(Arc::new(context));
+        }
+
+        *self.model_id.lock().unwrap() = Some(model_id.to_string());
+        *self.model_path.lock().unwrap() = Some(model_file.to_path_buf());
+
+        Ok(())
+    }
+
+    /// Check if a model is currently loaded.
+    pub fn is_loaded(&self) -> bool {
+        #[cfg(feature = "whisper-stt")]
+        {
+            self.context.lock().unwrap().is_some()
+        }
+        #[cfg(not(feature = "whisper-stt"))]
+        {
+            false
+        }
+    }
+
+    /// Get the current model status.
+    pub fn status(&self) -> WhisperModelStatus {
+        WhisperModelStatus {
+            loaded: self.is_loaded(),
+            model_id: self.model_id.lock().unwrap().clone(),
+            model_path: self
+                .model_path
+                .lock()
+                .unwrap()
+                .as_ref()
+                .map(|p| p.display().to_string()),
+        }
+    }
+
+    /// Snapshot the loaded model for one transcription.
+    ///
+    /// Decoding takes seconds of CPU time, so callers run the returned job
+    /// on a blocking thread instead of holding the manager's lock.
+    pub fn prepare(&self, language: &str, glossary: &[String]) -> Result<WhisperJob, AppError> {
+        #[cfg(feature = "whisper-stt")]
+        {
+            let context = self
+                .context
+                .lock()
+                .unwrap()
+                .clone()
+                .ok_or_else(|| AppError::Stt("No Whisper model loaded".into()))?;
+
+            Ok(WhisperJob {
+                context,
+                language: language.to_string(),
+                prompt: build_prompt(language, glossary),
+            })
+        }
+
+        #[cfg(not(feature = "whisper-stt"))]
+        {
+            let _ = (language, glossary);
+            Err(AppError::Stt(
+                "Whisper feature not enabled. Rebuild with --features whisper-stt".into(),
+            ))
+        }
+    }
+
+    /// Unload the current model to free memory.
+    pub fn unload_model(&self) {
+        #[cfg(feature = "whisper-stt")]
+        {
+            *self.context.lock().unwrap() = None;
+        }
+        *self.model_id.lock().unwrap() = None;
+        *self.model_path.lock().unwrap() = None;
+    }
+}
+
+/// One transcription on the loaded Whisper model.
+pub struct WhisperJob {
+    #[cfg(feature = "whisper-stt")]
+    context: Arc<whisper_rs::WhisperContext>,
+    #[cfg_attr(not(feature = "whisper-stt"), allow(dead_code))]
+    language: String,
+    #[cfg_attr(not(feature = "whisper-stt"), allow(dead_code))]
+    prompt: Option<String>,
+}
+
+impl WhisperJob {
+    /// Transcribe 16kHz mono i16 samples, giving up once `stop` is raised.
+    pub fn run(self, samples: &[i16], stop: &AtomicBool) -> Result<SttResult, AppError> {
+        #[cfg(feature = "whisper-stt")]
+        {
+            use std::ffi::c_void;
+            use std::sync::atomic::Ordering;
+            use whisper_rs::{FullParams, SamplingStrategy};
+
+            // whisper.cpp polls this between encoder and decoder steps.
+            unsafe extern "C" fn stop_requested(stop: *mut c_void) -> bool {
+                (*(stop as *const AtomicBool)).load(Ordering::Acquire)
+            }
+
+            let stt_err = |e: whisper_rs::WhisperError| AppError::Stt(format!("Whisper error: {e}"));
+
+            let mut audio = vec![0.0f32; samples.len()];
+            whisper_rs::convert_integer_to_float_audio(samples, &mut audio).map_err(stt_err)?;
+
+            let language = language_hint(&self.language);
+
+            let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+            params.set_n_threads(thread_count());
+            params.set_translate(false);
+            params.set_no_context(true);
+            params.set_suppress_blank(true);
+            params.set_print_special(false);
+            params.set_print_progress(false);
+            params.set_print_realtime(false);
+            params.set_print_timestamps(false);
+            // None lets whisper.cpp detect the spoken language.
+            params.set_language(language.as_deref());
+            if let Some(prompt) = &self.prompt {
+                params.set_initial_prompt(prompt);
+            }
+
+            // SAFETY: `stop` outlives `state.full`, the only call that reads it.
+            unsafe {
+                params.set_abort_callback(Some(stop_requested));
+                params.set_abort_callback_user_data(stop as *const AtomicBool as *mut c_void);
+            }
+
+            let mut state = self.context.create_state().map_err(stt_err)?;
+            state.full(params, &audio).map_err(stt_err)?;
+            if stop.load(Ordering::Acquire) {
+                return Err(AppError::Stt("Whisper pass stopped".into()));
+            }
+
+            let eot = self.context.token_eot();
+            let mut text = String::new();
+            let mut prob_sum = 0.0f32;
+            let mut prob_count = 0u32;
+            let mut segments = Vec::new();
+            for segment in 0..state.full_n_segments().map_err(stt_err)? {
+                let segment_text = state.full_get_segment_text_lossy(segment).map_err(stt_err)?;
+                text.push_str(&segment_text);
+                let (mut segment_sum, mut segment_count) = (0.0f32, 0u32);
+                for token in 0..state.full_n_tokens(segment).map_err(stt_err)? {
+                    // Special tokens (timestamps, language tags) sit above EOT.
+                    if state.full_get_token_id(segment, token).map_err(stt_err)? < eot {
+                        segment_sum += state.full_get_token_prob(segment, token).map_err(stt_err)?;
+                        segment_count += 1;
+                    }
+                }
+                prob_sum += segment_sum;
+                prob_count += segment_count;
+                // whisper.cpp reports segment bounds in centiseconds.
+                segments.push(crate::stt::SttSegment {
+                    text: segment_text.trim().to_string(),
+                    start: state.full_get_segment_t0(segment).map_err(stt_err)? as f32 / 100.0,
+                    end: state.full_get_segment_t1(segment).map_err(stt_err)? as f32 / 100.0,
+                    confidence: (segment_count > 0).then(|| segment_sum / segment_count as f32),
+                    speaker: None,
+                });
+            }
+
+            let language_detected = state
+                .full_lang_id_from_state()
+                .ok()
+                .and_then(whisper_rs::get_lang_str)
+                .map(str::to_string);
+
+            Ok(SttResult {
+                text: text.trim().to_string(),
+                confidence: (prob_count > 0).then(|| prob_sum / prob_count as f32),
+                language_detected,
+                segments,
+                ..Default::default()
+            })
+        }
+
+        #[cfg(not(feature = "whisper-stt"))]
+        {
+            let _ = (samples, stop);
+            Err(AppError::Stt(
+                "Whisper feature not enabled. Rebuild with --features whisper-stt".into(),
+            ))
+        }
+    }
+}
+
+/// Map the app's `stt_language` to a Whisper language code (`None` = auto-detect).
+#[cfg_attr(not(feature = "whisper-stt"), allow(dead_code))]
+fn language_hint(language: &str) -> Option<String> {
+    let lang = language.trim().to_lowercase();
+    let primary = lang.split(['-', '_']).next().unwrap_or_default();
+    match primary {
+        "" | "auto" => None,
+        other => Some(other.to_string()),
+    }
+}
+
+/// Initial prompt that nudges Whisper toward the expected script.
+///
+/// Whisper defaults to Simplified output for Mandarin; a Traditional
+/// prompt keeps zh-TW users from relying on OpenCC alone.
+#[cfg_attr(not(feature = "whisper-stt"), allow(dead_code))]
+fn initial_prompt(language: &str) -> Option<&'static str> {
+    match language.trim().to_lowercase().as_str() {
+        "zh" | "zh-tw" | "zh_tw" | "zh-hant" => Some("以下是繁體中文的逐字稿。"),
+        "zh-cn" | "zh_cn" | "zh-hans" => Some("以下是简体中文的逐字稿。"),
+        _ => None,
+    }
+}
+
+/// Script prompt followed by the glossary, which Whisper treats as prior context.
+#[cfg_attr(not(feature = "whisper-stt"), allow(dead_code))]
+fn build_prompt(language: &str, glossary: &[String]) -> Option<String> {
+    match (initial_prompt(language), glossary::prompt_hint(glossary)) {
+        (Some(script), Some(hint)) => Some(format!("{script}{hint}")),
+        (script, hint) => script.map(str::to_string).or(hint),
+    }
+}
+
+#[cfg_attr(not(feature = "whisper-stt"), allow(dead_code))]
+fn thread_count() -> i32 {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .min(MAX_THREADS) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_hint() {
+        assert_eq!(language_hint("zh-TW").as_deref(), Some("zh"));
+        assert_eq!(language_hint("en"), Some("en".to_string()));
+        assert_eq!(language_hint("ja_JP").as_deref(), Some("ja"));
+        assert_eq!(language_hint("auto"), None);
+        assert_eq!(language_hint(""), None);
+    }
+
+    #[test]
+    fn test_available_models_have_urls() {
+        for model in available_models() {
+            assert!(model.url.ends_with(&model.file_name));
+        }
+    }
+}
@@ -7,22 +7,84 @@ import {
   downloadVoskModel,
   loadVoskModel,
   getVoskStatus,
-  type VoskModel,
-  type VoskModelStatus,
+  listWhisperModels,
+  listDownloadedWhisperModels,
+  downloadWhisperModel,
+  loadWhisperModel,
+  getWhisperStatus,
+  listSherpaModels,
+  listDownloadedSherpaModels,
+  downloadSherpaModel,
+  loadSherpaModel,
+  getSherpaStatus,
   type DownloadProgress,
 } from "../lib/tauri";
 
-export function ModelManager() {
-  const [models, setModels] = useState<VoskModel[]>([]);
+type ModelEngine = "vosk" | "whisper" | "sherpa";
+
+interface ModelInfo {
+  id: string;
+  name: string;
+  size_mb: number;
+  description: string;
+}
+
+interface ModelStatus {
+  loaded: boolean;
+  modelId: string | null;
+  /** sherpa-onnx keeps a punctuation model loaded next to the recognizer. */
+  punctuationModelId?: string | null;
+}
+
+const ENGINES: Record<
+  ModelEngine,
+  {
+    title: string;
+    list: () => Promise<ModelInfo[]>;
+    listDownloaded: () => Promise<string[]>;
+    download: (modelId: string) => Promise<string>;
+    load: (modelId: string) => Promise<ModelStatus>;
+    status: () => Promise<ModelStatus>;
+  }
+> = {
+  vosk: {
+    title: "Vosk Models",
+    list: listVoskModels,
+    listDownloaded: listDownloadedVoskModels,
+    download: downloadVoskModel,
+    load: loadVoskModel,
+    status: getVoskStatus,
+  },
+  whisper: {
+    title: "Whisper Models",
+    list: listWhisperModels,
+    listDownloaded: listDownloadedWhisperModels,
+    download: downloadWhisperModel,
+    load: loadWhisperModel,
+    status: getWhisperStatus,
+  },
+  sherpa: {
+    title: "Paraformer / SenseVoice Models",
+    list: listSherpaModels,
+    listDownloaded: listDownloadedSherpaModels,
+    download: downloadSherpaModel,
+    load: loadSherpaModel,
+    status: getSherpaStatus,
+  },
+};
+
+export function ModelManager({ engine = "vosk" }: { engine?: ModelEngine }) {
+  const api = ENGINES[engine];
+  const [models, setModels] = useState<ModelInfo[]>([]);
   const [downloaded, setDownloaded] = useState<string[]>([]);
-  const [status, setStatus] = useState<VoskModelStatus | null>(null);
+  const [status, setStatus] = useState<ModelStatus | null>(null);
   const [downloading, setDownloading] = useState<string | null>(null);
   const [progress, setProgress] = useState<DownloadProgress | null>(null);
   const [error, setError] = useState<string | null>(null);
 
   useEffect(() => {
     loadData();
-  }, []);
+  }, [engine]);
 
   useEffect(() => {
     const unlisten = listen<DownloadProgress>("model:download-progress", (event) => {
@@ -35,14 +97,14 @@ export function ModelManager() {
 
   async function loadData() {
     try {
-      const [modelList, downloadedList, voskStatus] = await Promise.all([
-        listVoskModels(),
-        listDownloadedVoskModels(),
-        getVoskStatus(),
+      const [modelList, downloadedList, engineStatus] = await Promise.all([
+        api.list(),
+        api.listDownloaded(),
+        api.status(),
       ]);
       setModels(modelList);
       setDownloaded(downloadedList);
-      setStatus(voskStatus);
+      setStatus(engineStatus);
     } catch {
       // Models may not be available in dev mode
     }
@@ -53,7 +115,7 @@ export function ModelManager() {
     setError(null);
     setProgress(null);
     try {
-      await downloadVoskModel(modelId);
+      await api.download(modelId);
       setDownloaded((prev) => [...prev, modelId]);
     } catch (e) {
       setError(String(e));
@@ -66,7 +128,7 @@ export function ModelManager() {
   async function handleLoad(modelId: string) {
     setError(null);
     try {
-      const newStatus = await loadVoskModel(modelId);
+      const newStatus = await api.load(modelId);
       setStatus(newStatus);
     } catch (e) {
       setError(String(e));
@@ -77,7 +139,7 @@ export function ModelManager() {
 
   return (
     <div className="flex flex-col gap-3">
-      <span className="text-sm font-medium text-text-secondary">Vosk Models</span>
+      <span className="text-sm font-medium text-text-secondary">{api.title}</span>
 
       {error && (
         <div className="rounded-lg bg-error/10 px-3 py-2 text-xs text-error">{error}</div>
@@ -96,7 +158,9 @@ export function ModelManager() {
       <div className="flex flex-col gap-2">
         {models.map((model) => {
           const isDownloaded = downloaded.includes(model.id);
-          const isLoaded = status?.modelId === model.id && status?.loaded;
+          const isLoaded =
+            (status?.modelId === model.id && status?.loaded) ||
+            status?.punctuationModelId === model.id;
           const isDownloading = downloading === model.id;
 
           return (
@@ -0,0 +1,153 @@
+import { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { Input, Select, Toggle } from "./ui";
+import { useSettingsStore } from "../stores/useSettingsStore";
+import { validateSttProviderOptions, type SttProviderOptions } from "../lib/tauri";
+
+type Engine = SttProviderOptions["engine"];
+
+/** Engine whose options apply to `provider`, or `null` when it has none. */
+function optionsEngine(
+  provider: string,
+  protocol: string,
+  customProviders: { id: string; protocol?: string }[],
+): Engine | null {
+  if (provider === "elevenlabs") return "eleven_labs";
+  if (provider === "mistral") return "mistral";
+  if (provider === "openai" || provider === "openai_transcribe") return "open_ai_transcriptions";
+  if (provider === "custom_openai_compatible") {
+    return protocol === "chat_audio" ? null : "open_ai_transcriptions";
+  }
+  const custom = customProviders.find((p) => p.id === provider);
+  if (custom && (custom.protocol ?? "transcriptions") === "transcriptions") {
+    return "open_ai_transcriptions";
+  }
+  return null;
+}
+
+interface SttProviderOptionsEditorProps {
+  provider: string;
+}
+
+/** Diarization, timestamps and temperature for the selected cloud provider. */
+export function SttProviderOptionsEditor({ provider }: SttProviderOptionsEditorProps) {
+  const { t } = useTranslation();
+  const { sttProviderOptions, sttProtocol, customSttProviders, updateSettings } =
+    useSettingsStore();
+  const engine = optionsEngine(provider, sttProtocol, customSttProviders);
+  const stored = sttProviderOptions[provider];
+  const current: SttProviderOptions | null =
+    engine && stored?.engine === engine ? stored : engine ? { engine } : null;
+  const [error, setError] = useState<string | null>(null);
+  const [baseUrlDraft, setBaseUrlDraft] = useState(
+    current?.engine === "mistral" ? (current.baseUrl ?? "") : "",
+  );
+
+  useEffect(() => {
+    setError(null);
+    setBaseUrlDraft(stored?.engine === "mistral" ? (stored.baseUrl ?? "") : "");
+  }, [provider, stored]);
+
+  if (!current) return null;
+
+  const save = async (next: SttProviderOptions) => {
+    try {
+      await validateSttProviderOptions(provider, next);
+      setError(null);
+      updateSettings({ sttProviderOptions: { ...sttProviderOptions, [provider]: next } });
+    } catch (e) {
+      setError(String(e));
+    }
+  };
+
+  return (
+    <div className="flex flex-col gap-3">
+      <span className="text-sm font-medium text-text-secondary">
+        {t("settings.sttAdvancedOptions")}
+      </span>
+      {current.engine === "eleven_labs" && (
+        <>
+          <Toggle
+            label={t("settings.sttDiarize")}
+            description={t("settings.sttDiarizeDesc")}
+            checked={current.diarize ?? false}
+            onChange={(diarize) =>
+              void save({ ...current, diarize, numSpeakers: diarize ? current.numSpeakers : null })
+            }
+          />
+          <Input
+            type="number"
+            min={1}
+            max={32}
+            label={t("settings.sttNumSpeakers")}
+            placeholder={t("settings.sttNumSpeakersPlaceholder")}
+            disabled={!current.diarize}
+            value={current.numSpeakers ?? ""}
+            onChange={(e) => {
+              const n = e.target.value ? Math.floor(Number(e.target.value)) : null;
+              void save({ ...current, numSpeakers: n });
+            }}
+          />
+          <Select
+            label={t("settings.sttTagAudioEvents")}
+            options={[
+              { value: "default", label: t("settings.sttOptionProviderDefault") },
+              { value: "on", label: t("settings.sttOptionOn") },
+              { value: "off", label: t("settings.sttOptionOff") },
+            ]}
+            value={
+              current.tagAudioEvents == null ? "default" : current.tagAudioEvents ? "on" : "off"
+            }
+            onChange={(v) =>
+              void save({ ...current, tagAudioEvents: v === "default" ? null : v === "on" })
+            }
+          />
+        </>
+      )}
+      {current.engine === "mistral" && (
+        <>
+          <Select
+            label={t("settings.sttTimestampGranularity")}
+            options={[
+              { value: "", label: t("settings.sttOptionProviderDefault") },
+              { value: "segment", label: t("settings.sttTimestampSegment") },
+              { value: "word", label: t("settings.sttTimestampWord") },
+            ]}
+            value={current.timestampGranularity ?? ""}
+            onChange={(v) =>
+              void save({
+                ...current,
+                timestampGranularity: v === "" ? null : (v as "segment" | "word"),
+              })
+            }
+          />
+          <Input
+            label={t("settings.sttMistralBaseUrl")}
+            placeholder="https://api.mistral.ai/v1"
+            value={baseUrlDraft}
+            onChange={(e) => setBaseUrlDraft(e.target.value)}
+            onBlur={() => void save({ ...current, baseUrl: baseUrlDraft.trim() || null })}
+          />
+        </>
+      )}
+      {current.engine === "open_ai_transcriptions" && (
+        <Input
+          type="number"
+          min={0}
+          max={1}
+          step={0.1}
+          label={t("settings.sttTemperature")}
+          placeholder={t("settings.sttOptionProviderDefault")}
+          value={current.temperature ?? ""}
+          onChange={(e) =>
+            void save({
+              ...current,
+              temperature: e.target.value === "" ? null : Number(e.target.value),
+            })
+          }
+        />
+      )}
+      {error && <span className="text-xs text-error">{error}</span>}
+    </div>
+  );
+}
@@ -3,7 +3,7 @@ import { ProviderSelector } from "../../../components/ProviderSelector";
 import { ApiKeyInput } from "../../../components/ApiKeyInput";
 import { ModelManager } from "../../../components/ModelManager";
 import { useSettingsStore } from "../../../stores/useSettingsStore";
-import { DEFAULT_OPENROUTER_STT_MODEL, STT_PROVIDERS } from "../../../lib/constants";
+import { DEFAULT_OPENROUTER_STT_MODEL, LOCAL_STT_PROVIDERS, STT_PROVIDERS } from "../../../lib/constants";
 import { debugUiEvent } from "../../../lib/debug";
 
 const API_KEY_URLS: Record<string, string> = {
@@ -16,6 +16,7 @@ const API_KEY_URLS: Record<string, string> = {
 };
 
 const LANGUAGE_OPTIONS = [
+  { value: "auto", label: "Auto-detect" },
   { value: "en", label: "English" },
   { value: "zh", label: "Traditional Chinese (Taiwan)" },
   { value: "ja", label: "Japanese" },
@@ -47,8 +48,16 @@ const VOICE_MODEL_OPTIONS: Record<string, { value: string; label: string }[]> =
 };
 
 export function VoiceProviderStep() {
-  const { uiLanguage, sttProvider, sttLanguage, sttModel, sttBaseUrl, updateSettings } = useSettingsStore();
-  const isCloud = sttProvider !== "vosk";
+  const {
+    uiLanguage,
+    sttProvider,
+    sttLanguage,
+    sttModel,
+    sttBaseUrl,
+    customSttProviders,
+    updateSettings,
+  } = useSettingsStore();
+  const isCloud = !LOCAL_STT_PROVIDERS.includes(sttProvider);
   const modelOptions = VOICE_MODEL_OPTIONS[sttProvider];
   const hasModelSelector = Boolean(modelOptions);
   const matched = modelOptions?.some((o) => o.value === sttModel && o.value !== "custom");
@@ -66,7 +75,10 @@ export function VoiceProviderStep() {
       <Card padding="md" className="flex flex-col gap-4">
         <ProviderSelector
           label="Voice Provider"
-          options={[...STT_PROVIDERS]}
+          options={[
+          ...STT_PROVIDERS,
+          ...customSttProviders.map((p) => ({ value: p.id, label: p.label || p.id })),
+        ]}
         value={sttProvider}
         onChange={(v) => {
           void debugUiEvent("setup/voice_provider_change", { from: sttProvider, to: v });
@@ -82,6 +94,8 @@ export function VoiceProviderStep() {
       />
 
         {sttProvider === "vosk" && <ModelManager />}
+        {sttProvider === "whisper_local" && <ModelManager engine="whisper" />}
+        {sttProvider === "sherpa_local" && <ModelManager engine="sherpa" />}
 
         {isCloud && (
           <>
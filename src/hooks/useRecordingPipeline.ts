@@ -4,7 +4,14 @@ import { useSettingsStore } from "../stores/useSettingsStore";
 import { useRecordingStore } from "../stores/useRecordingStore";
 import { useToastStore } from "../stores/useToastStore";
 import type { RecordingResult } from "../lib/tauri";
-import { enhanceText, insertTextAtCursor, showPreviewWindow, syncSettings } from "../lib/tauri";
+import {
+  enhanceText,
+  finishProcessing,
+  insertTextAtCursor,
+  processingCancelled,
+  showPreviewWindow,
+  syncSettings,
+} from "../lib/tauri";
 import { buildSettingsConsistencySnapshot, debugUiEvent } from "../lib/debug";
 
 /**
@@ -21,17 +28,31 @@ export function useRecordingPipeline() {
   const sttProvider = useSettingsStore((s) => s.sttProvider);
   const sttModel = useSettingsStore((s) => s.sttModel);
   const sttBaseUrl = useSettingsStore((s) => s.sttBaseUrl);
+  const sttProtocol = useSettingsStore((s) => s.sttProtocol);
   const cloudTimeoutSecs = useSettingsStore((s) => s.cloudTimeoutSecs);
+  const stageDeadlines = useSettingsStore((s) => s.stageDeadlines);
+  const offlineInterimTranscript = useSettingsStore((s) => s.offlineInterimTranscript);
+  const voskMaxAlternatives = useSettingsStore((s) => s.voskMaxAlternatives);
+  const customSttProviders = useSettingsStore((s) => s.customSttProviders);
+  const sttProviderOptions = useSettingsStore((s) => s.sttProviderOptions);
+  const sttFallbackChain = useSettingsStore((s) => s.sttFallbackChain);
+  const sttRace = useSettingsStore((s) => s.sttRace);
+  const sttEscalation = useSettingsStore((s) => s.sttEscalation);
+  const sttRoutes = useSettingsStore((s) => s.sttRoutes);
+  const glossary = useSettingsStore((s) => s.glossary);
+  const network = useSettingsStore((s) => s.network);
   const debugLoggingEnabled = useSettingsStore((s) => s.debugLoggingEnabled);
   const enhancementEnabled = useSettingsStore((s) => s.enhancementEnabled);
   const enhancementProvider = useSettingsStore((s) => s.enhancementProvider);
   const enhancementModel = useSettingsStore((s) => s.enhancementModel);
   const enhancementBaseUrl = useSettingsStore((s) => s.enhancementBaseUrl);
   const uiLanguage = useSettingsStore((s) => s.uiLanguage);
-  const { setStatus, setFinalText, reset } = useRecordingStore();
+  const { setStatus, setPartialText, setFinalText, reset } = useRecordingStore();
   const addToast = useToastStore((s) => s.addToast);
 
-  const resolveEnhancementLanguage = (): string => {
+  const resolveEnhancementLanguage = (detected: string | null): string => {
+    // In auto mode the prompt follows the language that was actually spoken.
+    if (sttLanguage === "auto" && detected) return detected;
     if (uiLanguage === "zh-TW" || sttLanguage === "zh") return "zh-TW";
     if (uiLanguage === "zh-CN") return "zh-CN";
     return "en";
@@ -75,8 +96,20 @@ export function useRecordingPipeline() {
         sttProvider,
         sttModel,
         sttBaseUrl,
+        sttProtocol,
         cloudTimeoutSecs,
+        stageDeadlines,
         debugLoggingEnabled,
+        customSttProviders,
+        sttProviderOptions,
+        sttFallbackChain,
+        sttRace,
+        sttEscalation,
+        sttRoutes,
+        offlineInterimTranscript,
+        voskMaxAlternatives,
+        glossary,
+        network,
       })
         .then(() =>
           debugUiEvent("settings/sync_to_rust", {
@@ -86,20 +119,44 @@ export function useRecordingPipeline() {
             sttProvider,
             sttModel,
             sttBaseUrl,
+            sttProtocol,
             cloudTimeoutSecs,
+            stageDeadlines,
             debugLoggingEnabled,
+            customSttProviders: customSttProviders.map((p) => p.id),
+            sttProviderOptions: Object.keys(sttProviderOptions),
+            sttFallbackChain: sttFallbackChain.map((s) => s.provider),
+            sttRace: sttRace.provider || null,
+            sttEscalation: sttEscalation.provider || null,
+            sttRoutes: sttRoutes.map((r) => `${r.language}:${r.provider || "-"}`),
+            offlineInterimTranscript,
+            voskMaxAlternatives,
+            glossaryLists: Object.keys(glossary.lists),
+            proxy: network.proxyUrl !== null,
           }),
         )
         .catch(() => {});
   }, [
     cloudTimeoutSecs,
+    customSttProviders,
     debugLoggingEnabled,
     floatingWindowEnabled,
     floatingWindowPosition,
+    glossary,
+    network,
+    offlineInterimTranscript,
+    stageDeadlines,
     sttLanguage,
     sttBaseUrl,
+    sttEscalation,
+    sttFallbackChain,
     sttModel,
+    sttProtocol,
     sttProvider,
+    sttProviderOptions,
+    sttRace,
+    sttRoutes,
+    voskMaxAlternatives,
   ]);
 
   // Listen for recording:status events
@@ -115,6 +172,11 @@ export function useRecordingPipeline() {
           setStatus("processing");
         } else if (status === "done") {
           setStatus("idle");
+        } else if (status === "cancelled") {
+          setStatus("idle");
+        } else if (status === "queued") {
+          setStatus("idle");
+          addToast("目前離線，錄音已排入佇列，連線恢復後會自動轉錄。", "info");
         } else if (status === "error") {
           useRecordingStore.getState().setError(message ?? "Unknown error");
           const toastMessage = extractAiErrorToast(message);
@@ -129,11 +191,44 @@ export function useRecordingPipeline() {
     };
   }, [addToast, reset, setStatus]);
 
+  // Listen for offline-queue events (queued recordings transcribed after reconnecting)
+  useEffect(() => {
+    const transcribed = listen<{ text: string; provider: string }>(
+      "offline-queue:transcribed",
+      (event) => {
+        void debugUiEvent("offline-queue/transcribed", { provider: event.payload.provider });
+        addToast(`離線錄音已轉錄：${event.payload.text.slice(0, 80)}`, "success");
+      },
+    );
+    const failed = listen<{ message: string }>("offline-queue:failed", (event) => {
+      addToast(`離線錄音轉錄失敗：${event.payload.message.slice(0, 160)}`, "error");
+    });
+    return () => {
+      transcribed.then((fn) => fn());
+      failed.then((fn) => fn());
+    };
+  }, [addToast]);
+
+  // Listen for recording:partial events (live local transcript while recording)
+  useEffect(() => {
+    const unlisten = listen<{ text: string }>("recording:partial", (event) => {
+      setPartialText(event.payload.text);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [setPartialText]);
+
   // Listen for recording:result events (emitted after stop_recording succeeds)
   useEffect(() => {
     const unlisten = listen<RecordingResult>("recording:result", async (event) => {
-      const { text } = event.payload;
-      if (!text.trim()) return;
+      const { text, alternatives, language: detectedLanguage, enhancement: route } = event.payload;
+      // Ends the dictation, so a late cancel has nothing to cancel.
+      const finish = () => void finishProcessing().catch(() => undefined);
+      if (!text.trim()) {
+        finish();
+        return;
+      }
       void debugUiEvent(
         "recording/result",
         buildSettingsConsistencySnapshot({
@@ -149,25 +244,41 @@ export function useRecordingPipeline() {
       let outputText = text;
       setFinalText(text);
 
-      if (enhancementEnabled && outputText.trim()) {
+      // A language route may switch enhancement off or point it elsewhere.
+      const routed = route?.provider ? route : null;
+      const enhancement = {
+        enabled: route ? route.enabled : enhancementEnabled,
+        provider: routed?.provider ?? enhancementProvider,
+        model: routed ? (routed.model ?? "") : enhancementModel,
+        baseUrl: routed ? routed.baseUrl : enhancementBaseUrl,
+      };
+
+      if (enhancement.enabled && outputText.trim()) {
         try {
-          const language = resolveEnhancementLanguage();
+          const language = resolveEnhancementLanguage(detectedLanguage);
           void debugUiEvent("enhancement/run", {
-            provider: enhancementProvider,
-            model: enhancementModel,
+            provider: enhancement.provider,
+            model: enhancement.model,
             language,
+            routed: routed !== null,
           });
           outputText = await enhanceText(
             outputText,
-            enhancementProvider,
-            enhancementModel,
+            enhancement.provider,
+            enhancement.model,
             language,
-            enhancementBaseUrl,
+            enhancement.baseUrl,
           );
           useRecordingStore.getState().setEnhancedText(outputText);
         } catch (e) {
           const message = String(e);
           void debugUiEvent("enhancement/error", { message });
+          // Cancelled by the user: stop here instead of inserting the raw transcript.
+          if (await processingCancelled().catch(() => false)) {
+            finish();
+            setStatus("idle");
+            return;
+          }
           addToast("文字優化失敗，已使用原始轉錄結果。", "error");
         }
       }
@@ -175,10 +286,16 @@ export function useRecordingPipeline() {
       if (previewBeforeInsert) {
         setStatus("previewing");
         try {
-          await showPreviewWindow(outputText);
+          // Alternatives are n-best hypotheses of the raw transcript, so
+          // drop the one the transcript already is.
+          await showPreviewWindow(
+            outputText,
+            alternatives.map((alt) => alt.text).filter((alt) => alt.trim() !== text.trim()),
+          );
         } catch (e) {
           console.error("Failed to show preview:", e);
         }
+        finish();
       } else {
         setStatus("inserting");
         try {
@@ -189,6 +306,7 @@ export function useRecordingPipeline() {
         } catch (e) {
           console.error("Failed to insert text:", e);
         }
+        finish();
         setStatus("idle");
       }
     });
@@ -2,6 +2,8 @@ export const DEFAULT_OPENROUTER_STT_MODEL = "google/gemini-3-flash-preview";
 
 export const STT_PROVIDERS = [
   { value: "vosk", label: "Vosk (Local)" },
+  { value: "whisper_local", label: "Whisper (Local)" },
+  { value: "sherpa_local", label: "Paraformer / SenseVoice (Local)" },
   { value: "openrouter", label: "OpenRouter" },
   { value: "custom_openai_compatible", label: "Custom OpenAI-Compatible" },
   { value: "openai_transcribe", label: "OpenAI Transcribe" },
@@ -10,6 +12,8 @@ export const STT_PROVIDERS = [
   { value: "mistral", label: "Mistral" },
 ] as const;
 
+export const LOCAL_STT_PROVIDERS: readonly string[] = ["vosk", "whisper_local", "sherpa_local"];
+
 export const ENHANCEMENT_PROVIDERS = [
   { value: "openrouter", label: "OpenRouter" },
   { value: "custom_openai_compatible", label: "Custom OpenAI-Compatible" },
@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import { LOCAL_STT_PROVIDERS } from "./constants";
 
 type JsonValue = string | number | boolean | null | JsonValue[] | { [key: string]: JsonValue };
 
@@ -47,7 +48,7 @@ export function buildSettingsConsistencySnapshot(settings: {
   enhancementModel: string;
   previewBeforeInsert: boolean;
 }): JsonValue {
-  const sttLocal = settings.sttProvider === "vosk";
+  const sttLocal = LOCAL_STT_PROVIDERS.includes(settings.sttProvider);
   const enhancementLocal =
     settings.enhancementProvider === "ollama" || settings.enhancementProvider === "lmstudio";
 
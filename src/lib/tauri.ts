@@ -7,10 +7,35 @@ export interface AppInfo {
   version: string;
 }
 
+export interface SttWord {
+  word: string;
+  start: number;
+  end: number;
+  confidence: number | null;
+}
+
+/** Timed stretch of a transcript, in seconds. */
+export interface SttSegment {
+  text: string;
+  start: number;
+  end: number;
+  confidence: number | null;
+  /** Speaker label when the provider diarized the audio. */
+  speaker?: string;
+}
+
+export interface SttAlternative {
+  text: string;
+  confidence: number;
+}
+
 export interface SttResult {
   text: string;
   confidence: number | null;
   language_detected: string | null;
+  words: SttWord[];
+  alternatives: SttAlternative[];
+  segments: SttSegment[];
 }
 
 export type SttProvider =
@@ -20,15 +45,190 @@ export type SttProvider =
   | "openai_transcribe"
   | "openrouter"
   | "custom_openai_compatible"
-  | "mistral";
+  | "mistral"
+  | "whisper_local"
+  | "sherpa_local"
+  // Providers declared in `customSttProviders`
+  | (string & {});
+
+/** Wire protocol of an OpenAI-compatible endpoint: multipart `/audio/transcriptions` or chat audio. */
+export type SttProtocol = "transcriptions" | "chat_audio";
+
+/** Extra OpenAI-compatible STT provider declared in settings. */
+export interface CustomSttProvider {
+  id: string;
+  label?: string;
+  baseUrl: string;
+  model?: string;
+  protocol?: SttProtocol;
+  /** Keystore entry for the API key; defaults to `id`. */
+  keyName?: string;
+}
+
+/** Advanced request options for one STT provider, tagged with the engine they are for. */
+export type SttProviderOptions =
+  | {
+      engine: "eleven_labs";
+      /** Label speakers; turns come back as segments. */
+      diarize?: boolean;
+      /** Expected speakers (1–32); needs `diarize`. */
+      numSpeakers?: number | null;
+      /** Tag sounds like `(laughter)`; the API default when unset. */
+      tagAudioEvents?: boolean | null;
+    }
+  | {
+      engine: "mistral";
+      timestampGranularity?: "segment" | "word" | null;
+      /** Replaces `https://api.mistral.ai/v1`. */
+      baseUrl?: string | null;
+    }
+  | {
+      /** `/audio/transcriptions` endpoints (OpenAI Whisper, Groq, local servers). */
+      engine: "open_ai_transcriptions";
+      /** 0–1; lower is more literal. */
+      temperature?: number | null;
+    };
+
+/** Provider tried when the previous step of the STT chain fails. */
+export interface FallbackStep {
+  provider: SttProvider;
+  /** Overrides `cloudTimeoutSecs` for this step. */
+  timeoutSecs?: number;
+  model?: string;
+}
+
+/** Second provider transcribing the same audio; the first good answer wins. */
+export interface RaceConfig {
+  /** Empty turns racing off. */
+  provider: SttProvider | "";
+  model?: string;
+  timeoutSecs?: number;
+  /** Prefer the selected provider's answer for this long; 0 takes the first good answer. */
+  preferPrimaryMs: number;
+}
+
+/** Cloud provider asked again when a local transcript looks unreliable. */
+export interface EscalationConfig {
+  /** Empty turns escalation off. */
+  provider: SttProvider | "";
+  model?: string;
+  /** Escalate below this utterance confidence (0–1). */
+  minConfidence: number;
+  /** Escalate below this many words per second of speech; CJK characters count as words. */
+  minWordsPerSec: number;
+}
+
+/** Enhancement used for one spoken language instead of the global settings. */
+export interface EnhancementRoute {
+  /** `false` skips enhancement for this language. */
+  enabled: boolean;
+  /** Empty keeps the global provider, model and endpoint. */
+  provider?: string;
+  model?: string;
+  baseUrl?: string;
+}
+
+/** Provider, model and enhancement for one spoken language. */
+export interface LanguageRoute {
+  /** App language code (`en`, `zh-TW`, `ja`); a bare `zh` covers every variant. */
+  language: string;
+  /** Empty keeps the selected STT provider. */
+  provider?: SttProvider | "";
+  model?: string;
+  baseUrl?: string;
+  enhancement?: EnhancementRoute | null;
+}
+
+/** Vocabulary per language code; `"*"` applies to every language. */
+export interface Glossary {
+  lists: Record<string, string[]>;
+}
+
+/** Proxy, root CA and headers shared by every outgoing request. */
+export interface NetworkConfig {
+  /** http(s):// or socks5(h):// URL; null for a direct connection. */
+  proxyUrl: string | null;
+  noProxy: string | null;
+  /** PEM bundle trusted in addition to the system roots. */
+  caBundlePath: string | null;
+  userAgent: string | null;
+  /** Headers keyed by provider id, or "*" for every provider. */
+  extraHeaders: Record<string, Record<string, string>>;
+}
 
-export type RecordingStatus = "idle" | "recording" | "processing" | "error";
+export interface SttProviderDescriptor {
+  id: string;
+  label: string;
+  engine:
+    | "vosk"
+    | "whisper"
+    | "sherpa"
+    | "eleven_labs"
+    | "open_ai_transcriptions"
+    | "chat_audio"
+    | "mistral";
+  keyName: string | null;
+  defaultModel: string | null;
+  baseUrl: string | null;
+  capabilities: {
+    local: boolean;
+    livePartials: boolean;
+    customBaseUrl: boolean;
+    requiresBaseUrl: boolean;
+    /** Endpoint may use either protocol; see `sttProtocol`. */
+    selectableProtocol: boolean;
+  };
+  custom: boolean;
+}
+
+export type RecordingStatus =
+  | "idle"
+  | "recording"
+  | "processing"
+  | "queued"
+  | "cancelled"
+  | "error";
+
+/** Upper bound on each pipeline stage, in seconds. */
+export interface StageDeadlines {
+  transcriptionSecs: number;
+  enhancementSecs: number;
+  insertionSecs: number;
+}
 
 export interface RecordingResult {
   text: string;
   audioPath: string | null;
   textPath: string | null;
+  /** JSON sidecar with segment and word timings, when the engine reported them. */
+  transcriptPath: string | null;
   durationSecs: number;
+  confidence: number | null;
+  words: SttWord[];
+  segments: SttSegment[];
+  alternatives: SttAlternative[];
+  /** Provider that produced `text`; differs from the selected one after a failover. */
+  provider: string | null;
+  /** Utterance language; detected per recording when `sttLanguage` is "auto". */
+  language: string | null;
+  /** Provider was offline: the recording waits in the queue and `text` is the interim transcript. */
+  queued: boolean;
+  /** Enhancement override from the route for the spoken language. */
+  enhancement: EnhancementRoute | null;
+}
+
+/** A recording waiting for the network. */
+export interface PendingTranscription {
+  id: string;
+  audioPath: string;
+  textPath: string;
+  provider: string;
+  model: string | null;
+  baseUrl: string | null;
+  language: string;
+  queuedAt: string;
+  attempts: number;
+  interimText: string | null;
 }
 
 export interface ProviderHealth {
@@ -36,6 +236,8 @@ export interface ProviderHealth {
   hasKey: boolean;
   latencyMs: number | null;
   status: string;
+  /** What the engine heard in the voice probe clip; null for enhancement checks. */
+  transcript: string | null;
 }
 
 // --- Typed invoke wrappers ---
@@ -60,6 +262,49 @@ export async function getRecordingsDir(): Promise<string> {
   return invoke<string>("get_recordings_dir");
 }
 
+export interface SavedRecording {
+  audioPath: string;
+  /** Saved transcript; empty when the recording was not transcribed. */
+  text: string;
+  /** Whether word or segment timings were saved with it. */
+  timed: boolean;
+}
+
+/** Recordings in the output directory, newest first. */
+export async function listRecordings(outputDir?: string, limit?: number): Promise<SavedRecording[]> {
+  return invoke<SavedRecording[]>("list_recordings", {
+    outputDir: outputDir ?? null,
+    limit: limit ?? null,
+  });
+}
+
+/** Stop the transcription, enhancement or insertion in flight; `false` if nothing was running. */
+export async function cancelProcessing(): Promise<boolean> {
+  return invoke<boolean>("cancel_processing");
+}
+
+export async function finishProcessing(): Promise<void> {
+  return invoke("finish_processing");
+}
+
+/** Whether the dictation in flight was cancelled, as reported by the `cancelled` status. */
+export async function processingCancelled(): Promise<boolean> {
+  return invoke<boolean>("processing_cancelled");
+}
+
+export async function listPendingTranscriptions(): Promise<PendingTranscription[]> {
+  return invoke<PendingTranscription[]>("list_pending_transcriptions");
+}
+
+/** Retry queued recordings now; resolves to how many are still pending. */
+export async function retryPendingTranscriptions(): Promise<number> {
+  return invoke<number>("retry_pending_transcriptions");
+}
+
+export async function discardPendingTranscription(id: string): Promise<void> {
+  return invoke<void>("discard_pending_transcription", { id });
+}
+
 export async function saveApiKey(provider: string, key: string): Promise<void> {
   return invoke<void>("save_api_key", { provider, key });
 }
@@ -93,8 +338,20 @@ export async function syncSettings(settings: {
   sttProvider?: SttProvider;
   sttModel?: string;
   sttBaseUrl?: string;
+  sttProtocol?: SttProtocol | "auto";
   cloudTimeoutSecs?: number;
+  stageDeadlines?: StageDeadlines;
   debugLoggingEnabled?: boolean;
+  customSttProviders?: CustomSttProvider[];
+  sttProviderOptions?: Record<string, SttProviderOptions>;
+  sttFallbackChain?: FallbackStep[];
+  sttRace?: RaceConfig;
+  sttEscalation?: EscalationConfig;
+  sttRoutes?: LanguageRoute[];
+  offlineInterimTranscript?: boolean;
+  voskMaxAlternatives?: number;
+  glossary?: Glossary;
+  network?: NetworkConfig;
 }): Promise<void> {
   return invoke<void>("sync_settings", {
     widgetPosition: settings.widgetPosition ?? null,
@@ -103,8 +360,58 @@ export async function syncSettings(settings: {
     sttProvider: settings.sttProvider ?? null,
     sttModel: settings.sttModel ?? null,
     sttBaseUrl: settings.sttBaseUrl ?? null,
+    sttProtocol: settings.sttProtocol ?? null,
     cloudTimeoutSecs: settings.cloudTimeoutSecs ?? null,
+    stageDeadlines: settings.stageDeadlines ?? null,
     debugLoggingEnabled: settings.debugLoggingEnabled ?? null,
+    customSttProviders: settings.customSttProviders ?? null,
+    sttProviderOptions: settings.sttProviderOptions ?? null,
+    sttFallbackChain: settings.sttFallbackChain ?? null,
+    sttRace: settings.sttRace ?? null,
+    sttEscalation: settings.sttEscalation ?? null,
+    sttRoutes: settings.sttRoutes ?? null,
+    offlineInterimTranscript: settings.offlineInterimTranscript ?? null,
+    voskMaxAlternatives: settings.voskMaxAlternatives ?? null,
+    glossary: settings.glossary ?? null,
+    network: settings.network ?? null,
+  });
+}
+
+/** Read a glossary file; with `merge`, the current terms are kept. */
+export async function importGlossary(
+  path: string,
+  language?: string,
+  merge = true,
+): Promise<Glossary> {
+  return invoke<Glossary>("import_glossary", { path, language: language ?? null, merge });
+}
+
+/** Write the glossary as JSON; returns the file path. */
+export async function exportGlossary(path?: string): Promise<string> {
+  return invoke<string>("export_glossary", { path: path ?? null });
+}
+
+export type SubtitleFormat = "srt" | "vtt";
+
+export interface SubtitleOptions {
+  /** Line width in columns; CJK characters count as two. */
+  maxLineChars?: number;
+  maxLines?: number;
+  maxCueSecs?: number;
+}
+
+/** Write SRT/WebVTT subtitles for a saved recording; returns the file path. */
+export async function exportSubtitles(
+  audioPath: string,
+  format: SubtitleFormat,
+  options?: SubtitleOptions,
+  path?: string,
+): Promise<string> {
+  return invoke<string>("export_subtitles", {
+    audioPath,
+    format,
+    options: options ?? null,
+    path: path ?? null,
   });
 }
 
@@ -140,10 +447,15 @@ export interface VoskModel {
   description: string;
 }
 
+export type VoskMode = { kind: "free" } | { kind: "grammar"; name: string };
+
 export interface VoskModelStatus {
   loaded: boolean;
   modelId: string | null;
   modelPath: string | null;
+  mode: VoskMode;
+  grammars: string[];
+  maxAlternatives: number;
 }
 
 export interface DownloadProgress {
@@ -178,6 +490,136 @@ export async function listDownloadedVoskModels(): Promise<string[]> {
   return invoke<string[]>("list_downloaded_vosk_models");
 }
 
+export async function setVoskGrammar(name: string, phrases: string[]): Promise<VoskModelStatus> {
+  return invoke<VoskModelStatus>("set_vosk_grammar", { name, phrases });
+}
+
+export async function removeVoskGrammar(name: string): Promise<VoskModelStatus> {
+  return invoke<VoskModelStatus>("remove_vosk_grammar", { name });
+}
+
+export async function setVoskMode(mode: VoskMode): Promise<VoskModelStatus> {
+  return invoke<VoskModelStatus>("set_vosk_mode", { mode });
+}
+
+export async function setVoskMaxAlternatives(count: number): Promise<VoskModelStatus> {
+  return invoke<VoskModelStatus>("set_vosk_max_alternatives", { count });
+}
+
+// --- Whisper Model Management ---
+
+export interface WhisperModel {
+  id: string;
+  name: string;
+  file_name: string;
+  size_mb: number;
+  url: string;
+  description: string;
+}
+
+export interface WhisperModelStatus {
+  loaded: boolean;
+  modelId: string | null;
+  modelPath: string | null;
+}
+
+export async function listWhisperModels(): Promise<WhisperModel[]> {
+  return invoke<WhisperModel[]>("list_whisper_models");
+}
+
+export async function downloadWhisperModel(modelId: string): Promise<string> {
+  return invoke<string>("download_whisper_model", { modelId });
+}
+
+export async function loadWhisperModel(modelId: string): Promise<WhisperModelStatus> {
+  return invoke<WhisperModelStatus>("load_whisper_model", { modelId });
+}
+
+export async function unloadWhisperModel(): Promise<WhisperModelStatus> {
+  return invoke<WhisperModelStatus>("unload_whisper_model");
+}
+
+export async function getWhisperStatus(): Promise<WhisperModelStatus> {
+  return invoke<WhisperModelStatus>("get_whisper_status");
+}
+
+export async function listDownloadedWhisperModels(): Promise<string[]> {
+  return invoke<string[]>("list_downloaded_whisper_models");
+}
+
+// --- sherpa-onnx Model Management ---
+
+export type SherpaModelKind = "paraformer" | "senseVoice" | "punctuation";
+
+export interface SherpaModel {
+  id: string;
+  name: string;
+  kind: SherpaModelKind;
+  language: string;
+  size_mb: number;
+  url: string;
+  description: string;
+  model_file: string;
+  tokens_file: string | null;
+}
+
+export interface SherpaModelStatus {
+  loaded: boolean;
+  modelId: string | null;
+  modelPath: string | null;
+  kind: SherpaModelKind | null;
+  punctuationModelId: string | null;
+  punctuationEnabled: boolean;
+}
+
+export async function listSherpaModels(): Promise<SherpaModel[]> {
+  return invoke<SherpaModel[]>("list_sherpa_models");
+}
+
+export async function downloadSherpaModel(modelId: string): Promise<string> {
+  return invoke<string>("download_sherpa_model", { modelId });
+}
+
+export async function loadSherpaModel(modelId: string): Promise<SherpaModelStatus> {
+  return invoke<SherpaModelStatus>("load_sherpa_model", { modelId });
+}
+
+export async function unloadSherpaModel(): Promise<SherpaModelStatus> {
+  return invoke<SherpaModelStatus>("unload_sherpa_model");
+}
+
+export async function getSherpaStatus(): Promise<SherpaModelStatus> {
+  return invoke<SherpaModelStatus>("get_sherpa_status");
+}
+
+export async function listDownloadedSherpaModels(): Promise<string[]> {
+  return invoke<string[]>("list_downloaded_sherpa_models");
+}
+
+export async function setSherpaPunctuation(enabled: boolean): Promise<SherpaModelStatus> {
+  return invoke<SherpaModelStatus>("set_sherpa_punctuation", { enabled });
+}
+
+export async function listSttProviders(): Promise<SttProviderDescriptor[]> {
+  return invoke<SttProviderDescriptor[]>("list_stt_providers");
+}
+
+/** Rejects with a readable reason when `provider` cannot use `options`. */
+export async function validateSttProviderOptions(
+  provider: string,
+  options: SttProviderOptions,
+): Promise<void> {
+  return invoke<void>("validate_stt_provider_options", { provider, options });
+}
+
+/** Probe the custom endpoint for the protocol it speaks. */
+export async function detectSttProtocol(baseUrl?: string, model?: string): Promise<SttProtocol> {
+  return invoke<SttProtocol>("detect_stt_protocol", {
+    baseUrl: baseUrl || null,
+    model: model || null,
+  });
+}
+
 export async function transcribeAudio(
   audioData: number[],
   provider: SttProvider,
@@ -223,8 +665,8 @@ export async function hideFloatingWidget(): Promise<void> {
 
 // --- Preview Window ---
 
-export async function showPreviewWindow(text: string): Promise<void> {
-  return invoke<void>("show_preview_window", { text });
+export async function showPreviewWindow(text: string, alternatives?: string[]): Promise<void> {
+  return invoke<void>("show_preview_window", { text, alternatives: alternatives ?? null });
 }
 
 export async function getPreviewText(): Promise<string | null> {
@@ -108,6 +108,7 @@ export function EnhancementSection() {
         hasKey: false,
         latencyMs: null,
         status: String(e),
+        transcript: null,
       });
       if (isManual) {
         addToast(`文字增強連線測試失敗（${String(e)}）`, "error");
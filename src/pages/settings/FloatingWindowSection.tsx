@@ -1,4 +1,4 @@
-import { Toggle } from "../../components/ui";
+import { Input, Toggle } from "../../components/ui";
 import { ProviderSelector } from "../../components/ProviderSelector";
 import { useSettingsStore } from "../../stores/useSettingsStore";
 import { hideFloatingWidget } from "../../lib/tauri";
@@ -6,8 +6,13 @@ import { useTranslation } from "react-i18next";
 
 export function FloatingWindowSection() {
   const { t } = useTranslation();
-  const { floatingWindowEnabled, floatingWindowPosition, previewBeforeInsert, updateSettings } =
-    useSettingsStore();
+  const {
+    floatingWindowEnabled,
+    floatingWindowPosition,
+    previewBeforeInsert,
+    voskMaxAlternatives,
+    updateSettings,
+  } = useSettingsStore();
   const floatingPositions = [
     { value: "top-right", label: t("settings.floatingPositions.topRight") },
     { value: "bottom-right", label: t("settings.floatingPositions.bottomRight") },
@@ -50,6 +55,21 @@ export function FloatingWindowSection() {
         checked={previewBeforeInsert}
         onChange={(v) => updateSettings({ previewBeforeInsert: v })}
       />
+
+      {previewBeforeInsert && (
+        <Input
+          type="number"
+          min={0}
+          max={10}
+          label={t("settings.voskMaxAlternatives")}
+          hint={t("settings.voskMaxAlternativesDesc")}
+          value={voskMaxAlternatives}
+          onChange={(e) => {
+            const n = Math.floor(Number(e.target.value)) || 0;
+            updateSettings({ voskMaxAlternatives: Math.min(Math.max(n, 0), 10) });
+          }}
+        />
+      )}
     </section>
   );
 }
@@ -0,0 +1,101 @@
+import { useEffect, useState } from "react";
+import { Button, Card, Input, Select } from "../../components/ui";
+import { useSettingsStore } from "../../stores/useSettingsStore";
+import { useToastStore } from "../../stores/useToastStore";
+import { exportGlossary, importGlossary } from "../../lib/tauri";
+import { useTranslation } from "react-i18next";
+
+const ALL_LANGUAGES = "*";
+
+export function GlossarySection() {
+  const { t } = useTranslation();
+  const { glossary, updateSettings } = useSettingsStore();
+  const addToast = useToastStore((s) => s.addToast);
+  const [language, setLanguage] = useState(ALL_LANGUAGES);
+  const [draft, setDraft] = useState("");
+  const [filePath, setFilePath] = useState("");
+
+  const listOptions = [
+    { value: ALL_LANGUAGES, label: t("settings.glossaryAllLanguages") },
+    { value: "en", label: "English" },
+    { value: "zh", label: "中文" },
+    { value: "ja", label: "日本語" },
+    { value: "ko", label: "한국어" },
+  ];
+
+  useEffect(() => {
+    setDraft((glossary.lists[language] ?? []).join("\n"));
+  }, [glossary, language]);
+
+  const saveList = () => {
+    const terms = draft
+      .split("\n")
+      .map((term) => term.trim())
+      .filter(Boolean);
+    const lists = { ...glossary.lists, [language]: terms };
+    if (terms.length === 0) delete lists[language];
+    updateSettings({ glossary: { lists } });
+  };
+
+  const handleImport = async () => {
+    try {
+      const imported = await importGlossary(filePath, language, true);
+      updateSettings({ glossary: imported });
+      addToast(t("settings.glossaryImported"), "success");
+    } catch (e) {
+      addToast(String(e), "error");
+    }
+  };
+
+  const handleExport = async () => {
+    try {
+      const path = await exportGlossary(filePath || undefined);
+      addToast(t("settings.glossaryExported", { path }), "success");
+    } catch (e) {
+      addToast(String(e), "error");
+    }
+  };
+
+  return (
+    <section className="flex flex-col gap-4">
+      <h3 className="text-sm font-semibold text-text-primary flex items-center gap-2">
+        <span className="text-accent">●</span> {t("settings.glossary")}
+      </h3>
+
+      <Card padding="md" className="flex flex-col gap-3">
+        <span className="text-xs text-text-muted">{t("settings.glossaryDesc")}</span>
+        <Select
+          label={t("settings.glossaryLanguage")}
+          options={listOptions}
+          value={language}
+          onChange={setLanguage}
+        />
+        <textarea
+          value={draft}
+          onChange={(e) => setDraft(e.target.value)}
+          onBlur={saveList}
+          rows={5}
+          placeholder={t("settings.glossaryTermsPlaceholder")}
+          className="rounded-lg border border-border bg-bg-primary px-3 py-2 text-xs text-text-primary font-mono placeholder:text-text-muted focus:border-accent focus:outline-none"
+        />
+      </Card>
+
+      <Card padding="md" className="flex flex-col gap-3">
+        <Input
+          label={t("settings.glossaryFilePath")}
+          value={filePath}
+          onChange={(e) => setFilePath(e.target.value)}
+          placeholder="~/Documents/Voxlore/glossary.json"
+        />
+        <div className="flex items-center gap-2">
+          <Button size="sm" variant="secondary" disabled={!filePath} onClick={handleImport}>
+            {t("settings.glossaryImport")}
+          </Button>
+          <Button size="sm" variant="secondary" onClick={handleExport}>
+            {t("settings.glossaryExport")}
+          </Button>
+        </div>
+      </Card>
+    </section>
+  );
+}
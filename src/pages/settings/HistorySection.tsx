@@ -0,0 +1,91 @@
+import { useEffect, useState } from "react";
+import { Button, Card } from "../../components/ui";
+import { useSettingsStore } from "../../stores/useSettingsStore";
+import { useToastStore } from "../../stores/useToastStore";
+import {
+  exportSubtitles,
+  listRecordings,
+  type SavedRecording,
+  type SubtitleFormat,
+} from "../../lib/tauri";
+import { useTranslation } from "react-i18next";
+
+/** File name without directory, e.g. `recording_20260101_120000.wav`. */
+function fileName(path: string): string {
+  return path.split(/[\\/]/).pop() ?? path;
+}
+
+export function HistorySection() {
+  const { t } = useTranslation();
+  const outputDirectory = useSettingsStore((s) => s.outputDirectory);
+  const addToast = useToastStore((s) => s.addToast);
+  const [recordings, setRecordings] = useState<SavedRecording[]>([]);
+
+  const refresh = () => {
+    listRecordings(outputDirectory || undefined)
+      .then(setRecordings)
+      .catch(console.error);
+  };
+
+  useEffect(() => {
+    refresh();
+  }, [outputDirectory]);
+
+  const handleExport = async (recording: SavedRecording, format: SubtitleFormat) => {
+    try {
+      const path = await exportSubtitles(recording.audioPath, format);
+      addToast(t("settings.subtitlesExported", { path }), "success");
+    } catch (e) {
+      addToast(String(e), "error");
+    }
+  };
+
+  return (
+    <section className="flex flex-col gap-4">
+      <h3 className="text-sm font-semibold text-text-primary flex items-center gap-2">
+        <span className="text-accent">●</span> {t("settings.history")}
+      </h3>
+
+      <Card padding="md" className="flex flex-col gap-3">
+        <div className="flex items-center justify-between">
+          <span className="text-xs text-text-muted">{t("settings.historyDesc")}</span>
+          <Button size="sm" variant="ghost" onClick={refresh}>
+            {t("settings.historyRefresh")}
+          </Button>
+        </div>
+        {recordings.length === 0 && (
+          <span className="text-xs text-text-muted">{t("settings.historyEmpty")}</span>
+        )}
+        {recordings.map((recording) => (
+          <div key={recording.audioPath} className="flex items-center gap-2">
+            <div className="flex min-w-0 flex-1 flex-col">
+              <span className="text-xs text-text-primary font-mono truncate">
+                {fileName(recording.audioPath)}
+              </span>
+              <span className="text-xs text-text-muted truncate">
+                {recording.text.trim() || t("settings.historyNoText")}
+                {recording.text.trim() && !recording.timed && ` · ${t("settings.historyUntimed")}`}
+              </span>
+            </div>
+            <Button
+              size="sm"
+              variant="secondary"
+              disabled={!recording.text.trim()}
+              onClick={() => handleExport(recording, "srt")}
+            >
+              SRT
+            </Button>
+            <Button
+              size="sm"
+              variant="secondary"
+              disabled={!recording.text.trim()}
+              onClick={() => handleExport(recording, "vtt")}
+            >
+              VTT
+            </Button>
+          </div>
+        ))}
+      </Card>
+    </section>
+  );
+}
@@ -0,0 +1,91 @@
+import { useEffect, useState, type ChangeEvent } from "react";
+import { Card, Input } from "../../components/ui";
+import { useSettingsStore } from "../../stores/useSettingsStore";
+import { useToastStore } from "../../stores/useToastStore";
+import type { NetworkConfig } from "../../lib/tauri";
+import { useTranslation } from "react-i18next";
+
+function formatHeaders(headers: NetworkConfig["extraHeaders"]): string {
+  return Object.keys(headers).length === 0 ? "" : JSON.stringify(headers, null, 2);
+}
+
+export function NetworkSection() {
+  const { t } = useTranslation();
+  const { network, updateSettings } = useSettingsStore();
+  const addToast = useToastStore((s) => s.addToast);
+  const [draft, setDraft] = useState(network);
+  const [headersDraft, setHeadersDraft] = useState(formatHeaders(network.extraHeaders));
+
+  // Sync drafts when store changes externally
+  useEffect(() => {
+    setDraft(network);
+    setHeadersDraft(formatHeaders(network.extraHeaders));
+  }, [network]);
+
+  const save = () => updateSettings({ network: draft });
+
+  const saveHeaders = () => {
+    try {
+      const extraHeaders = headersDraft.trim() ? JSON.parse(headersDraft) : {};
+      updateSettings({ network: { ...network, extraHeaders } });
+    } catch {
+      addToast(t("settings.networkHeadersInvalid"), "error");
+    }
+  };
+
+  const field = (key: "proxyUrl" | "noProxy" | "caBundlePath" | "userAgent") => ({
+    value: draft[key] ?? "",
+    onChange: (e: ChangeEvent<HTMLInputElement>) =>
+      setDraft({ ...draft, [key]: e.target.value || null }),
+    onBlur: save,
+  });
+
+  return (
+    <section className="flex flex-col gap-4">
+      <h3 className="text-sm font-semibold text-text-primary flex items-center gap-2">
+        <span className="text-accent">●</span> {t("settings.network")}
+      </h3>
+
+      <Card padding="md" className="flex flex-col gap-3">
+        <span className="text-xs text-text-muted">{t("settings.networkDesc")}</span>
+        <Input
+          label={t("settings.networkProxyUrl")}
+          placeholder="socks5h://proxy.corp:1080"
+          {...field("proxyUrl")}
+        />
+        <Input
+          label={t("settings.networkNoProxy")}
+          placeholder="*.corp.example, 10.0.0.0/8"
+          {...field("noProxy")}
+        />
+        <Input
+          label={t("settings.networkCaBundle")}
+          placeholder="/etc/ssl/corp-root.pem"
+          {...field("caBundlePath")}
+        />
+        <Input
+          label={t("settings.networkUserAgent")}
+          placeholder={`Voxlore/${__APP_VERSION__}`}
+          {...field("userAgent")}
+        />
+      </Card>
+
+      <Card padding="md" className="flex flex-col gap-3">
+        <div className="flex flex-col gap-1">
+          <span className="text-sm text-text-primary">{t("settings.networkHeaders")}</span>
+          <span className="text-xs text-text-muted">{t("settings.networkHeadersDesc")}</span>
+        </div>
+        <textarea
+          value={headersDraft}
+          onChange={(e) => setHeadersDraft(e.target.value)}
+          onBlur={saveHeaders}
+          rows={4}
+          placeholder={'{ "*": { "X-Team": "speech" } }'}
+          className="rounded-lg border border-border bg-bg-primary px-3 py-2 text-xs text-text-primary font-mono placeholder:text-text-muted focus:border-accent focus:outline-none"
+        />
+      </Card>
+    </section>
+  );
+}
+
+declare const __APP_VERSION__: string;
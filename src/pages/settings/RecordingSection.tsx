@@ -1,14 +1,51 @@
 import { useEffect, useState } from "react";
 import { Card, Toggle } from "../../components/ui";
 import { useSettingsStore } from "../../stores/useSettingsStore";
-import { getRecordingsDir } from "../../lib/tauri";
+import { listen } from "@tauri-apps/api/event";
+import {
+  getRecordingsDir,
+  listPendingTranscriptions,
+  retryPendingTranscriptions,
+  type FallbackStep,
+  type LanguageRoute,
+} from "../../lib/tauri";
 import { useTranslation } from "react-i18next";
 
+const routeInput =
+  "rounded-lg border border-border bg-bg-primary px-2 py-1 text-xs text-text-primary font-mono placeholder:text-text-muted focus:border-accent focus:outline-none";
+
+/** "openai_transcribe:20, vosk" ⇄ fallback steps (optional per-step timeout in seconds). */
+function formatFallbackChain(chain: FallbackStep[]): string {
+  return chain
+    .map((s) => (s.timeoutSecs ? `${s.provider}:${s.timeoutSecs}` : s.provider))
+    .join(", ");
+}
+
+function parseFallbackChain(value: string): FallbackStep[] {
+  return value
+    .split(",")
+    .map((entry) => entry.trim())
+    .filter(Boolean)
+    .map((entry) => {
+      const [provider, timeout] = entry.split(":").map((s) => s.trim());
+      const n = Number(timeout);
+      return timeout && Number.isFinite(n)
+        ? { provider, timeoutSecs: Math.max(5, Math.min(180, Math.floor(n))) }
+        : { provider };
+    });
+}
+
 export function RecordingSection() {
   const { t } = useTranslation();
   const {
     outputDirectory,
     cloudTimeoutSecs,
+    stageDeadlines,
+    sttFallbackChain,
+    sttRace,
+    sttEscalation,
+    sttRoutes,
+    offlineInterimTranscript,
     debugLoggingEnabled,
     rightClickDevtools,
     updateSettings,
@@ -16,16 +53,69 @@ export function RecordingSection() {
   const [defaultDir, setDefaultDir] = useState("");
   const [draft, setDraft] = useState(outputDirectory);
   const isDirty = draft !== outputDirectory;
+  const [chainDraft, setChainDraft] = useState(formatFallbackChain(sttFallbackChain));
+  const [raceDraft, setRaceDraft] = useState(sttRace.provider);
+  const [escalationDraft, setEscalationDraft] = useState(sttEscalation.provider);
+  const [routesDraft, setRoutesDraft] = useState<LanguageRoute[]>(sttRoutes);
+  const [pendingCount, setPendingCount] = useState(0);
+  const [retrying, setRetrying] = useState(false);
 
   useEffect(() => {
     getRecordingsDir().then(setDefaultDir).catch(console.error);
   }, []);
 
+  useEffect(() => {
+    listPendingTranscriptions()
+      .then((items) => setPendingCount(items.length))
+      .catch(console.error);
+    const unlisten = listen<{ pending: number }>("offline-queue:changed", (event) => {
+      setPendingCount(event.payload.pending);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const handleRetryPending = async () => {
+    setRetrying(true);
+    try {
+      setPendingCount(await retryPendingTranscriptions());
+    } catch (e) {
+      console.error(e);
+    } finally {
+      setRetrying(false);
+    }
+  };
+
   // Sync draft when store changes externally
   useEffect(() => {
     setDraft(outputDirectory);
   }, [outputDirectory]);
 
+  useEffect(() => {
+    setChainDraft(formatFallbackChain(sttFallbackChain));
+  }, [sttFallbackChain]);
+
+  useEffect(() => {
+    setRaceDraft(sttRace.provider);
+  }, [sttRace.provider]);
+
+  useEffect(() => {
+    setEscalationDraft(sttEscalation.provider);
+  }, [sttEscalation.provider]);
+
+  useEffect(() => {
+    setRoutesDraft(sttRoutes);
+  }, [sttRoutes]);
+
+  const editRoute = (index: number, patch: Partial<LanguageRoute>) => {
+    setRoutesDraft((routes) => routes.map((r, i) => (i === index ? { ...r, ...patch } : r)));
+  };
+
+  const saveRoutes = (routes: LanguageRoute[]) => {
+    updateSettings({ sttRoutes: routes.filter((r) => r.language.trim()) });
+  };
+
   const displayPath = outputDirectory || defaultDir || "~/Documents/Voxlore/recordings";
 
   const handleSave = () => {
@@ -116,6 +206,286 @@ export function RecordingSection() {
         />
       </Card>
 
+      <Card padding="md" className="flex flex-col gap-3">
+        <div className="flex flex-col gap-1">
+          <span className="text-sm text-text-primary">{t("settings.stageDeadlines")}</span>
+          <span className="text-xs text-text-muted">{t("settings.stageDeadlinesDesc")}</span>
+        </div>
+        <div className="flex items-center gap-4">
+          {(
+            [
+              ["transcriptionSecs", "settings.stageDeadlineTranscription", 10, 600],
+              ["enhancementSecs", "settings.stageDeadlineEnhancement", 5, 180],
+              ["insertionSecs", "settings.stageDeadlineInsertion", 2, 60],
+            ] as const
+          ).map(([key, label, min, max]) => (
+            <label key={key} className="flex flex-col gap-1">
+              <span className="text-xs text-text-muted">{t(label)}</span>
+              <input
+                type="number"
+                min={min}
+                max={max}
+                value={stageDeadlines[key]}
+                onChange={(e) => {
+                  const n = Number(e.target.value || min);
+                  updateSettings({
+                    stageDeadlines: {
+                      ...stageDeadlines,
+                      [key]: Math.max(min, Math.min(max, Math.floor(n))),
+                    },
+                  });
+                }}
+                className="w-24 rounded-lg border border-border bg-bg-primary px-3 py-1.5 text-xs text-text-primary focus:border-accent focus:outline-none"
+              />
+            </label>
+          ))}
+        </div>
+      </Card>
+
+      <Card padding="md" className="flex flex-col gap-3">
+        <div className="flex flex-col gap-1">
+          <span className="text-sm text-text-primary">{t("settings.sttFallbackChain")}</span>
+          <span className="text-xs text-text-muted">{t("settings.sttFallbackChainDesc")}</span>
+        </div>
+        <input
+          type="text"
+          value={chainDraft}
+          onChange={(e) => setChainDraft(e.target.value)}
+          onBlur={() => updateSettings({ sttFallbackChain: parseFallbackChain(chainDraft) })}
+          placeholder="openai_transcribe:20, vosk"
+          className="rounded-lg border border-border bg-bg-primary px-3 py-1.5 text-xs text-text-primary font-mono placeholder:text-text-muted focus:border-accent focus:outline-none"
+        />
+      </Card>
+
+      <Card padding="md" className="flex flex-col gap-3">
+        <div className="flex flex-col gap-1">
+          <span className="text-sm text-text-primary">{t("settings.sttRace")}</span>
+          <span className="text-xs text-text-muted">{t("settings.sttRaceDesc")}</span>
+        </div>
+        <input
+          type="text"
+          value={raceDraft}
+          onChange={(e) => setRaceDraft(e.target.value)}
+          onBlur={() => updateSettings({ sttRace: { ...sttRace, provider: raceDraft.trim() } })}
+          placeholder="vosk"
+          className="rounded-lg border border-border bg-bg-primary px-3 py-1.5 text-xs text-text-primary font-mono placeholder:text-text-muted focus:border-accent focus:outline-none"
+        />
+        <div className="flex flex-col gap-1">
+          <span className="text-sm text-text-primary">{t("settings.sttRacePreferMs")}</span>
+          <span className="text-xs text-text-muted">{t("settings.sttRacePreferMsDesc")}</span>
+        </div>
+        <input
+          type="number"
+          min={0}
+          max={60000}
+          step={100}
+          value={sttRace.preferPrimaryMs}
+          disabled={!sttRace.provider}
+          onChange={(e) => {
+            const n = Number(e.target.value || 0);
+            updateSettings({
+              sttRace: { ...sttRace, preferPrimaryMs: Math.max(0, Math.min(60000, Math.floor(n))) },
+            });
+          }}
+          className="w-28 rounded-lg border border-border bg-bg-primary px-3 py-1.5 text-xs text-text-primary focus:border-accent focus:outline-none disabled:opacity-50"
+        />
+      </Card>
+
+      <Card padding="md" className="flex flex-col gap-3">
+        <div className="flex flex-col gap-1">
+          <span className="text-sm text-text-primary">{t("settings.sttEscalation")}</span>
+          <span className="text-xs text-text-muted">{t("settings.sttEscalationDesc")}</span>
+        </div>
+        <input
+          type="text"
+          value={escalationDraft}
+          onChange={(e) => setEscalationDraft(e.target.value)}
+          onBlur={() =>
+            updateSettings({ sttEscalation: { ...sttEscalation, provider: escalationDraft.trim() } })
+          }
+          placeholder="openai_transcribe"
+          className="rounded-lg border border-border bg-bg-primary px-3 py-1.5 text-xs text-text-primary font-mono placeholder:text-text-muted focus:border-accent focus:outline-none"
+        />
+        <div className="flex items-center gap-4">
+          <label className="flex flex-col gap-1">
+            <span className="text-xs text-text-muted">{t("settings.sttEscalationMinConfidence")}</span>
+            <input
+              type="number"
+              min={0}
+              max={1}
+              step={0.05}
+              value={sttEscalation.minConfidence}
+              disabled={!sttEscalation.provider}
+              onChange={(e) => {
+                const n = Number(e.target.value || 0);
+                updateSettings({
+                  sttEscalation: { ...sttEscalation, minConfidence: Math.max(0, Math.min(1, n)) },
+                });
+              }}
+              className="w-28 rounded-lg border border-border bg-bg-primary px-3 py-1.5 text-xs text-text-primary focus:border-accent focus:outline-none disabled:opacity-50"
+            />
+          </label>
+          <label className="flex flex-col gap-1">
+            <span className="text-xs text-text-muted">{t("settings.sttEscalationMinWordsPerSec")}</span>
+            <input
+              type="number"
+              min={0}
+              max={10}
+              step={0.1}
+              value={sttEscalation.minWordsPerSec}
+              disabled={!sttEscalation.provider}
+              onChange={(e) => {
+                const n = Number(e.target.value || 0);
+                updateSettings({
+                  sttEscalation: { ...sttEscalation, minWordsPerSec: Math.max(0, Math.min(10, n)) },
+                });
+              }}
+              className="w-28 rounded-lg border border-border bg-bg-primary px-3 py-1.5 text-xs text-text-primary focus:border-accent focus:outline-none disabled:opacity-50"
+            />
+          </label>
+        </div>
+      </Card>
+
+      <Card padding="md" className="flex flex-col gap-3">
+        <div className="flex flex-col gap-1">
+          <span className="text-sm text-text-primary">{t("settings.sttRoutes")}</span>
+          <span className="text-xs text-text-muted">{t("settings.sttRoutesDesc")}</span>
+        </div>
+        <div className="flex flex-col gap-2" onBlur={() => saveRoutes(routesDraft)}>
+          {routesDraft.map((route, index) => {
+            const mode = !route.enhancement ? "global" : route.enhancement.enabled ? "custom" : "off";
+            return (
+              <div key={index} className="flex flex-col gap-1.5 rounded-lg border border-border p-2">
+                <div className="flex items-center gap-2">
+                  <input
+                    type="text"
+                    value={route.language}
+                    onChange={(e) => editRoute(index, { language: e.target.value })}
+                    placeholder="zh-TW"
+                    className={`w-20 ${routeInput}`}
+                  />
+                  <input
+                    type="text"
+                    value={route.provider ?? ""}
+                    onChange={(e) => editRoute(index, { provider: e.target.value })}
+                    placeholder={t("settings.sttRouteProvider")}
+                    className={`w-36 ${routeInput}`}
+                  />
+                  <input
+                    type="text"
+                    value={route.model ?? ""}
+                    onChange={(e) => editRoute(index, { model: e.target.value })}
+                    placeholder={t("settings.sttRouteModel")}
+                    className={`min-w-0 flex-1 ${routeInput}`}
+                  />
+                  <input
+                    type="text"
+                    value={route.baseUrl ?? ""}
+                    onChange={(e) => editRoute(index, { baseUrl: e.target.value })}
+                    placeholder={t("settings.sttRouteBaseUrl")}
+                    className={`min-w-0 flex-1 ${routeInput}`}
+                  />
+                  <button
+                    type="button"
+                    onClick={() => saveRoutes(routesDraft.filter((_, i) => i !== index))}
+                    className="rounded-lg px-2 py-1 text-xs text-text-muted hover:text-text-primary transition-colors cursor-pointer"
+                    title={t("settings.sttRouteRemove")}
+                  >
+                    ✕
+                  </button>
+                </div>
+                <div className="flex items-center gap-2">
+                  <span className="text-xs text-text-muted">{t("settings.sttRouteEnhancement")}</span>
+                  <select
+                    value={mode}
+                    onChange={(e) => {
+                      const next = e.target.value;
+                      const enhancement =
+                        next === "global"
+                          ? null
+                          : { ...route.enhancement, enabled: next === "custom" };
+                      const routes = routesDraft.map((r, i) => (i === index ? { ...r, enhancement } : r));
+                      setRoutesDraft(routes);
+                      saveRoutes(routes);
+                    }}
+                    className="rounded-lg border border-border bg-bg-primary px-2 py-1 text-xs text-text-primary focus:border-accent focus:outline-none"
+                  >
+                    <option value="global">{t("settings.sttRouteEnhancementGlobal")}</option>
+                    <option value="off">{t("settings.sttRouteEnhancementOff")}</option>
+                    <option value="custom">{t("settings.sttRouteEnhancementCustom")}</option>
+                  </select>
+                  {mode === "custom" && route.enhancement && (
+                    <>
+                      <input
+                        type="text"
+                        value={route.enhancement.provider ?? ""}
+                        onChange={(e) =>
+                          editRoute(index, {
+                            enhancement: { ...route.enhancement!, provider: e.target.value },
+                          })
+                        }
+                        placeholder="openrouter"
+                        className={`w-28 ${routeInput}`}
+                      />
+                      <input
+                        type="text"
+                        value={route.enhancement.model ?? ""}
+                        onChange={(e) =>
+                          editRoute(index, {
+                            enhancement: { ...route.enhancement!, model: e.target.value },
+                          })
+                        }
+                        placeholder={t("settings.sttRouteModel")}
+                        className={`min-w-0 flex-1 ${routeInput}`}
+                      />
+                      <input
+                        type="text"
+                        value={route.enhancement.baseUrl ?? ""}
+                        onChange={(e) =>
+                          editRoute(index, {
+                            enhancement: { ...route.enhancement!, baseUrl: e.target.value },
+                          })
+                        }
+                        placeholder={t("settings.sttRouteBaseUrl")}
+                        className={`min-w-0 flex-1 ${routeInput}`}
+                      />
+                    </>
+                  )}
+                </div>
+              </div>
+            );
+          })}
+        </div>
+        <button
+          type="button"
+          onClick={() => setRoutesDraft([...routesDraft, { language: "", provider: "" }])}
+          className="self-start rounded-lg border border-border px-3 py-1.5 text-xs text-text-primary hover:border-accent transition-colors cursor-pointer"
+        >
+          {t("settings.sttRouteAdd")}
+        </button>
+      </Card>
+
+      <Card padding="md" className="flex flex-col gap-3">
+        <Toggle
+          label={t("settings.offlineInterimTranscript")}
+          description={t("settings.offlineInterimTranscriptDesc")}
+          checked={offlineInterimTranscript}
+          onChange={(v) => updateSettings({ offlineInterimTranscript: v })}
+        />
+        <div className="flex items-center justify-between">
+          <span className="text-xs text-text-muted">
+            {t("settings.offlineQueuePending", { count: pendingCount })}
+          </span>
+          <button
+            onClick={handleRetryPending}
+            disabled={pendingCount === 0 || retrying}
+            className="rounded-lg border border-border px-3 py-1.5 text-xs text-text-muted hover:text-text-primary hover:border-accent transition-colors disabled:opacity-50"
+          >
+            {t("settings.offlineQueueRetry")}
+          </button>
+        </div>
+      </Card>
+
       <Card padding="md" className="flex flex-col gap-3">
         <Toggle
           label={t("settings.debugLogs")}
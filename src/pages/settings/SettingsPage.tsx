@@ -3,6 +3,9 @@ import { EnhancementSection } from "./EnhancementSection";
 import { FloatingWindowSection } from "./FloatingWindowSection";
 import { ShortcutsSection } from "./ShortcutsSection";
 import { RecordingSection } from "./RecordingSection";
+import { HistorySection } from "./HistorySection";
+import { NetworkSection } from "./NetworkSection";
+import { GlossarySection } from "./GlossarySection";
 import { LanguageSection } from "./LanguageSection";
 import { useTranslation } from "react-i18next";
 
@@ -25,10 +28,13 @@ export function SettingsPage() {
       <div className="flex-1 overflow-y-auto px-6 py-4">
         <div className="flex flex-col gap-8">
           <VoiceProviderSection />
+          <GlossarySection />
           <EnhancementSection />
           <FloatingWindowSection />
           <ShortcutsSection />
           <RecordingSection />
+          <HistorySection />
+          <NetworkSection />
           <LanguageSection />
         </div>
 
@@ -3,10 +3,11 @@ import { Card, Select, Input, Button } from "../../components/ui";
 import { ProviderSelector } from "../../components/ProviderSelector";
 import { ApiKeyInput } from "../../components/ApiKeyInput";
 import { ModelManager } from "../../components/ModelManager";
+import { SttProviderOptionsEditor } from "../../components/SttProviderOptionsEditor";
 import { useSettingsStore } from "../../stores/useSettingsStore";
-import { DEFAULT_OPENROUTER_STT_MODEL, STT_PROVIDERS } from "../../lib/constants";
+import { DEFAULT_OPENROUTER_STT_MODEL, LOCAL_STT_PROVIDERS, STT_PROVIDERS } from "../../lib/constants";
 import { debugUiEvent } from "../../lib/debug";
-import { checkProviderHealth, type ProviderHealth } from "../../lib/tauri";
+import { checkProviderHealth, detectSttProtocol, type ProviderHealth } from "../../lib/tauri";
 import { useToastStore } from "../../stores/useToastStore";
 import { useTranslation } from "react-i18next";
 
@@ -40,9 +41,17 @@ const VOICE_MODEL_OPTIONS: Record<string, { value: string; label: string }[]> =
 
 export function VoiceProviderSection() {
   const { t } = useTranslation();
-  const { sttProvider, sttLanguage, sttModel, sttBaseUrl, updateSettings } = useSettingsStore();
+  const {
+    sttProvider,
+    sttLanguage,
+    sttModel,
+    sttBaseUrl,
+    sttProtocol,
+    customSttProviders,
+    updateSettings,
+  } = useSettingsStore();
   const addToast = useToastStore((s) => s.addToast);
-  const isCloud = sttProvider !== "vosk";
+  const isCloud = !LOCAL_STT_PROVIDERS.includes(sttProvider);
   const modelOptions = VOICE_MODEL_OPTIONS[sttProvider];
   const hasModelSelector = Boolean(modelOptions);
   const matched = modelOptions?.some((o) => o.value === sttModel && o.value !== "custom");
@@ -50,6 +59,23 @@ export function VoiceProviderSection() {
   const hasCustomEndpoint = sttBaseUrl.trim().length > 0;
   const [health, setHealth] = useState<ProviderHealth | null>(null);
   const [healthLoading, setHealthLoading] = useState(false);
+  const [detecting, setDetecting] = useState(false);
+
+  const handleDetectProtocol = async () => {
+    setDetecting(true);
+    try {
+      const protocol = await detectSttProtocol(sttBaseUrl, sttModel);
+      updateSettings({ sttProtocol: protocol });
+      addToast(
+        t("settings.sttProtocolDetected", { protocol: t(`settings.sttProtocolOptions.${protocol}`) }),
+        "success",
+      );
+    } catch (e) {
+      addToast(String(e), "error");
+    } finally {
+      setDetecting(false);
+    }
+  };
 
   const runHealthCheck = useCallback(async (isManual = false) => {
     if (isManual) {
@@ -77,6 +103,7 @@ export function VoiceProviderSection() {
         hasKey: false,
         latencyMs: null,
         status: String(e),
+        transcript: null,
       });
       if (isManual) {
         addToast(`語音連線測試失敗（${String(e)}）`, "error");
@@ -97,6 +124,7 @@ export function VoiceProviderSection() {
   }, [runHealthCheck]);
 
   const languageOptions = [
+    { value: "auto", label: t("settings.languageOptions.auto") },
     { value: "en", label: t("settings.languageOptions.en") },
     { value: "zh", label: t("settings.languageOptions.zh") },
     { value: "ja", label: t("settings.languageOptions.ja") },
@@ -148,7 +176,10 @@ export function VoiceProviderSection() {
 
       <ProviderSelector
         label={t("settings.provider")}
-        options={[...STT_PROVIDERS]}
+        options={[
+          ...STT_PROVIDERS,
+          ...customSttProviders.map((p) => ({ value: p.id, label: p.label || p.id })),
+        ]}
         value={sttProvider}
         onChange={(v) => {
           void debugUiEvent("voice/provider_change", { from: sttProvider, to: v });
@@ -169,6 +200,18 @@ export function VoiceProviderSection() {
         </Card>
       )}
 
+      {sttProvider === "whisper_local" && (
+        <Card padding="md">
+          <ModelManager engine="whisper" />
+        </Card>
+      )}
+
+      {sttProvider === "sherpa_local" && (
+        <Card padding="md">
+          <ModelManager engine="sherpa" />
+        </Card>
+      )}
+
       {isCloud && (
         <Card padding="md" className="flex flex-col gap-4">
           <ApiKeyInput provider={sttProvider} getKeyUrl={API_KEY_URLS[sttProvider]} />
@@ -217,6 +260,31 @@ export function VoiceProviderSection() {
               onChange={(e) => updateSettings({ sttBaseUrl: e.target.value })}
             />
           )}
+          {sttProvider === "custom_openai_compatible" && (
+            <div className="flex items-end gap-2">
+              <div className="flex-1">
+                <Select
+                  label={t("settings.sttProtocol")}
+                  options={[
+                    { value: "auto", label: t("settings.sttProtocolOptions.auto") },
+                    { value: "transcriptions", label: t("settings.sttProtocolOptions.transcriptions") },
+                    { value: "chat_audio", label: t("settings.sttProtocolOptions.chat_audio") },
+                  ]}
+                  value={sttProtocol}
+                  onChange={(v) => updateSettings({ sttProtocol: v as typeof sttProtocol })}
+                />
+              </div>
+              <Button
+                size="sm"
+                variant="secondary"
+                disabled={!hasCustomEndpoint || detecting}
+                onClick={handleDetectProtocol}
+              >
+                {detecting ? t("settings.sttProtocolDetecting") : t("settings.sttProtocolDetect")}
+              </Button>
+            </div>
+          )}
+          <SttProviderOptionsEditor provider={sttProvider} />
         </Card>
       )}
 
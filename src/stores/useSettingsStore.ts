@@ -2,23 +2,45 @@ import { create } from "zustand";
 import { persist, createJSONStorage } from "zustand/middleware";
 import { buildSettingsConsistencySnapshot, debugUiEvent } from "../lib/debug";
 import { DEFAULT_OPENROUTER_STT_MODEL } from "../lib/constants";
+import type {
+  CustomSttProvider,
+  EscalationConfig,
+  LanguageRoute,
+  FallbackStep,
+  Glossary,
+  NetworkConfig,
+  RaceConfig,
+  StageDeadlines,
+  SttProviderOptions,
+  SttProtocol,
+  SttProvider,
+} from "../lib/tauri";
 
 export interface SettingsState {
   // Theme
   themeMode: "dark" | "light";
 
   // Voice Provider
-  sttProvider:
-    | "vosk"
-    | "elevenlabs"
-    | "openai"
-    | "openai_transcribe"
-    | "openrouter"
-    | "custom_openai_compatible"
-    | "mistral";
+  sttProvider: SttProvider;
   sttModel: string;
   sttBaseUrl: string;
+  /** Protocol of the custom OpenAI-compatible endpoint; "auto" probes it. */
+  sttProtocol: SttProtocol | "auto";
   sttLanguage: string;
+  /** Extra OpenAI-compatible STT providers, selectable by `id`. */
+  customSttProviders: CustomSttProvider[];
+  /** Advanced request options (diarization, timestamps, temperature) by provider id. */
+  sttProviderOptions: Record<string, SttProviderOptions>;
+  /** Tried in order when the selected provider fails (e.g. openai_transcribe → vosk). */
+  sttFallbackChain: FallbackStep[];
+  /** Provider raced against the selected one for lower latency. */
+  sttRace: RaceConfig;
+  /** Re-transcribe unsure local results with a cloud provider. */
+  sttEscalation: EscalationConfig;
+  /** Provider, model and enhancement per spoken language. */
+  sttRoutes: LanguageRoute[];
+  /** Product names, people and identifiers fed to every STT engine. */
+  glossary: Glossary;
 
   // Enhancement
   enhancementEnabled: boolean;
@@ -30,6 +52,8 @@ export interface SettingsState {
   floatingWindowEnabled: boolean;
   floatingWindowPosition: "top-right" | "bottom-right" | "top-left" | "bottom-left";
   previewBeforeInsert: boolean;
+  /** Vosk n-best hypotheses offered as one-click swaps in the preview (0 disables). */
+  voskMaxAlternatives: number;
 
   // Shortcuts
   inputMode: "push-to-talk" | "toggle";
@@ -38,7 +62,13 @@ export interface SettingsState {
 
   // Recording output
   outputDirectory: string;
+  /** Proxy, custom root CA, user-agent and extra headers for all providers. */
+  network: NetworkConfig;
   cloudTimeoutSecs: number;
+  /** Limits for the whole transcription, enhancement and insertion stages. */
+  stageDeadlines: StageDeadlines;
+  /** Write an on-device transcript for recordings queued while offline. */
+  offlineInterimTranscript: boolean;
   debugLoggingEnabled: boolean;
   rightClickDevtools: boolean;
 
@@ -60,7 +90,15 @@ export const useSettingsStore = create<SettingsState>()(
       sttProvider: "openrouter",
       sttModel: DEFAULT_OPENROUTER_STT_MODEL,
       sttBaseUrl: "",
+      sttProtocol: "auto",
       sttLanguage: "en",
+      customSttProviders: [],
+      sttProviderOptions: {},
+      sttFallbackChain: [],
+      sttRace: { provider: "", preferPrimaryMs: 0 },
+      sttEscalation: { provider: "", minConfidence: 0.6, minWordsPerSec: 0.5 },
+      sttRoutes: [],
+      glossary: { lists: {} },
       enhancementEnabled: false,
       enhancementProvider: "openrouter",
       enhancementModel: "google/gemini-3-flash-preview",
@@ -68,11 +106,21 @@ export const useSettingsStore = create<SettingsState>()(
       floatingWindowEnabled: false,
       floatingWindowPosition: "bottom-right",
       previewBeforeInsert: false,
+      voskMaxAlternatives: 3,
       inputMode: "push-to-talk",
       pushToTalkShortcut: "Option+Space",
       toggleModeShortcut: "Option+Shift+Space",
       outputDirectory: "",
+      network: {
+        proxyUrl: null,
+        noProxy: null,
+        caBundlePath: null,
+        userAgent: null,
+        extraHeaders: {},
+      },
       cloudTimeoutSecs: 45,
+      stageDeadlines: { transcriptionSecs: 240, enhancementSecs: 30, insertionSecs: 10 },
+      offlineInterimTranscript: true,
       debugLoggingEnabled: true,
       rightClickDevtools: false,
       uiLanguage: "en",
@@ -1,6 +1,7 @@
 import { useState, useEffect, useRef } from "react";
 import { listen, emit } from "@tauri-apps/api/event";
 import { getCurrentWindow } from "@tauri-apps/api/window";
+import { cancelProcessing } from "../../lib/tauri";
 
 export function FloatingWidget() {
   const [status, setStatus] = useState<"recording" | "processing">("recording");
@@ -8,6 +9,7 @@ export function FloatingWidget() {
   const [progressDots, setProgressDots] = useState("");
   const [duration, setDuration] = useState(0);
   const [audioLevel, setAudioLevel] = useState(0);
+  const [partialText, setPartialText] = useState("");
   const intervalRef = useRef<ReturnType<typeof setInterval>>(undefined);
 
   // Timer
@@ -37,7 +39,12 @@ export function FloatingWidget() {
       if (newStatus === "recording") {
         setStatusMessage("Listening...");
       }
-      if (newStatus === "done" || newStatus === "error") {
+      if (
+        newStatus === "done" ||
+        newStatus === "error" ||
+        newStatus === "cancelled" ||
+        newStatus === "queued"
+      ) {
         getCurrentWindow().close().catch(() => {});
       }
     });
@@ -67,12 +74,27 @@ export function FloatingWidget() {
     };
   }, []);
 
+  // Live transcript from the local recognizer (Vosk only)
+  useEffect(() => {
+    const unlisten = listen<{ text: string }>("recording:partial", (event) => {
+      setPartialText(event.payload.text);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   const handleStop = async () => {
     if (intervalRef.current) clearInterval(intervalRef.current);
     await emit("recording:stop", {});
     getCurrentWindow().close().catch(() => {});
   };
 
+  const handleCancel = async () => {
+    await cancelProcessing().catch(() => false);
+    getCurrentWindow().close().catch(() => {});
+  };
+
   const formatTime = (seconds: number) => {
     const m = Math.floor(seconds / 60);
     const s = seconds % 60;
@@ -100,19 +122,29 @@ export function FloatingWidget() {
           <span className="text-xs font-medium text-white">
             {status === "recording" ? "Recording" : "Processing..."}
           </span>
-          <span className="truncate text-[10px] text-[#9ca3af]" title={statusMessage}>
-            {status === "recording" ? formatTime(duration) : processingSubtitle}
+          <span className="truncate text-[10px] text-[#9ca3af]" title={partialText || statusMessage}>
+            {status === "recording" ? partialText || formatTime(duration) : processingSubtitle}
           </span>
         </div>
 
-        {/* Stop button */}
-        <button
-          onClick={handleStop}
-          className="ml-1 flex h-6 w-6 items-center justify-center rounded-full bg-red-500/80 hover:bg-red-500 transition-colors cursor-pointer"
-          title="Stop recording (Option+Space)"
-        >
-          <div className="h-2 w-2 rounded-[1px] bg-white" />
-        </button>
+        {/* Stop button; cancels once processing */}
+        {status === "recording" ? (
+          <button
+            onClick={handleStop}
+            className="ml-1 flex h-6 w-6 items-center justify-center rounded-full bg-red-500/80 hover:bg-red-500 transition-colors cursor-pointer"
+            title="Stop recording (Option+Space)"
+          >
+            <div className="h-2 w-2 rounded-[1px] bg-white" />
+          </button>
+        ) : (
+          <button
+            onClick={handleCancel}
+            className="ml-1 flex h-6 w-6 items-center justify-center rounded-full bg-white/15 hover:bg-white/25 text-[11px] leading-none text-white transition-colors cursor-pointer"
+            title="Cancel processing"
+          >
+            ✕
+          </button>
+        )}
       </div>
     </div>
   );
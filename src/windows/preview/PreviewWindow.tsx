@@ -4,6 +4,7 @@ import { Button } from "../../components/ui";
 
 export function PreviewWindow() {
   const [text, setText] = useState("");
+  const [alternatives, setAlternatives] = useState<string[]>([]);
   const [applying, setApplying] = useState(false);
   const [targetBundleId, setTargetBundleId] = useState<string | null>(null);
 
@@ -12,6 +13,9 @@ export function PreviewWindow() {
     invoke<string | null>("get_preview_text").then((t) => {
       if (t) setText(t);
     });
+    invoke<string[]>("get_preview_alternatives").then((alts) => {
+      setAlternatives(alts);
+    });
     invoke<string | null>("get_preview_target_bundle_id").then((id) => {
       setTargetBundleId(id);
     });
@@ -50,6 +54,22 @@ export function PreviewWindow() {
         autoFocus
       />
 
+      {/* Alternative hypotheses — one click replaces the text */}
+      {alternatives.length > 0 && (
+        <div className="mt-2 flex flex-wrap gap-1.5">
+          {alternatives.map((alt) => (
+            <button
+              key={alt}
+              onClick={() => setText(alt)}
+              className="max-w-full truncate rounded-md border border-border px-2 py-1 text-xs text-text-muted hover:border-border-focus hover:text-text-primary"
+              title={alt}
+            >
+              {alt}
+            </button>
+          ))}
+        </div>
+      )}
+
       {/* Action buttons */}
       <div className="mt-3 flex items-center justify-between">
         <Button variant="ghost" size="sm" onClick={handleCancel}>